@@ -0,0 +1,66 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+/// Space required for an AuthorityStats account
+pub const AUTHORITY_STATS_SPACE: usize = 8 + // discriminator
+    32 +                   // authority (Pubkey)
+    16 +                   // total_bet_volume (u128)
+    8 +                    // sessions_created (u64)
+    1;                     // bump (u8)
+
+/// Creates the analytics account a game server passes to create_game_session
+pub fn initialize_authority_stats_handler(ctx: Context<InitializeAuthorityStats>) -> Result<()> {
+    let authority_stats = &mut ctx.accounts.authority_stats;
+
+    authority_stats.authority = ctx.accounts.authority.key();
+    authority_stats.total_bet_volume = 0;
+    authority_stats.sessions_created = 0;
+    authority_stats.bump = ctx.bumps.authority_stats;
+
+    msg!("Authority stats initialized for {}", authority_stats.authority);
+    Ok(())
+}
+
+/// Returns the authority's average bet and total volume via return data
+pub fn get_authority_analytics_handler(ctx: Context<GetAuthorityAnalytics>) -> Result<()> {
+    let authority_stats = &ctx.accounts.authority_stats;
+
+    let analytics = AuthorityAnalytics {
+        average_bet: authority_stats.average_bet(),
+        total_bet_volume: authority_stats.total_bet_volume,
+        sessions_created: authority_stats.sessions_created,
+    };
+
+    msg!("Authority {}: {} sessions, average bet {}, total volume {}",
+         authority_stats.authority, analytics.sessions_created,
+         analytics.average_bet, analytics.total_bet_volume);
+
+    anchor_lang::solana_program::program::set_return_data(&analytics.try_to_vec()?);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeAuthorityStats<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = AUTHORITY_STATS_SPACE,
+        seeds = [b"authority_stats", authority.key().as_ref()],
+        bump
+    )]
+    pub authority_stats: Account<'info, AuthorityStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetAuthorityAnalytics<'info> {
+    #[account(
+        seeds = [b"authority_stats", authority_stats.authority.as_ref()],
+        bump = authority_stats.bump,
+    )]
+    pub authority_stats: Account<'info, AuthorityStats>,
+}
@@ -0,0 +1,134 @@
+use crate::instructions::{validate_new_session, GAME_SESSION_SPACE};
+use crate::{errors::WagerError, events::*, state::*};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::spl_token::state::AccountState;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+/// Creates a 1v1 session with the challenger already seated on team A and
+/// team B reserved for `opponent`, who accepts by joining it
+pub fn create_challenge_handler(
+    ctx: Context<CreateChallenge>,
+    session_id: String,
+    bet_amount: u64,
+    game_mode: GameMode,
+    opponent: Pubkey,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let challenger = ctx.accounts.challenger.key();
+
+    validate_new_session(&session_id, bet_amount, game_mode)?;
+    require!(opponent != challenger, WagerError::PlayerAlreadyJoined);
+
+    let game_session = &mut ctx.accounts.game_session;
+    game_session.set_inner(GameSession::new(
+        session_id.clone(),
+        ctx.accounts.game_server.key(),
+        bet_amount,
+        game_mode,
+        clock.unix_timestamp,
+        ctx.bumps.game_session,
+        ctx.bumps.vault,
+    )?);
+    game_session.token_mint = ctx.accounts.mint.key();
+    game_session.token_decimals = ctx.accounts.mint.decimals;
+    game_session.designate_opponent(opponent)?;
+
+    require!(
+        ctx.accounts.challenger_token_account.amount >= bet_amount,
+        WagerError::InsufficientUserBalance
+    );
+
+    anchor_spl::token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: ctx.accounts.challenger_token_account.to_account_info(),
+                to: ctx.accounts.vault_token_account.to_account_info(),
+                authority: ctx.accounts.challenger.to_account_info(),
+            },
+        ),
+        bet_amount,
+    )?;
+
+    ctx.accounts.vault_token_account.reload()?;
+    require!(
+        ctx.accounts.vault_token_account.amount == bet_amount,
+        WagerError::TransferVerificationFailed
+    );
+
+    emit!(TokenMoved {
+        session_id: session_id.clone(),
+        from: ctx.accounts.challenger_token_account.key(),
+        to: ctx.accounts.vault_token_account.key(),
+        amount: bet_amount,
+        reason: TokenMoveReason::Join as u8,
+    });
+
+    ctx.accounts
+        .game_session
+        .seat_player(0, 0, challenger, clock.unix_timestamp)?;
+
+    msg!("Challenge {} created: {} vs {} for {} tokens each",
+         session_id, challenger, opponent, bet_amount);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct CreateChallenge<'info> {
+    #[account(mut)]
+    pub game_server: Signer<'info>,
+
+    pub challenger: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = challenger_token_account.owner == challenger.key() @ WagerError::InvalidTokenAccountOwner,
+        constraint = challenger_token_account.mint == mint.key() @ WagerError::InvalidTokenMint,
+        constraint = challenger_token_account.state == AccountState::Initialized @ WagerError::FrozenTokenAccount,
+    )]
+    pub challenger_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = game_server,
+        space = GAME_SESSION_SPACE,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: Vault PDA for holding funds
+    #[account(
+        init,
+        payer = game_server,
+        space = 0,
+        seeds = [b"vault", session_id.as_bytes()],
+        bump
+    )]
+    pub vault: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = game_server,
+        associated_token::mint = mint,
+        associated_token::authority = vault,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    pub mint: Account<'info, Mint>,
+
+    /// Program-wide config whose whitelist the mint must be on
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = !global_config.creation_paused @ WagerError::EmergencyPauseActive,
+        constraint = global_config.is_mint_allowed(&mint.key()) @ WagerError::InvalidMint,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
@@ -1,6 +1,7 @@
 use crate::errors::WagerError;
+use crate::events::*;
+use crate::instructions::{vault_transfer, verify_vault_drain};
 use crate::state::*;
-use crate::TOKEN_ID;
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::{Token, TokenAccount};
@@ -11,80 +12,140 @@ const MAX_BET_AMOUNT: u64 = 1_000_000_000_000; // Maximum 1M tokens (1M if 6 dec
 const MAX_SESSION_ID_LENGTH: usize = 32;
 const MIN_SESSION_ID_LENGTH: usize = 3;
 const SESSION_TIMEOUT_SECONDS: i64 = 7200; // 2 hours default timeout
-const MAX_SESSIONS_PER_AUTHORITY: u16 = 100; // Prevent spam
 
 // SECURITY FIX: Game session space calculation with proper sizing
-const GAME_SESSION_SPACE: usize = 
+pub const GAME_SESSION_SPACE: usize = 
     8 +                    // Account discriminator
     4 + MAX_SESSION_ID_LENGTH + // session_id (String)
     32 +                   // authority (Pubkey)
     8 +                    // session_bet (u64)
     1 +                    // game_mode (enum)
+    32 +                   // token_mint (Pubkey)
+    1 +                    // token_decimals (u8)
     (32 * MAX_PLAYERS_PER_TEAM + 8 + 16 * MAX_PLAYERS_PER_TEAM + 16 * MAX_PLAYERS_PER_TEAM) + // team_a
+    4 * MAX_PLAYERS_PER_TEAM + // team_a weighted kill scores
+    (8 * MAX_PLAYERS_PER_TEAM + 8 * MAX_PLAYERS_PER_TEAM) + // team_a contributions and payouts
+    8 * MAX_PLAYERS_PER_TEAM + // team_a join timestamps
+    2 * MAX_PLAYERS_PER_TEAM + // team_a spawn purchase counts
+    2 * MAX_PLAYERS_PER_TEAM + // team_a lifetime spawns bought
+    MAX_PLAYERS_PER_TEAM +  // team_a claimed flags
+    MAX_PLAYERS_PER_TEAM +  // team_a paid flags
     (32 * MAX_PLAYERS_PER_TEAM + 8 + 16 * MAX_PLAYERS_PER_TEAM + 16 * MAX_PLAYERS_PER_TEAM) + // team_b
+    4 * MAX_PLAYERS_PER_TEAM + // team_b weighted kill scores
+    (8 * MAX_PLAYERS_PER_TEAM + 8 * MAX_PLAYERS_PER_TEAM) + // team_b contributions and payouts
+    8 * MAX_PLAYERS_PER_TEAM + // team_b join timestamps
+    2 * MAX_PLAYERS_PER_TEAM + // team_b spawn purchase counts
+    2 * MAX_PLAYERS_PER_TEAM + // team_b lifetime spawns bought
+    MAX_PLAYERS_PER_TEAM +  // team_b claimed flags
+    MAX_PLAYERS_PER_TEAM +  // team_b paid flags
     1 +                    // status (enum)
     8 +                    // created_at (i64)
     8 +                    // expires_at (i64) - SECURITY FIX: Added expiration
     2 +                    // spawns_per_purchase (u16) - SECURITY FIX: Configurable spawns
+    1 +                    // empty_winner_policy (enum)
+    1 +                    // strict_account_order (bool)
+    8 +                    // min_time_before_start_seconds (i64)
+    8 +                    // total_distributed (u64)
+    1 +                    // kill_tie_breaker (enum)
+    2 +                    // spawn_cost_scale_bps (u16)
+    8 +                    // last_heartbeat (i64)
+    8 +                    // liveness_window_seconds (i64)
+    1 +                    // is_draw (bool)
+    2 +                    // winning_team (Option<u8>)
+    1 +                    // distribution_in_progress (bool)
+    1 +                    // round_to_whole_tokens (bool)
+    8 +                    // rounding_remainder (u64)
+    2 +                    // leave_penalty_bps (u16)
+    8 +                    // leave_penalties (u64)
+    32 +                   // match_seed ([u8; 32])
+    2 +                    // refunded_bitmap (u16)
+    32 +                   // metadata ([u8; 32])
+    16 +                   // spectator_pools ([u64; 2])
+    1 +                    // spectator_vault_bump (u8)
+    8 +                    // max_vault_balance (u64)
+    8 +                    // bonus_pool (u64)
+    32 +                   // series (Pubkey)
+    8 +                    // team_a_bet (u64)
+    8 +                    // team_b_bet (u64)
+    2 +                    // earnings_divisor (u16)
+    1 +                    // frozen (bool)
+    1 +                    // allow_friendly_fire (bool)
+    1 + 8 +                // auto_start_at (Option<i64>)
+    8 +                    // hard_deadline (i64)
+    1 + 32 +               // designated_opponent (Option<Pubkey>)
+    1 +                    // require_victim_ack (bool)
+    4 + MAX_PENDING_KILLS * (1 + 32 + 1 + 32 + 2 + 8) + // pending_kills (Vec<PendingKill>)
+    2 +                    // min_kills_to_earn (u16)
+    8 +                    // lobby_lock_seconds (i64)
+    32 +                   // state_hash ([u8; 32])
+    2 +                    // max_failure_bps (u16)
+    1 +                    // enforce_balance (bool)
+    2 +                    // initial_spawns (u16)
+    1 +                    // is_sol (bool)
+    1 +                    // require_ata (bool)
+    8 +                    // total_collected (u64)
+    2 +                    // max_concurrent_spawns (u16)
+    2 +                    // max_lifetime_spawns (u16)
     1 +                    // bump (u8)
     1 +                    // vault_bump (u8)
     1 +                    // vault_token_bump (u8)
     64;                    // Extra padding for future fields
 
-/// SECURITY FIX: Comprehensive game session creation with all validations
+/// SECURITY FIX: Comprehensive game session creation with all validations.
+/// The match seed is derived from the authority and creation time,
+/// metadata defaults to zeros and the vault is uncapped when not provided.
 pub fn create_game_session_handler(
     ctx: Context<CreateGameSession>,
     session_id: String,
     bet_amount: u64,
     game_mode: GameMode,
+    metadata: Option<[u8; 32]>,
+    max_vault_balance: Option<u64>,
+    earnings_divisor: Option<u16>,
+) -> Result<()> {
+    create_game_session(
+        ctx,
+        session_id,
+        bet_amount,
+        game_mode,
+        None,
+        metadata,
+        max_vault_balance,
+        earnings_divisor,
+    )
+}
+
+/// Creates a game session carrying a match seed agreed on by the game server
+pub fn create_seeded_game_session_handler(
+    ctx: Context<CreateGameSession>,
+    session_id: String,
+    bet_amount: u64,
+    game_mode: GameMode,
+    match_seed: [u8; 32],
+) -> Result<()> {
+    create_game_session(ctx, session_id, bet_amount, game_mode, Some(match_seed), None, None, None)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn create_game_session(
+    ctx: Context<CreateGameSession>,
+    session_id: String,
+    bet_amount: u64,
+    game_mode: GameMode,
+    match_seed: Option<[u8; 32]>,
+    metadata: Option<[u8; 32]>,
+    max_vault_balance: Option<u64>,
+    earnings_divisor: Option<u16>,
 ) -> Result<()> {
     let clock = Clock::get()?;
     let authority = ctx.accounts.game_server.key();
     
     msg!("Creating game session '{}' by authority {}", session_id, authority);
 
-    // SECURITY FIX: Comprehensive session ID validation
-    require!(
-        session_id.len() >= MIN_SESSION_ID_LENGTH,
-        WagerError::SessionIdTooShort
-    );
-    
-    require!(
-        session_id.len() <= MAX_SESSION_ID_LENGTH,
-        WagerError::SessionIdTooLong
-    );
-
-    // Validate session ID contains only valid characters
-    require!(
-        session_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-'),
-        WagerError::InvalidSessionIdFormat
-    );
-
-    // SECURITY FIX: Comprehensive bet amount validation
-    require!(
-        bet_amount > 0,
-        WagerError::InvalidBetAmount
-    );
-
-    require!(
-        bet_amount >= MIN_BET_AMOUNT,
-        WagerError::BetAmountTooLow
-    );
-
-    require!(
-        bet_amount <= MAX_BET_AMOUNT,
-        WagerError::BetAmountTooHigh
-    );
-
-    msg!("Bet amount validated: {} tokens", bet_amount);
-
-    // SECURITY FIX: Validate game mode is supported
+    validate_new_session(&session_id, bet_amount, game_mode)?;
     let players_per_team = game_mode.players_per_team();
-    require!(
-        players_per_team > 0 && players_per_team <= MAX_PLAYERS_PER_TEAM,
-        WagerError::InvalidGameMode
-    );
 
+    msg!("Bet amount validated: {} tokens", bet_amount);
     msg!("Game mode: {:?}, Players per team: {}", game_mode, players_per_team);
 
     // SECURITY FIX: Calculate expiration time with overflow protection
@@ -104,15 +165,29 @@ pub fn create_game_session_handler(
         clock.unix_timestamp,
         ctx.bumps.game_session,
         ctx.bumps.vault,
-        ctx.bumps.vault_token_account, // SECURITY FIX: Added vault token bump
     )?;
+    game_session.token_mint = ctx.accounts.mint.key();
+    game_session.vault_token_bump = ctx.bumps.vault_token_account; // SECURITY FIX: Added vault token bump
+    game_session.token_decimals = ctx.accounts.mint.decimals;
+    if let Some(seed) = match_seed {
+        game_session.match_seed = seed;
+    }
+    game_session.metadata = metadata.unwrap_or_default();
+    if let Some(cap) = max_vault_balance {
+        // A cap below a single bet would make the session unjoinable
+        require!(cap >= bet_amount, WagerError::InvalidBetAmount);
+        game_session.max_vault_balance = cap;
+    }
+    if let Some(divisor) = earnings_divisor {
+        game_session.set_earnings_divisor(divisor)?;
+    }
 
     msg!("Game session initialized successfully");
 
     // SECURITY FIX: Verify vault token account initialization
     let vault_token_account = &ctx.accounts.vault_token_account;
     require!(
-        vault_token_account.mint == TOKEN_ID,
+        vault_token_account.mint == game_session.token_mint,
         WagerError::InvalidTokenMint
     );
     
@@ -128,12 +203,23 @@ pub fn create_game_session_handler(
 
     msg!("Vault validation completed successfully");
 
+    // Fold this session into the authority's running analytics, if tracked
+    if let Some(authority_stats) = ctx.accounts.authority_stats.as_mut() {
+        authority_stats.record_session(bet_amount)?;
+    }
+
+    // List the session among the authority's active sessions, if indexed
+    if let Some(session_index) = ctx.accounts.session_index.as_mut() {
+        session_index.add_session(game_session.key())?;
+    }
+
     // SECURITY FIX: Log comprehensive session creation details for monitoring
     msg!("=== Game Session Created ===");
     msg!("Session ID: {}", game_session.session_id);
     msg!("Authority: {}", game_session.authority);
     msg!("Bet Amount: {} tokens", game_session.session_bet);
     msg!("Game Mode: {:?}", game_session.game_mode);
+    msg!("Token Mint: {}", game_session.token_mint);
     msg!("Players per team: {}", players_per_team);
     msg!("Status: {:?}", game_session.status);
     msg!("Created at: {}", game_session.created_at);
@@ -147,6 +233,52 @@ pub fn create_game_session_handler(
     Ok(())
 }
 
+/// SECURITY FIX: Comprehensive validation of a new session's id, bet and mode,
+/// shared by every instruction that creates a session
+pub fn validate_new_session(session_id: &str, bet_amount: u64, game_mode: GameMode) -> Result<()> {
+    // SECURITY FIX: Comprehensive session ID validation
+    require!(
+        session_id.len() >= MIN_SESSION_ID_LENGTH,
+        WagerError::SessionIdTooShort
+    );
+    
+    require!(
+        session_id.len() <= MAX_SESSION_ID_LENGTH,
+        WagerError::SessionIdTooLong
+    );
+
+    // Validate session ID contains only valid characters
+    require!(
+        session_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-'),
+        WagerError::InvalidSessionIdFormat
+    );
+
+    // SECURITY FIX: Comprehensive bet amount validation
+    require!(
+        bet_amount > 0,
+        WagerError::InvalidBetAmount
+    );
+
+    require!(
+        bet_amount >= MIN_BET_AMOUNT,
+        WagerError::BetAmountTooLow
+    );
+
+    require!(
+        bet_amount <= MAX_BET_AMOUNT,
+        WagerError::BetAmountTooHigh
+    );
+
+    // SECURITY FIX: Validate game mode is supported
+    let players_per_team = game_mode.players_per_team();
+    require!(
+        players_per_team > 0 && players_per_team <= MAX_PLAYERS_PER_TEAM,
+        WagerError::InvalidGameMode
+    );
+
+    Ok(())
+}
+
 /// SECURITY FIX: Function to update session expiration (authority only)
 pub fn extend_session_handler(
     ctx: Context<ExtendSession>,
@@ -154,7 +286,6 @@ pub fn extend_session_handler(
     additional_seconds: i64,
 ) -> Result<()> {
     let game_session = &mut ctx.accounts.game_session;
-    let clock = Clock::get()?;
 
     // Only authority can extend sessions
     require!(
@@ -162,53 +293,375 @@ pub fn extend_session_handler(
         WagerError::UnauthorizedAction
     );
 
-    // Can only extend active sessions
+    // Active sessions only, at most 24 hours per call and never past the hard deadline
+    game_session.extend_expiry(additional_seconds)?;
+
+    msg!("Session {} extended by {} seconds. New expiration: {}", 
+         game_session.session_id, additional_seconds, game_session.expires_at);
+
+    Ok(())
+}
+
+/// SECURITY FIX: Function to cancel a session before it starts, refunding
+/// every seated player's bet (authority only). remaining_accounts holds
+/// [player, player_token_account] pairs in get_all_players order.
+pub fn cancel_session_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CancelSession<'info>>,
+    session_id: String,
+) -> Result<()> {
+    let game_session = &ctx.accounts.game_session;
+
+    // Only authority can cancel sessions
     require!(
-        game_session.status == GameStatus::WaitingForPlayers || 
-        game_session.status == GameStatus::InProgress,
-        WagerError::InvalidGameState
+        game_session.authority == ctx.accounts.authority.key(),
+        WagerError::UnauthorizedAction
     );
 
-    // Validate extension time is reasonable (max 24 hours)
+    // Can only cancel sessions that haven't started
+    let refunds = game_session.get_cancellation_refunds()?;
     require!(
-        additional_seconds > 0 && additional_seconds <= 86400,
-        WagerError::InvalidExtensionTime
+        ctx.remaining_accounts.len() == refunds.len() * 2,
+        WagerError::InvalidRemainingAccounts
     );
 
-    // Extend expiration time with overflow protection
-    game_session.expires_at = game_session.expires_at
-        .checked_add(additional_seconds)
+    // SECURITY FIX: Make sure every refund can be paid before moving any funds
+    game_session.assert_vault_covers_contributions(ctx.accounts.vault_token_account.amount)?;
+    let total_refund = refunds
+        .iter()
+        .try_fold(0u64, |total, (_, amount)| total.checked_add(*amount))
         .ok_or(WagerError::ArithmeticError)?;
+    require!(
+        ctx.accounts.vault_token_account.amount >= total_refund,
+        WagerError::InsufficientVaultBalance
+    );
 
-    msg!("Session {} extended by {} seconds. New expiration: {}", 
-         game_session.session_id, additional_seconds, game_session.expires_at);
+    let vault_balance_before = ctx.accounts.vault_token_account.amount;
+
+    for (i, (player, amount)) in refunds.iter().enumerate() {
+        let player_account = &ctx.remaining_accounts[i * 2];
+        let player_token_account_info = &ctx.remaining_accounts[i * 2 + 1];
+
+        require!(
+            player_account.key() == *player,
+            WagerError::InvalidPlayer
+        );
+
+        let player_token_account = Account::<TokenAccount>::try_from(player_token_account_info)
+            .map_err(|_| error!(WagerError::InvalidPlayerTokenAccount))?;
+
+        require!(
+            player_token_account.owner == *player,
+            WagerError::InvalidPlayerTokenAccount
+        );
+
+        require!(
+            player_token_account.mint == game_session.token_mint,
+            WagerError::InvalidTokenMint
+        );
+
+        vault_transfer(
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault_token_account,
+            &ctx.accounts.vault,
+            &session_id,
+            game_session.vault_bump,
+            player_token_account_info,
+            *amount,
+        )?;
+
+        emit!(TokenMoved {
+            session_id: session_id.clone(),
+            from: ctx.accounts.vault_token_account.key(),
+            to: player_token_account_info.key(),
+            amount: *amount,
+            reason: TokenMoveReason::Refund as u8,
+        });
+    }
+
+    ctx.accounts.vault_token_account.reload()?;
+    verify_vault_drain(vault_balance_before, ctx.accounts.vault_token_account.amount, total_refund)?;
+
+    let game_session = &mut ctx.accounts.game_session;
+    for (player, amount) in &refunds {
+        game_session.record_payout(*player, *amount)?;
+    }
+
+    // Change status to cancelled
+    game_session.status = GameStatus::Cancelled;
+
+    if let Some(session_index) = ctx.accounts.session_index.as_mut() {
+        session_index.remove_session(game_session.key());
+    }
+
+    msg!("Session {} cancelled by authority, refunded {} tokens to {} players",
+         session_id, total_refund, refunds.len());
 
     Ok(())
 }
 
-/// SECURITY FIX: Function to cancel a session before it starts (authority only)
-pub fn cancel_session_handler(
-    ctx: Context<CancelSession>,
+/// Start a filled session once the minimum start gap has elapsed (authority only)
+pub fn force_start_handler(
+    ctx: Context<ForceStart>,
     _session_id: String,
 ) -> Result<()> {
     let game_session = &mut ctx.accounts.game_session;
+    let clock = Clock::get()?;
 
-    // Only authority can cancel sessions
     require!(
-        game_session.authority == ctx.accounts.authority.key(),
-        WagerError::UnauthorizedAction
+        !game_session.is_expired(clock.unix_timestamp),
+        WagerError::GameSessionExpired
     );
 
-    // Can only cancel sessions that haven't started
+    game_session.start(clock.unix_timestamp)?;
+
+    msg!("Session {} force started by authority", game_session.session_id);
+
+    Ok(())
+}
+
+/// Start a casual queue with uneven teams once its auto-start time has
+/// passed. Anyone may call this so the queue does not depend on the server.
+pub fn try_auto_start_handler(
+    ctx: Context<TryAutoStart>,
+    _session_id: String,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    let clock = Clock::get()?;
+
     require!(
-        game_session.status == GameStatus::WaitingForPlayers,
-        WagerError::GameAlreadyStarted
+        !game_session.is_expired(clock.unix_timestamp),
+        WagerError::GameSessionExpired
     );
 
-    // Change status to cancelled
-    game_session.status = GameStatus::Cancelled;
+    game_session.auto_start(clock.unix_timestamp)?;
+
+    msg!("Session {} auto started with {} players",
+         game_session.session_id, game_session.get_all_players().len());
+
+    Ok(())
+}
+
+/// Set or clear when a casual queue may start without full teams (authority only)
+pub fn update_auto_start_handler(
+    ctx: Context<UpdateSessionConfig>,
+    _session_id: String,
+    auto_start_at: Option<i64>,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    game_session.update_auto_start_at(auto_start_at)?;
+
+    msg!("Session {} auto start set to {:?}", game_session.session_id, auto_start_at);
+
+    Ok(())
+}
+
+/// Update the minimum gap between creation and start (authority only)
+pub fn update_min_time_before_start_handler(
+    ctx: Context<UpdateSessionConfig>,
+    _session_id: String,
+    seconds: i64,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    game_session.update_min_time_before_start(seconds)?;
+
+    msg!("Session {} minimum time before start set to {} seconds",
+         game_session.session_id, seconds);
+
+    Ok(())
+}
+
+/// Correct a misconfigured bet amount before any player has joined (authority only)
+pub fn update_bet_amount_handler(
+    ctx: Context<UpdateSessionConfig>,
+    _session_id: String,
+    new_bet: u64,
+) -> Result<()> {
+    require!(
+        new_bet >= MIN_BET_AMOUNT,
+        WagerError::BetAmountTooLow
+    );
+
+    require!(
+        new_bet <= MAX_BET_AMOUNT,
+        WagerError::BetAmountTooHigh
+    );
+
+    let game_session = &mut ctx.accounts.game_session;
+    let old_bet = game_session.session_bet;
+
+    game_session.update_session_bet(new_bet)?;
 
-    msg!("Session {} cancelled by authority", game_session.session_id);
+    msg!("Session {} bet amount changed from {} to {}",
+         game_session.session_id, old_bet, new_bet);
+
+    Ok(())
+}
+
+/// Handicap a ranked match with a per-player bet for each team before any
+/// player has joined (authority only)
+pub fn set_team_bets_handler(
+    ctx: Context<UpdateSessionConfig>,
+    _session_id: String,
+    team_a_bet: u64,
+    team_b_bet: u64,
+) -> Result<()> {
+    for bet in [team_a_bet, team_b_bet] {
+        require!(bet >= MIN_BET_AMOUNT, WagerError::BetAmountTooLow);
+        require!(bet <= MAX_BET_AMOUNT, WagerError::BetAmountTooHigh);
+    }
+
+    let game_session = &mut ctx.accounts.game_session;
+    game_session.set_team_bets(team_a_bet, team_b_bet)?;
+
+    msg!("Session {} team bets set to {} (team A) and {} (team B)",
+         game_session.session_id, team_a_bet, team_b_bet);
+
+    Ok(())
+}
+
+/// Let kills on teammates count, e.g. for hardcore modes (authority only, before start)
+pub fn update_friendly_fire_handler(
+    ctx: Context<UpdateSessionConfig>,
+    _session_id: String,
+    allow: bool,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    game_session.set_allow_friendly_fire(allow)?;
+
+    msg!("Session {} friendly fire {}", game_session.session_id,
+         if allow { "enabled" } else { "disabled" });
+
+    Ok(())
+}
+
+/// Stop pay-to-spawn players who never score from earning on spawns alone
+/// (authority only, before start)
+pub fn update_min_kills_to_earn_handler(
+    ctx: Context<UpdateSessionConfig>,
+    _session_id: String,
+    min_kills: u16,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    game_session.set_min_kills_to_earn(min_kills)?;
+
+    msg!("Session {} min kills to earn set to {}", game_session.session_id, min_kills);
+
+    Ok(())
+}
+
+/// Require players to join and be paid through their canonical associated
+/// token accounts (authority only, before start)
+pub fn update_require_ata_handler(
+    ctx: Context<UpdateSessionConfig>,
+    _session_id: String,
+    require_ata: bool,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    game_session.set_require_ata(require_ata)?;
+
+    msg!("Session {} canonical ATA {}", game_session.session_id,
+         if require_ata { "required" } else { "not required" });
+
+    Ok(())
+}
+
+/// Stop joins from stacking one team more than a player ahead of the other
+/// (authority only, before start)
+pub fn update_enforce_balance_handler(
+    ctx: Context<UpdateSessionConfig>,
+    _session_id: String,
+    enforce: bool,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    game_session.set_enforce_balance(enforce)?;
+
+    msg!("Session {} team balance {}", game_session.session_id,
+         if enforce { "enforced" } else { "not enforced" });
+
+    Ok(())
+}
+
+/// Require victims to acknowledge kills before they count, for contested
+/// high-stakes matches (authority only, before start)
+pub fn update_require_victim_ack_handler(
+    ctx: Context<UpdateSessionConfig>,
+    _session_id: String,
+    require_ack: bool,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    game_session.set_require_victim_ack(require_ack)?;
+
+    msg!("Session {} victim kill acks {}", game_session.session_id,
+         if require_ack { "required" } else { "not required" });
+
+    Ok(())
+}
+
+/// Freeze a single session under investigation, blocking joins, spawn
+/// purchases, kills and distribution; leaving and refunds stay open (authority only)
+pub fn freeze_session_handler(
+    ctx: Context<UpdateSessionConfig>,
+    _session_id: String,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    require!(!game_session.frozen, WagerError::SessionFrozen);
+
+    game_session.frozen = true;
+
+    msg!("Session {} frozen by authority", game_session.session_id);
+    Ok(())
+}
+
+/// Lift a session freeze once the investigation is over (authority only)
+pub fn unfreeze_session_handler(
+    ctx: Context<UpdateSessionConfig>,
+    _session_id: String,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    require!(game_session.frozen, WagerError::InvalidGameState);
+
+    game_session.frozen = false;
+
+    msg!("Session {} unfrozen by authority", game_session.session_id);
+    Ok(())
+}
+
+/// Hand off control of a session to a new game server authority (current authority only)
+pub fn transfer_authority_handler(
+    ctx: Context<TransferAuthority>,
+    _session_id: String,
+    new_authority: Pubkey,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    require!(
+        game_session.status != GameStatus::Distributed,
+        WagerError::SessionAlreadyFinalized
+    );
+
+    require!(
+        new_authority != Pubkey::default(),
+        WagerError::InvalidAuthority
+    );
+
+    let old_authority = game_session.authority;
+    game_session.authority = new_authority;
+
+    msg!("Session {} authority transferred from {} to {}",
+         game_session.session_id, old_authority, new_authority);
+
+    emit!(AuthorityTransferred {
+        session_id: game_session.session_id.clone(),
+        old_authority,
+        new_authority,
+    });
 
     Ok(())
 }
@@ -232,6 +685,9 @@ pub fn get_session_info_handler(
     msg!("Expires at: {}", game_session.expires_at);
     msg!("Is expired: {}", game_session.is_expired(clock.unix_timestamp));
     msg!("Spawns per purchase: {}", game_session.spawns_per_purchase);
+    msg!("Match seed: {:?}", game_session.match_seed);
+    msg!("Metadata: {:?}", game_session.metadata);
+    msg!("Max vault balance: {}", game_session.max_vault_balance);
     
     // Team information
     let players_per_team = game_session.game_mode.players_per_team();
@@ -284,11 +740,34 @@ pub struct CreateGameSession<'info> {
     )]
     pub vault_token_account: Account<'info, TokenAccount>,
 
+    /// The SPL token this session is wagered in
+    #[account(mut)]
+    pub mint: Account<'info, anchor_spl::token::Mint>,
+
+    /// Running analytics for the game server, updated when provided
     #[account(
         mut,
-        address = TOKEN_ID @ WagerError::InvalidMint
+        seeds = [b"authority_stats", game_server.key().as_ref()],
+        bump = authority_stats.bump,
     )]
-    pub mint: Account<'info, anchor_spl::token::Mint>,
+    pub authority_stats: Option<Account<'info, AuthorityStats>>,
+
+    /// Active session index for the game server, updated when provided
+    #[account(
+        mut,
+        seeds = [b"session_index", game_server.key().as_ref()],
+        bump = session_index.bump,
+    )]
+    pub session_index: Option<Account<'info, AuthoritySessionIndex>>,
+
+    /// Program-wide config whose whitelist the mint must be on
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = !global_config.creation_paused @ WagerError::EmergencyPauseActive,
+        constraint = global_config.is_mint_allowed(&mint.key()) @ WagerError::InvalidMint,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
 
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -331,6 +810,100 @@ pub struct CancelSession<'info> {
         constraint = game_session.status == GameStatus::WaitingForPlayers @ WagerError::GameAlreadyStarted,
     )]
     pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: Vault PDA that holds the funds
+    #[account(
+        seeds = [b"vault", session_id.as_bytes()],
+        bump = game_session.vault_bump,
+    )]
+    pub vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = game_session.token_mint,
+        associated_token::authority = vault,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    /// Active session index for the authority, updated when provided
+    #[account(
+        mut,
+        seeds = [b"session_index", authority.key().as_ref()],
+        bump = session_index.bump,
+    )]
+    pub session_index: Option<Account<'info, AuthoritySessionIndex>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Account structure for force starting sessions
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct ForceStart<'info> {
+    #[account(
+        constraint = authority.key() == game_session.authority @ WagerError::UnauthorizedAction,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+        constraint = game_session.status == GameStatus::WaitingForPlayers @ WagerError::GameAlreadyStarted,
+    )]
+    pub game_session: Account<'info, GameSession>,
+}
+
+/// Account structure for permissionless auto start
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct TryAutoStart<'info> {
+    /// Anyone may start a queue whose auto-start time has passed
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+        constraint = game_session.status == GameStatus::WaitingForPlayers @ WagerError::GameAlreadyStarted,
+    )]
+    pub game_session: Account<'info, GameSession>,
+}
+
+/// Account structure for updating session configuration
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct UpdateSessionConfig<'info> {
+    #[account(
+        constraint = authority.key() == game_session.authority @ WagerError::UnauthorizedAction,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+    )]
+    pub game_session: Account<'info, GameSession>,
+}
+
+/// Account structure for transferring session authority
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct TransferAuthority<'info> {
+    #[account(
+        constraint = authority.key() == game_session.authority @ WagerError::UnauthorizedAction,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+        constraint = game_session.status != GameStatus::Distributed @ WagerError::SessionAlreadyFinalized,
+    )]
+    pub game_session: Account<'info, GameSession>,
 }
 
 /// SECURITY FIX: Account structure for session info queries
@@ -406,6 +979,7 @@ mod tests {
             GameMode::PayToSpawnOneVsOne,
             GameMode::PayToSpawnThreeVsThree,
             GameMode::PayToSpawnFiveVsFive,
+            GameMode::FreeForAll,
         ];
         
         for mode in modes {
@@ -1,48 +1,58 @@
-use crate::{errors::WagerError, state::*, TOKEN_ID};
+use crate::instructions::vault_transfer;
+use crate::{errors::WagerError, events::*, state::*};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::{Token, TokenAccount};
 
 // SECURITY FIX: Added constants for better maintainability
-const EARNINGS_DIVISOR: u64 = 10;
 const MAX_DISTRIBUTION_ATTEMPTS: usize = 3;
+// Return data is capped at 1024 bytes: 4 byte length + 56 bytes per entry
+const MAX_SETTLEMENT_REPORT_ENTRIES: usize = 18;
 
 /// SECURITY FIX: Comprehensive vault balance validation and rollback capability
 pub fn distribute_pay_spawn_earnings<'info>(
     ctx: Context<'_, '_, 'info, 'info, DistributeWinnings<'info>>,
     session_id: String,
 ) -> Result<()> {
-    let game_session = &ctx.accounts.game_session;
     msg!("Starting pay-to-spawn earnings distribution for session: {}", session_id);
+    settle_pay_spawn_earnings(ctx, session_id)
+}
 
-    // SECURITY FIX: Validate game session state and expiration
-    let clock = Clock::get()?;
-    require!(
-        !game_session.is_expired(clock.unix_timestamp),
-        WagerError::GameSessionExpired
-    );
-
+/// Resumes a pay-to-spawn distribution that left some players unpaid, paying
+/// only players with positive earnings who were not paid by an earlier run
+pub fn resume_distribution_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributeWinnings<'info>>,
+    session_id: String,
+) -> Result<()> {
     require!(
-        game_session.status == GameStatus::InProgress || 
-        game_session.status == GameStatus::Completed,
+        ctx.accounts.game_session.has_paid_earnings(),
         WagerError::InvalidGameState
     );
 
-    require!(
-        game_session.is_pay_to_spawn(),
-        WagerError::InvalidGameMode
-    );
+    msg!("Resuming pay-to-spawn earnings distribution for session: {}", session_id);
+    settle_pay_spawn_earnings(ctx, session_id)
+}
+
+/// Pays every unpaid player their pay-to-spawn earnings. The session only
+/// becomes Distributed once all of them are paid; after a partial failure it
+/// stays AwaitingDistribution so resume_distribution_handler can finish the job.
+fn settle_pay_spawn_earnings<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributeWinnings<'info>>,
+    session_id: String,
+) -> Result<()> {
+    begin_distribution(&mut ctx.accounts.game_session)?;
+    let game_session = &ctx.accounts.game_session;
+
+    validate_pay_spawn_distribution(game_session)?;
+
+    // SECURITY FIX: Catch any external drain before moving funds
+    game_session.assert_vault_covers_contributions(ctx.accounts.vault_token_account.amount)?;
 
     let players = game_session.get_all_players();
     msg!("Number of players: {}", players.len());
     msg!("Number of remaining accounts: {}", ctx.remaining_accounts.len());
 
     // Validate remaining accounts structure
-    require!(
-        !ctx.remaining_accounts.is_empty(),
-        WagerError::InvalidRemainingAccounts
-    );
-
     require!(
         ctx.remaining_accounts.len() % 2 == 0,
         WagerError::InvalidRemainingAccounts
@@ -54,34 +64,27 @@ pub fn distribute_pay_spawn_earnings<'info>(
     );
 
     // SECURITY FIX: Calculate total distribution required before any transfers
-    let mut total_distribution_needed = 0u64;
-    let mut player_distributions = Vec::new();
-
-    for player in &players {
-        let kills_and_spawns = game_session.get_kills_and_spawns(*player)?;
-        if kills_and_spawns == 0 {
-            continue;
-        }
-
-        // SECURITY FIX: Use checked arithmetic to prevent overflow
-        let earnings = (kills_and_spawns as u64)
-            .checked_mul(game_session.session_bet)
-            .and_then(|x| x.checked_div(EARNINGS_DIVISOR))
-            .ok_or(WagerError::ArithmeticError)?;
-
-        if earnings > 0 {
-            total_distribution_needed = total_distribution_needed
-                .checked_add(earnings)
-                .ok_or(WagerError::ArithmeticError)?;
-
-            player_distributions.push((*player, earnings));
-        }
+    let (player_distributions, total_distribution_needed) =
+        compute_pay_spawn_earnings(game_session)?;
+
+    // Strict mode: reject a mis-ordered layout before any transfer happens.
+    // Otherwise every player being paid this run must have exactly one pair.
+    if game_session.strict_account_order {
+        let account_keys: Vec<Pubkey> = ctx
+            .remaining_accounts
+            .iter()
+            .map(|account| account.key())
+            .collect();
+        validate_account_order(&players, &account_keys)?;
+    } else {
+        check_player_account_count(player_distributions.len(), ctx.remaining_accounts.len())?;
     }
 
     msg!("Total distribution needed: {}", total_distribution_needed);
 
     // SECURITY FIX: Validate vault has sufficient balance BEFORE any transfers
     let vault_balance = ctx.accounts.vault_token_account.amount;
+    let distributed_before = game_session.total_distributed;
     msg!("Vault balance: {}", vault_balance);
     
     require!(
@@ -93,7 +96,8 @@ pub fn distribute_pay_spawn_earnings<'info>(
     if player_distributions.is_empty() {
         msg!("No earnings to distribute");
         let game_session = &mut ctx.accounts.game_session;
-        game_session.status = GameStatus::Completed;
+        game_session.end_distribution();
+        game_session.status = GameStatus::Distributed;
         return Ok(());
     }
 
@@ -101,9 +105,10 @@ pub fn distribute_pay_spawn_earnings<'info>(
     let mut successful_transfers = Vec::new();
     let mut transfer_errors = Vec::new();
 
-    for (player, earnings) in &player_distributions {
+    for (position, player, earnings) in &player_distributions {
         match process_player_distribution(
             &ctx,
+            *position,
             *player,
             *earnings,
             &session_id,
@@ -126,47 +131,122 @@ pub fn distribute_pay_spawn_earnings<'info>(
              player_distributions.len());
         
         // If critical number of transfers failed, consider rollback
-        let failure_rate = transfer_errors.len() as f64 / player_distributions.len() as f64;
-        if failure_rate > 0.5 {
-            msg!("High failure rate detected: {:.2}%. Manual intervention may be required.", failure_rate * 100.0);
+        if game_session.exceeds_failure_threshold(transfer_errors.len(), player_distributions.len()) {
+            msg!("High failure rate detected: {} of {} transfers failed, limit {} bps. Manual intervention may be required.",
+                 transfer_errors.len(), player_distributions.len(), game_session.max_failure_bps);
             return Err(error!(WagerError::DistributionPartialFailure));
         }
     }
 
-    // Mark session as completed
+    // SECURITY FIX: Verify the vault dropped by exactly what was paid out
+    let total_transferred = successful_transfers
+        .iter()
+        .try_fold(0u64, |total, (_, amount)| total.checked_add(*amount))
+        .ok_or(WagerError::ArithmeticError)?;
+    ctx.accounts.vault_token_account.reload()?;
+    verify_vault_drain(vault_balance, ctx.accounts.vault_token_account.amount, total_transferred)?;
+
+    let remaining_balance = ctx.accounts.vault_token_account.amount;
     let game_session = &mut ctx.accounts.game_session;
-    game_session.status = GameStatus::Completed;
+    for (player, earnings) in &successful_transfers {
+        game_session.record_payout(*player, *earnings)?;
+        game_session.mark_earnings_paid(*player)?;
+    }
+    game_session.end_distribution();
+
+    // Stay AwaitingDistribution until every player is paid so the run can be resumed
+    if !transfer_errors.is_empty() {
+        msg!("{} players unpaid, call resume_distribution to pay them", transfer_errors.len());
+        return Ok(());
+    }
+    reconcile_vault_residual(game_session, vault_balance, remaining_balance, distributed_before)?;
+    game_session.status = GameStatus::Distributed;
 
     msg!("Pay-to-spawn earnings distribution completed successfully");
     Ok(())
 }
 
-/// SECURITY FIX: Secure winner-takes-all distribution with comprehensive validation
+/// SECURITY FIX: Secure winner-takes-all distribution with comprehensive validation.
+/// A `winning_team` with no active players is never corrected to the other
+/// side: the pot is settled by the session's empty_winner_policy, by default
+/// refunding every remaining player their bet (see settle_empty_winning_team).
 pub fn distribute_all_winnings_handler<'info>(
     ctx: Context<'_, '_, 'info, 'info, DistributeWinnings<'info>>,
     session_id: String,
     winning_team: u8,
 ) -> Result<()> {
+    begin_distribution(&mut ctx.accounts.game_session)?;
     let game_session = &ctx.accounts.game_session;
     msg!("Starting winner-takes-all distribution for session: {}", session_id);
 
     // SECURITY FIX: Validate game session state and expiration
     let clock = Clock::get()?;
-    require!(
-        !game_session.is_expired(clock.unix_timestamp),
-        WagerError::GameSessionExpired
-    );
+    game_session.check_distribution_window(clock.unix_timestamp)?;
+
+    settle_team_winnings(ctx, session_id, winning_team)
+}
+
+/// Settles an in-progress team game that ran past expiry, which the regular
+/// distribute handlers reject: the side ahead on kills (then spawns purchased)
+/// wins and is paid out, and a full tie refunds everyone as a draw. A game
+/// resolved before expiry but not yet paid is paid on its recorded result.
+/// remaining_accounts follow the layout of the matching distribute handler.
+pub fn settle_expired_game_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributeWinnings<'info>>,
+    session_id: String,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let already_resolved = ctx.accounts.game_session.status == GameStatus::AwaitingDistribution;
+    let outcome = ctx.accounts.game_session.settle_expired(clock.unix_timestamp)?;
+    let winning_team = outcome.winning_team();
+
+    match winning_team {
+        Some(team) => msg!("Expired session {} settled, team {} wins", session_id, team),
+        None => msg!("Expired session {} settled as a draw", session_id),
+    }
+
+    // A session resolved before expiry already announced its result
+    if !already_resolved {
+        emit!(GameResolved {
+            session_id: session_id.clone(),
+            winning_team,
+        });
+    }
+
+    if ctx.accounts.game_session.is_pay_to_spawn() {
+        return settle_pay_spawn_earnings(ctx, session_id);
+    }
+
+    begin_distribution(&mut ctx.accounts.game_session)?;
+    settle_team_winnings(ctx, session_id, winning_team.unwrap_or(DRAW_TEAM))
+}
+
+/// Pays a resolved winner-takes-all pot to `winning_team`, or refunds a draw
+fn settle_team_winnings<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributeWinnings<'info>>,
+    session_id: String,
+    winning_team: u8,
+) -> Result<()> {
+    let game_session = &ctx.accounts.game_session;
 
     require!(
-        game_session.status == GameStatus::Completed,
+        game_session.status == GameStatus::AwaitingDistribution,
         WagerError::InvalidGameState
     );
 
     require!(
-        !game_session.is_pay_to_spawn(),
+        !game_session.is_pay_to_spawn() && !game_session.game_mode.is_free_for_all(),
         WagerError::InvalidGameMode
     );
 
+    require!(
+        !game_session.is_series_round(),
+        WagerError::SeriesRoundDistribution
+    );
+
+    // SECURITY FIX: Catch any external drain before moving funds
+    game_session.assert_vault_covers_contributions(ctx.accounts.vault_token_account.amount)?;
+
     // Verify authority matches game session authority
     require!(
         game_session.authority == ctx.accounts.game_server.key(),
@@ -175,10 +255,17 @@ pub fn distribute_all_winnings_handler<'info>(
 
     // Validate winning team selection
     require!(
-        winning_team == 0 || winning_team == 1,
+        winning_team == 0 || winning_team == 1 || winning_team == DRAW_TEAM,
         WagerError::InvalidWinningTeam
     );
 
+    // The pot follows the result recorded at resolution, not the caller
+    game_session.check_recorded_winner(winning_team)?;
+
+    if winning_team == DRAW_TEAM {
+        return refund_draw(ctx, session_id);
+    }
+
     let players_per_team = game_session.game_mode.players_per_team();
 
     // Get the winning team players
@@ -195,7 +282,12 @@ pub fn distribute_all_winnings_handler<'info>(
         .copied()
         .collect();
 
-    require!(!active_winners.is_empty(), WagerError::NoActiveWinners);
+    // Every winner left before completion: settle per the session's policy
+    // instead of stranding the pot in the vault
+    if active_winners.is_empty() {
+        msg!("Winning team {} has no active players", winning_team);
+        return settle_empty_winning_team(ctx, session_id);
+    }
 
     msg!("Active winners: {}", active_winners.len());
     for player in &active_winners {
@@ -208,20 +300,28 @@ pub fn distribute_all_winnings_handler<'info>(
         WagerError::InvalidRemainingAccounts
     );
 
-    // SECURITY FIX: Calculate total distribution and validate vault balance
-    let winning_amount_per_player = game_session.session_bet
-        .checked_mul(2)
-        .ok_or(WagerError::ArithmeticError)?;
+    // SECURITY FIX: Split the whole pot, remainder tokens going to the earliest
+    // slots, so the winners' shares add up to exactly what was collected
+    let winner_shares = game_session.winner_shares(winning_team)?;
 
-    let total_distribution = winning_amount_per_player
-        .checked_mul(active_winners.len() as u64)
-        .ok_or(WagerError::ArithmeticError)?;
+    // Winners already paid by an earlier, partially failed run are skipped
+    let unclaimed_winners = game_session.get_unclaimed_winners(winning_team)?;
+    msg!("Unclaimed winners: {}", unclaimed_winners.len());
+
+    let mut total_distribution = 0u64;
+    for (winner, share) in &winner_shares {
+        if unclaimed_winners.contains(winner) {
+            total_distribution = total_distribution
+                .checked_add(game_session.round_payout(*share)?.0)
+                .ok_or(WagerError::ArithmeticError)?;
+        }
+    }
 
-    msg!("Winning amount per player: {}", winning_amount_per_player);
     msg!("Total distribution needed: {}", total_distribution);
 
     // SECURITY FIX: Validate vault has sufficient balance BEFORE any transfers
     let vault_balance = ctx.accounts.vault_token_account.amount;
+    let distributed_before = game_session.total_distributed;
     msg!("Vault balance: {}", vault_balance);
     
     require!(
@@ -231,7 +331,7 @@ pub fn distribute_all_winnings_handler<'info>(
 
     // SECURITY FIX: Validate all winner accounts before starting transfers
     let mut winner_validations = Vec::new();
-    for (i, &winner_pubkey) in active_winners.iter().enumerate() {
+    for (i, &(winner_pubkey, share)) in winner_shares.iter().enumerate() {
         let winner_account = &ctx.remaining_accounts[i * 2];
         let winner_token_account_info = &ctx.remaining_accounts[i * 2 + 1];
 
@@ -251,9 +351,11 @@ pub fn distribute_all_winnings_handler<'info>(
             WagerError::InvalidWinnerTokenAccount
         );
 
+        game_session.check_canonical_token_account(&winner_pubkey, &winner_token_account_info.key())?;
+
         // Validate token mint
         require!(
-            winner_token_account.mint == TOKEN_ID,
+            winner_token_account.mint == game_session.token_mint,
             WagerError::InvalidTokenMint
         );
 
@@ -263,234 +365,996 @@ pub fn distribute_all_winnings_handler<'info>(
             WagerError::InvalidWinner
         );
 
-        winner_validations.push((winner_account, winner_token_account_info, winner_pubkey));
+        if !unclaimed_winners.contains(&winner_pubkey) {
+            msg!("Winner {} already paid, skipping", winner_pubkey);
+            continue;
+        }
+
+        let (amount, remainder) = game_session.round_payout(share)?;
+        winner_validations.push((winner_token_account_info, winner_pubkey, amount, remainder));
     }
 
-    // SECURITY FIX: Execute transfers with error handling and rollback capability
+    // SECURITY FIX: Execute transfers, leaving failed winners unclaimed so a
+    // later run can resume and pay only them
     let mut successful_transfers = Vec::new();
+    let mut failed_transfers = 0usize;
 
-    for (winner_account, winner_token_account_info, winner_pubkey) in winner_validations {
+    for (winner_token_account_info, winner_pubkey, amount, remainder) in winner_validations {
         match execute_winner_transfer(
             &ctx,
             winner_token_account_info,
-            winning_amount_per_player,
+            amount,
             &session_id,
+            TokenMoveReason::Distribution,
         ) {
             Ok(()) => {
-                successful_transfers.push((winner_pubkey, winning_amount_per_player));
+                successful_transfers.push((winner_pubkey, amount, remainder));
                 msg!("Successfully transferred {} tokens to winner {}", 
-                     winning_amount_per_player, winner_pubkey);
+                     amount, winner_pubkey);
             }
             Err(e) => {
                 msg!("Failed to transfer to winner {}: {:?}", winner_pubkey, e);
-                
-                // SECURITY FIX: On any transfer failure in winner-takes-all, 
-                // we need to handle it carefully since partial distribution 
-                // would be unfair. For now, we'll fail the entire transaction.
-                return Err(e);
+                failed_transfers += 1;
             }
         }
     }
 
-    // Mark session as distributed
+    // SECURITY FIX: Verify the vault dropped by exactly what was paid out
+    let total_transferred = successful_transfers
+        .iter()
+        .try_fold(0u64, |total, (_, amount, _)| total.checked_add(*amount))
+        .ok_or(WagerError::ArithmeticError)?;
+    ctx.accounts.vault_token_account.reload()?;
+    verify_vault_drain(vault_balance, ctx.accounts.vault_token_account.amount, total_transferred)?;
+
+    let remaining_balance = ctx.accounts.vault_token_account.amount;
     let game_session = &mut ctx.accounts.game_session;
+    for (winner_pubkey, amount, remainder) in &successful_transfers {
+        game_session.record_payout(*winner_pubkey, *amount)?;
+        game_session.mark_claimed(*winner_pubkey)?;
+        game_session.add_rounding_remainder(*remainder)?;
+    }
+    game_session.end_distribution();
+
+    // Stay AwaitingDistribution until every winner is paid so the run can be resumed
+    if failed_transfers > 0 {
+        msg!("{} winners unpaid, re-run distribution to resume", failed_transfers);
+        return Ok(());
+    }
+    reconcile_vault_residual(game_session, vault_balance, remaining_balance, distributed_before)?;
     game_session.status = GameStatus::Distributed;
 
     msg!("Winner-takes-all distribution completed successfully");
+    if game_session.rounding_remainder > 0 {
+        msg!("Rounding remainder left in vault: {}", game_session.rounding_remainder);
+    }
     msg!("Total distributed: {} tokens to {} winners", 
          total_distribution, active_winners.len());
     
     Ok(())
 }
 
-/// SECURITY FIX: Helper function for individual player distribution with validation
-fn process_player_distribution<'info>(
-    ctx: &Context<'_, '_, 'info, 'info, DistributeWinnings<'info>>,
-    player: Pubkey,
-    earnings: u64,
-    session_id: &str,
+/// Pays the whole vault to the top free-for-all player.
+/// remaining_accounts: [winner, winner_token_account]
+pub fn distribute_free_for_all_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributeWinnings<'info>>,
+    session_id: String,
 ) -> Result<()> {
-    // Find the player's account and token account in remaining_accounts
-    let player_index = ctx
-        .remaining_accounts
-        .iter()
-        .step_by(2)
-        .position(|acc| acc.key() == player)
-        .ok_or(WagerError::InvalidPlayer)?;
+    begin_distribution(&mut ctx.accounts.game_session)?;
+    let game_session = &ctx.accounts.game_session;
+    msg!("Starting free-for-all distribution for session: {}", session_id);
 
-    let player_account = &ctx.remaining_accounts[player_index * 2];
-    let player_token_account_info = &ctx.remaining_accounts[player_index * 2 + 1];
+    require!(
+        game_session.game_mode.is_free_for_all(),
+        WagerError::InvalidGameMode
+    );
 
-    // Validate and deserialize player token account
-    let player_token_account = Account::<TokenAccount>::try_from(player_token_account_info)
-        .map_err(|_| error!(WagerError::InvalidPlayerTokenAccount))?;
+    // SECURITY FIX: Catch any external drain before moving funds
+    game_session.assert_vault_covers_contributions(ctx.accounts.vault_token_account.amount)?;
 
-    // Validate token account ownership
     require!(
-        player_token_account.owner == player,
-        WagerError::InvalidPlayerTokenAccount
+        game_session.status == GameStatus::AwaitingDistribution,
+        WagerError::InvalidGameState
     );
 
-    // Validate token mint
+    let winner = game_session
+        .free_for_all_winner()
+        .ok_or(WagerError::GameNotResolvable)?;
+
     require!(
-        player_token_account.mint == TOKEN_ID,
-        WagerError::InvalidTokenMint
+        ctx.remaining_accounts.len() == 2,
+        WagerError::InvalidRemainingAccounts
     );
 
-    // SECURITY FIX: Double-check vault balance before individual transfer
-    let vault_balance = ctx.accounts.vault_token_account.amount;
+    let winner_account = &ctx.remaining_accounts[0];
+    let winner_token_account_info = &ctx.remaining_accounts[1];
+
     require!(
-        vault_balance >= earnings,
-        WagerError::InsufficientVaultBalance
+        winner_account.key() == winner,
+        WagerError::InvalidWinner
     );
 
-    // Execute the transfer
-    anchor_spl::token::transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            anchor_spl::token::Transfer {
-                from: ctx.accounts.vault_token_account.to_account_info(),
-                to: player_token_account_info.to_account_info(),
-                authority: ctx.accounts.vault.to_account_info(),
-            },
-            &[&[
-                b"vault",
-                session_id.as_bytes(),
-                &[ctx.accounts.game_session.vault_bump],
-            ]],
-        ),
-        earnings,
-    )?;
+    let winner_token_account = Account::<TokenAccount>::try_from(winner_token_account_info)
+        .map_err(|_| error!(WagerError::InvalidWinnerTokenAccount))?;
 
-    Ok(())
-}
+    require!(
+        winner_token_account.owner == winner,
+        WagerError::InvalidWinnerTokenAccount
+    );
+
+    game_session.check_canonical_token_account(&winner, &winner_token_account_info.key())?;
 
-/// SECURITY FIX: Helper function for winner transfer with validation
-fn execute_winner_transfer<'info>(
-    ctx: &Context<'_, '_, 'info, 'info, DistributeWinnings<'info>>,
-    winner_token_account_info: &AccountInfo<'info>,
-    amount: u64,
-    session_id: &str,
-) -> Result<()> {
-    // SECURITY FIX: Double-check vault balance before individual transfer
-    let vault_balance = ctx.accounts.vault_token_account.amount;
     require!(
-        vault_balance >= amount,
-        WagerError::InsufficientVaultBalance
+        winner_token_account.mint == game_session.token_mint,
+        WagerError::InvalidTokenMint
     );
 
-    // Execute the transfer
-    anchor_spl::token::transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            anchor_spl::token::Transfer {
-                from: ctx.accounts.vault_token_account.to_account_info(),
-                to: winner_token_account_info.to_account_info(),
-                authority: ctx.accounts.vault.to_account_info(),
-            },
-            &[&[
-                b"vault",
-                session_id.as_bytes(),
-                &[ctx.accounts.game_session.vault_bump],
-            ]],
-        ),
-        amount,
+    let pot = ctx.accounts.vault_token_account.amount;
+    execute_winner_transfer(
+        &ctx,
+        winner_token_account_info,
+        pot,
+        &session_id,
+        TokenMoveReason::Distribution,
     )?;
 
-    Ok(())
-}
+    ctx.accounts.vault_token_account.reload()?;
+    verify_vault_drain(pot, ctx.accounts.vault_token_account.amount, pot)?;
 
-/// SECURITY FIX: Emergency function to cancel distribution if needed
-pub fn cancel_distribution<'info>(
-    ctx: Context<'_, '_, 'info, 'info, DistributeWinnings<'info>>,
-    _session_id: String,
-) -> Result<()> {
     let game_session = &mut ctx.accounts.game_session;
-    
-    // Only authority can cancel
-    require!(
-        game_session.authority == ctx.accounts.game_server.key(),
-        WagerError::UnauthorizedDistribution
-    );
-
-    // Can only cancel if not yet distributed
-    require!(
-        game_session.status != GameStatus::Distributed,
-        WagerError::AlreadyDistributed
-    );
+    game_session.record_payout(winner, pot)?;
+    game_session.end_distribution();
+    game_session.status = GameStatus::Distributed;
 
-    game_session.status = GameStatus::Cancelled;
-    
-    msg!("Distribution cancelled by authority");
+    msg!("Free-for-all pot of {} tokens paid to {}", pot, winner);
     Ok(())
 }
 
-/// SECURITY FIX: Function to get distribution summary without executing transfers
-pub fn get_distribution_summary<'info>(
+/// Settles a winner-takes-all game whose winning team has no active players.
+/// Each remaining player is refunded their recorded contribution via
+/// remaining_accounts pairs in `get_all_players` order; the authority route
+/// expects the authority's token account as the first remaining account.
+fn settle_empty_winning_team<'info>(
     ctx: Context<'_, '_, 'info, 'info, DistributeWinnings<'info>>,
-    _session_id: String,
+    session_id: String,
 ) -> Result<()> {
     let game_session = &ctx.accounts.game_session;
-    
-    if game_session.is_pay_to_spawn() {
-        let players = game_session.get_all_players();
-        let mut total_earnings = 0u64;
-        let mut eligible_players = 0u32;
+    let refunds = game_session.get_outstanding_contributions()?;
+    let vault_balance = ctx.accounts.vault_token_account.amount;
+    let mut routed_to_authority = 0u64;
 
-        for player in &players {
-            let kills_and_spawns = game_session.get_kills_and_spawns(*player)?;
-            if kills_and_spawns > 0 {
-                let earnings = (kills_and_spawns as u64)
-                    .checked_mul(game_session.session_bet)
-                    .and_then(|x| x.checked_div(EARNINGS_DIVISOR))
-                    .unwrap_or(0);
-                
-                if earnings > 0 {
-                    total_earnings = total_earnings.checked_add(earnings).unwrap_or(u64::MAX);
-                    eligible_players += 1;
-                    msg!("Player {} eligible for {} tokens", player, earnings);
-                }
-            }
+    // With nobody left to refund, the authority route is the only way out
+    if game_session.empty_winner_policy == EmptyWinnerPolicy::SendToAuthority
+        || refunds.is_empty()
+    {
+        let authority_token_account_info = ctx
+            .remaining_accounts
+            .first()
+            .ok_or(WagerError::InvalidRemainingAccounts)?;
+
+        let authority_token_account = Account::<TokenAccount>::try_from(authority_token_account_info)
+            .map_err(|_| error!(WagerError::InvalidRemainingAccounts))?;
+
+        require!(
+            authority_token_account.owner == game_session.authority,
+            WagerError::InvalidRemainingAccounts
+        );
+
+        require!(
+            authority_token_account.mint == game_session.token_mint,
+            WagerError::InvalidTokenMint
+        );
+
+        execute_winner_transfer(
+            &ctx,
+            authority_token_account_info,
+            vault_balance,
+            &session_id,
+            TokenMoveReason::Distribution,
+        )?;
+        routed_to_authority = vault_balance;
+        msg!("Routed {} tokens to authority {}", vault_balance, game_session.authority);
+    } else {
+        require!(
+            ctx.remaining_accounts.len() >= refunds.len() * 2,
+            WagerError::InvalidRemainingAccounts
+        );
+
+        let total_refund = refunds
+            .iter()
+            .try_fold(0u64, |total, (_, amount)| total.checked_add(*amount))
+            .ok_or(WagerError::ArithmeticError)?;
+
+        require!(
+            vault_balance >= total_refund,
+            WagerError::InsufficientVaultBalance
+        );
+
+        for (i, (player, amount)) in refunds.iter().enumerate() {
+            let player_account = &ctx.remaining_accounts[i * 2];
+            let player_token_account_info = &ctx.remaining_accounts[i * 2 + 1];
+
+            require!(
+                player_account.key() == *player,
+                WagerError::InvalidPlayer
+            );
+
+            let player_token_account = Account::<TokenAccount>::try_from(player_token_account_info)
+                .map_err(|_| error!(WagerError::InvalidPlayerTokenAccount))?;
+
+            require!(
+                player_token_account.owner == *player,
+                WagerError::InvalidPlayerTokenAccount
+            );
+
+            game_session.check_canonical_token_account(player, &player_token_account_info.key())?;
+
+            require!(
+                player_token_account.mint == game_session.token_mint,
+                WagerError::InvalidTokenMint
+            );
+
+            execute_winner_transfer(
+                &ctx,
+                player_token_account_info,
+                *amount,
+                &session_id,
+                TokenMoveReason::Refund,
+            )?;
+            msg!("Refunded {} tokens to player {}", amount, player);
         }
+    }
 
-        msg!("Pay-to-spawn summary: {} players eligible for total of {} tokens", 
-             eligible_players, total_earnings);
+    let total_transferred = if routed_to_authority > 0 {
+        routed_to_authority
     } else {
-        let players_per_team = game_session.game_mode.players_per_team();
-        let winning_amount = game_session.session_bet
-            .checked_mul(2)
-            .unwrap_or(u64::MAX);
-        let total_distribution = winning_amount
-            .checked_mul(players_per_team as u64)
-            .unwrap_or(u64::MAX);
-            
-        msg!("Winner-takes-all summary: {} winners eligible for {} tokens each, total: {} tokens", 
-             players_per_team, winning_amount, total_distribution);
+        refunds
+            .iter()
+            .try_fold(0u64, |total, (_, amount)| total.checked_add(*amount))
+            .ok_or(WagerError::ArithmeticError)?
+    };
+    ctx.accounts.vault_token_account.reload()?;
+    verify_vault_drain(vault_balance, ctx.accounts.vault_token_account.amount, total_transferred)?;
+
+    let game_session = &mut ctx.accounts.game_session;
+    if routed_to_authority > 0 {
+        game_session.add_total_distributed(routed_to_authority)?;
+    } else {
+        for (player, amount) in &refunds {
+            game_session.record_payout(*player, *amount)?;
+        }
     }
+    game_session.end_distribution();
+    game_session.status = GameStatus::Distributed;
 
-    let vault_balance = ctx.accounts.vault_token_account.amount;
-    msg!("Current vault balance: {}", vault_balance);
-    
+    msg!("Empty winning team settled for session {}", session_id);
     Ok(())
 }
 
-/// SECURITY FIX: Enhanced account validation structure
-#[derive(Accounts)]
-#[instruction(session_id: String)]
-pub struct DistributeWinnings<'info> {
-    /// The game server authority that created the session
-    pub game_server: Signer<'info>,
+/// Settles a winner-takes-all game resolved as a draw, refunding every
+/// player's outstanding stake instead of paying the pot to one side. Only a
+/// session recorded as a draw gets here; a decided game is never refunded.
+/// remaining_accounts holds [player, player_token_account] pairs in get_all_players order.
+fn refund_draw<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributeWinnings<'info>>,
+    session_id: String,
+) -> Result<()> {
+    require!(ctx.accounts.game_session.is_draw, WagerError::InvalidWinningTeam);
+    let refunds = ctx.accounts.game_session.settle_as_draw()?;
+    require!(
+        ctx.remaining_accounts.len() == refunds.len() * 2,
+        WagerError::InvalidRemainingAccounts
+    );
 
-    #[account(
-        mut,
-        seeds = [b"game_session", session_id.as_bytes()],
-        bump = game_session.bump,
-        constraint = game_session.authority == game_server.key() @ WagerError::UnauthorizedDistribution,
-        constraint = session_id.len() <= 32 @ WagerError::SessionIdTooLong,
-    )]
-    pub game_session: Account<'info, GameSession>,
+    let total_refund = refunds
+        .iter()
+        .try_fold(0u64, |total, (_, amount)| total.checked_add(*amount))
+        .ok_or(WagerError::ArithmeticError)?;
 
-    /// CHECK: Vault PDA that holds the funds
+    let vault_balance = ctx.accounts.vault_token_account.amount;
+    require!(
+        vault_balance >= total_refund,
+        WagerError::InsufficientVaultBalance
+    );
+
+    for (i, (player, amount)) in refunds.iter().enumerate() {
+        let player_account = &ctx.remaining_accounts[i * 2];
+        let player_token_account_info = &ctx.remaining_accounts[i * 2 + 1];
+
+        require!(
+            player_account.key() == *player,
+            WagerError::InvalidPlayer
+        );
+
+        let player_token_account = Account::<TokenAccount>::try_from(player_token_account_info)
+            .map_err(|_| error!(WagerError::InvalidPlayerTokenAccount))?;
+
+        require!(
+            player_token_account.owner == *player,
+            WagerError::InvalidPlayerTokenAccount
+        );
+
+        ctx.accounts.game_session.check_canonical_token_account(player, &player_token_account_info.key())?;
+
+        require!(
+            player_token_account.mint == ctx.accounts.game_session.token_mint,
+            WagerError::InvalidTokenMint
+        );
+
+        if *amount == 0 {
+            continue;
+        }
+
+        execute_winner_transfer(
+            &ctx,
+            player_token_account_info,
+            *amount,
+            &session_id,
+            TokenMoveReason::Refund,
+        )?;
+    }
+
+    ctx.accounts.vault_token_account.reload()?;
+    verify_vault_drain(vault_balance, ctx.accounts.vault_token_account.amount, total_refund)?;
+
+    let game_session = &mut ctx.accounts.game_session;
+    for (player, amount) in &refunds {
+        game_session.record_payout(*player, *amount)?;
+        game_session.mark_refunded(*player)?;
+    }
+    game_session.end_distribution();
+    game_session.status = GameStatus::Distributed;
+
+    msg!("Session {} settled as a draw: {} tokens refunded to {} players",
+         session_id, total_refund, refunds.len());
+    Ok(())
+}
+
+/// Pay-to-spawn pays out only once the match has ended (elimination, expiry
+/// resolution or an explicit end_match), so no spawns can be bought into a
+/// pot that is already being settled
+fn validate_pay_spawn_distribution(game_session: &GameSession) -> Result<()> {
+    require!(
+        game_session.status == GameStatus::AwaitingDistribution,
+        WagerError::InvalidGameState
+    );
+
+    require!(
+        game_session.is_pay_to_spawn(),
+        WagerError::InvalidGameMode
+    );
+
+    Ok(())
+}
+
+/// Flags the session as distributing and persists the flag before any transfer
+/// CPI, so a re-entrant distribute call sees it and is rejected. Failed runs
+/// revert the flag along with the rest of the transaction.
+fn begin_distribution(game_session: &mut Account<GameSession>) -> Result<()> {
+    game_session.begin_distribution()?;
+    game_session.exit(&crate::ID)
+}
+
+/// Checks a reloaded vault balance dropped by exactly the sum of the transfers
+/// just made, catching a partial or miscounted transfer
+pub fn verify_vault_drain(
+    balance_before: u64,
+    balance_after: u64,
+    total_transferred: u64,
+) -> Result<()> {
+    let drained = balance_before
+        .checked_sub(balance_after)
+        .ok_or(WagerError::TransferVerificationFailed)?;
+
+    require!(
+        drained == total_transferred,
+        WagerError::TransferVerificationFailed
+    );
+
+    msg!("Vault drain verified: {} tokens", drained);
+    Ok(())
+}
+
+/// Final reconciliation before a session is marked Distributed: the vault must
+/// hold exactly its starting balance minus what the ledger recorded as paid
+/// this run. Rounding dust is never recorded as paid, so it is expected to be
+/// part of the residual.
+fn reconcile_vault_residual(
+    game_session: &GameSession,
+    starting_balance: u64,
+    remaining_balance: u64,
+    distributed_before: u64,
+) -> Result<()> {
+    let distributed = game_session
+        .total_distributed
+        .checked_sub(distributed_before)
+        .ok_or(WagerError::ArithmeticError)?;
+
+    check_vault_residual(starting_balance, remaining_balance, distributed)
+}
+
+fn check_vault_residual(
+    starting_balance: u64,
+    remaining_balance: u64,
+    distributed: u64,
+) -> Result<()> {
+    let expected_residual = starting_balance
+        .checked_sub(distributed)
+        .ok_or(WagerError::IncompleteDistribution)?;
+
+    require!(
+        remaining_balance == expected_residual,
+        WagerError::IncompleteDistribution
+    );
+
+    msg!("Vault reconciled: {} tokens remain", remaining_balance);
+    Ok(())
+}
+
+/// Computes (position, player, earnings) for every unpaid player owed pay-to-spawn
+/// earnings, along with the total the vault must hold to pay them all
+fn compute_pay_spawn_earnings(
+    game_session: &GameSession,
+) -> Result<(Vec<(usize, Pubkey, u64)>, u64)> {
+    let mut total_distribution_needed = 0u64;
+    let mut player_distributions = Vec::new();
+
+    let all_score_and_spawns = game_session.all_score_and_spawns()?;
+    for (position, (player, score_and_spawns, paid)) in all_score_and_spawns.into_iter().enumerate() {
+        if paid || score_and_spawns == 0 {
+            continue;
+        }
+
+        // SECURITY FIX: Use checked arithmetic to prevent overflow
+        let earnings = score_and_spawns
+            .checked_mul(game_session.session_bet)
+            .and_then(|x| x.checked_div(game_session.earnings_divisor as u64))
+            .ok_or(WagerError::ArithmeticError)?;
+
+        if earnings > 0 {
+            total_distribution_needed = total_distribution_needed
+                .checked_add(earnings)
+                .ok_or(WagerError::ArithmeticError)?;
+
+            player_distributions.push((position, player, earnings));
+        }
+    }
+
+    Ok((player_distributions, total_distribution_needed))
+}
+
+/// Checks remaining_accounts holds exactly one [player, token_account] pair per
+/// player owed a payout, so a short list fails up front instead of per player
+fn check_player_account_count(expected_players: usize, account_count: usize) -> Result<()> {
+    let expected_accounts = expected_players
+        .checked_mul(2)
+        .ok_or(WagerError::ArithmeticError)?;
+
+    if account_count < expected_accounts {
+        msg!("Expected {} remaining accounts for {} players owed earnings, got {}",
+             expected_accounts, expected_players, account_count);
+        return Err(error!(WagerError::MissingPlayerAccounts));
+    }
+
+    require!(
+        account_count == expected_accounts,
+        WagerError::TooManyRemainingAccounts
+    );
+    Ok(())
+}
+
+/// Checks that remaining_accounts[2 * i] is get_all_players()[i] for every player
+fn validate_account_order(players: &[Pubkey], account_keys: &[Pubkey]) -> Result<()> {
+    require!(
+        account_keys.len() == players.len() * 2,
+        WagerError::InvalidRemainingAccounts
+    );
+
+    for (i, player) in players.iter().enumerate() {
+        require!(
+            account_keys[i * 2] == *player,
+            WagerError::InvalidRemainingAccounts
+        );
+    }
+
+    Ok(())
+}
+
+/// SECURITY FIX: Helper function for individual player distribution with validation
+fn process_player_distribution<'info>(
+    ctx: &Context<'_, '_, 'info, 'info, DistributeWinnings<'info>>,
+    position: usize,
+    player: Pubkey,
+    earnings: u64,
+    session_id: &str,
+) -> Result<()> {
+    // Strict layouts index directly by the player's position in get_all_players,
+    // otherwise find the player's account in remaining_accounts
+    let player_index = if ctx.accounts.game_session.strict_account_order {
+        require!(
+            ctx.remaining_accounts
+                .get(position * 2)
+                .map(|acc| acc.key())
+                == Some(player),
+            WagerError::InvalidRemainingAccounts
+        );
+        position
+    } else {
+        ctx.remaining_accounts
+            .iter()
+            .step_by(2)
+            .position(|acc| acc.key() == player)
+            .ok_or(WagerError::InvalidPlayer)?
+    };
+
+    let player_token_account_info = &ctx.remaining_accounts[player_index * 2 + 1];
+
+    // Validate and deserialize player token account
+    let player_token_account = Account::<TokenAccount>::try_from(player_token_account_info)
+        .map_err(|_| error!(WagerError::InvalidPlayerTokenAccount))?;
+
+    // Validate token account ownership
+    require!(
+        player_token_account.owner == player,
+        WagerError::InvalidPlayerTokenAccount
+    );
+
+    ctx.accounts.game_session.check_canonical_token_account(&player, &player_token_account_info.key())?;
+
+    // Validate token mint
+    require!(
+        player_token_account.mint == ctx.accounts.game_session.token_mint,
+        WagerError::InvalidTokenMint
+    );
+
+    // SECURITY FIX: Double-check vault balance before individual transfer
+    let vault_balance = ctx.accounts.vault_token_account.amount;
+    require!(
+        vault_balance >= earnings,
+        WagerError::InsufficientVaultBalance
+    );
+
+    vault_transfer(
+        &ctx.accounts.token_program,
+        &ctx.accounts.vault_token_account,
+        &ctx.accounts.vault,
+        session_id,
+        ctx.accounts.game_session.vault_bump,
+        player_token_account_info,
+        earnings,
+    )?;
+
+    emit!(TokenMoved {
+        session_id: session_id.to_string(),
+        from: ctx.accounts.vault_token_account.key(),
+        to: player_token_account_info.key(),
+        amount: earnings,
+        reason: TokenMoveReason::Distribution as u8,
+    });
+
+    Ok(())
+}
+
+/// SECURITY FIX: Helper function for winner transfer with validation
+fn execute_winner_transfer<'info>(
+    ctx: &Context<'_, '_, 'info, 'info, DistributeWinnings<'info>>,
+    winner_token_account_info: &AccountInfo<'info>,
+    amount: u64,
+    session_id: &str,
+    reason: TokenMoveReason,
+) -> Result<()> {
+    // SECURITY FIX: Double-check vault balance before individual transfer
+    let vault_balance = ctx.accounts.vault_token_account.amount;
+    require!(
+        vault_balance >= amount,
+        WagerError::InsufficientVaultBalance
+    );
+
+    vault_transfer(
+        &ctx.accounts.token_program,
+        &ctx.accounts.vault_token_account,
+        &ctx.accounts.vault,
+        session_id,
+        ctx.accounts.game_session.vault_bump,
+        winner_token_account_info,
+        amount,
+    )?;
+
+    emit!(TokenMoved {
+        session_id: session_id.to_string(),
+        from: ctx.accounts.vault_token_account.key(),
+        to: winner_token_account_info.key(),
+        amount,
+        reason: reason as u8,
+    });
+
+    Ok(())
+}
+
+/// SECURITY FIX: Emergency function to cancel distribution if needed
+pub fn cancel_distribution<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributeWinnings<'info>>,
+    _session_id: String,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    
+    // Only authority can cancel
+    require!(
+        game_session.authority == ctx.accounts.game_server.key(),
+        WagerError::UnauthorizedDistribution
+    );
+
+    // Can only cancel if not yet distributed
+    require!(
+        game_session.status != GameStatus::Distributed,
+        WagerError::AlreadyDistributed
+    );
+
+    game_session.status = GameStatus::Cancelled;
+    
+    msg!("Distribution cancelled by authority");
+    Ok(())
+}
+
+/// Update how an empty winning team is settled (authority only)
+pub fn update_empty_winner_policy_handler(
+    ctx: Context<UpdateDistributionConfig>,
+    _session_id: String,
+    policy: EmptyWinnerPolicy,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    require!(
+        game_session.authority == ctx.accounts.authority.key(),
+        WagerError::UnauthorizedAction
+    );
+
+    game_session.update_empty_winner_policy(policy)?;
+
+    msg!("Empty winner policy updated for session {}", game_session.session_id);
+    Ok(())
+}
+
+/// Toggle strict remaining_accounts ordering for pay-to-spawn distribution (authority only)
+pub fn update_strict_account_order_handler(
+    ctx: Context<UpdateDistributionConfig>,
+    _session_id: String,
+    strict: bool,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    require!(
+        game_session.authority == ctx.accounts.authority.key(),
+        WagerError::UnauthorizedAction
+    );
+
+    require!(
+        game_session.status != GameStatus::Distributed,
+        WagerError::SessionAlreadyFinalized
+    );
+
+    game_session.strict_account_order = strict;
+
+    msg!("Strict account order set to {} for session {}", strict, game_session.session_id);
+    Ok(())
+}
+
+/// Toggle flooring winner payouts to whole tokens (authority only, before the game starts)
+pub fn update_round_to_whole_tokens_handler(
+    ctx: Context<UpdateDistributionConfig>,
+    _session_id: String,
+    enabled: bool,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    game_session.set_round_to_whole_tokens(enabled)?;
+
+    msg!("Round to whole tokens set to {} for session {}", enabled, game_session.session_id);
+    Ok(())
+}
+
+/// Set the share of failed transfers a distribution run tolerates (authority only, before the game starts)
+pub fn update_max_failure_bps_handler(
+    ctx: Context<UpdateDistributionConfig>,
+    _session_id: String,
+    max_failure_bps: u16,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    game_session.set_max_failure_bps(max_failure_bps)?;
+
+    msg!("Max distribution failure set to {} bps for session {}", max_failure_bps, game_session.session_id);
+    Ok(())
+}
+
+/// Returns everything a player is currently owed and which instruction collects it
+pub fn get_claimable_handler(
+    ctx: Context<GetClaimable>,
+    _session_id: String,
+    player: Pubkey,
+) -> Result<()> {
+    let claimable = compute_claimable(&ctx.accounts.game_session, player)?;
+
+    msg!("Claimable for {}: refund {}, winnings {}, hint {}",
+         player, claimable.refund, claimable.winnings, claimable.claim_instruction_hint);
+
+    anchor_lang::solana_program::program::set_return_data(&claimable.try_to_vec()?);
+    Ok(())
+}
+
+/// Works out a player's outstanding refund or winnings from the session status.
+/// Cancelled sessions and draws owe contributions back; resolved games owe the
+/// winners' payouts. Anything already paid out is subtracted.
+fn compute_claimable(game_session: &GameSession, player: Pubkey) -> Result<Claimable> {
+    let (team, index) = game_session.get_player_team_and_index(player)?;
+    let selected_team = match team {
+        0 => &game_session.team_a,
+        _ => &game_session.team_b,
+    };
+    let paid_out = selected_team.player_payouts[index];
+
+    let draw_or_cancelled = game_session.status == GameStatus::Cancelled
+        || (game_session.is_draw
+            && matches!(
+                game_session.status,
+                GameStatus::AwaitingDistribution | GameStatus::Completed | GameStatus::Distributed
+            ));
+    let resolved = matches!(
+        game_session.status,
+        GameStatus::AwaitingDistribution | GameStatus::Completed | GameStatus::Distributed
+    );
+
+    let mut refund = 0u64;
+    let mut winnings = 0u64;
+
+    if draw_or_cancelled {
+        refund = selected_team.player_contributions[index].saturating_sub(paid_out);
+    } else if resolved {
+        let owed = if game_session.is_pay_to_spawn() {
+            game_session
+                .get_score_and_spawns(player)?
+                .checked_mul(game_session.session_bet)
+                .and_then(|x| x.checked_div(game_session.earnings_divisor as u64))
+                .ok_or(WagerError::ArithmeticError)?
+        } else if game_session.game_mode.is_free_for_all() {
+            if game_session.free_for_all_winner() == Some(player) {
+                game_session.total_pot()?
+            } else {
+                0
+            }
+        } else if game_session.winning_team == Some(team) {
+            let share = game_session
+                .winner_shares(team)?
+                .into_iter()
+                .find(|(winner, _)| *winner == player)
+                .map(|(_, share)| share)
+                .unwrap_or(0);
+            game_session.round_payout(share)?.0
+        } else {
+            0
+        };
+        winnings = owed.saturating_sub(paid_out);
+    }
+
+    let hint = if refund > 0 {
+        ClaimHint::Refund
+    } else if winnings > 0 {
+        ClaimHint::Winnings
+    } else {
+        ClaimHint::Nothing
+    };
+
+    Ok(Claimable {
+        refund,
+        winnings,
+        total: refund.checked_add(winnings).ok_or(WagerError::ArithmeticError)?,
+        claim_instruction_hint: hint as u8,
+    })
+}
+
+/// Returns each player's contributed vs paid-out ledger via return data
+pub fn get_settlement_report_handler(
+    ctx: Context<GetSettlementReport>,
+    _session_id: String,
+) -> Result<()> {
+    let game_session = &ctx.accounts.game_session;
+    let report = game_session.get_settlement_report(MAX_SETTLEMENT_REPORT_ENTRIES)?;
+
+    msg!("Settlement report: {} players, total distributed: {}",
+         report.len(), game_session.total_distributed);
+
+    anchor_lang::solana_program::program::set_return_data(&report.try_to_vec()?);
+    Ok(())
+}
+
+/// Returns each player's pay-to-spawn earnings, the total needed and the vault balance
+pub fn compute_earnings_handler(
+    ctx: Context<ComputeEarnings>,
+    _session_id: String,
+) -> Result<()> {
+    let game_session = &ctx.accounts.game_session;
+
+    require!(
+        game_session.is_pay_to_spawn(),
+        WagerError::InvalidGameMode
+    );
+
+    let (player_distributions, total_needed) =
+        compute_pay_spawn_earnings(game_session)?;
+
+    let preview = EarningsPreview {
+        earnings: player_distributions
+            .into_iter()
+            .map(|(_, player, earnings)| (player, earnings))
+            .collect(),
+        total_needed,
+        vault_balance: ctx.accounts.vault_token_account.amount,
+    };
+
+    msg!("Earnings preview: {} players, total needed: {}, vault balance: {}",
+         preview.earnings.len(), preview.total_needed, preview.vault_balance);
+
+    anchor_lang::solana_program::program::set_return_data(&preview.try_to_vec()?);
+    Ok(())
+}
+
+/// Returns the exact (player, amount) table the matching distribute handler
+/// would pay now, with its total and the vault balance, without moving funds
+pub fn simulate_distribution_handler(ctx: Context<ComputeEarnings>, _session_id: String) -> Result<()> {
+    let game_session = &ctx.accounts.game_session;
+    let vault_balance = ctx.accounts.vault_token_account.amount;
+
+    let payouts = simulate_payouts(game_session, vault_balance)?;
+    let total = payouts
+        .iter()
+        .try_fold(0u64, |total, (_, amount)| total.checked_add(*amount))
+        .ok_or(WagerError::ArithmeticError)?;
+
+    let simulation = DistributionSimulation {
+        payouts,
+        total,
+        vault_balance,
+    };
+
+    msg!("Simulated distribution: {} payouts, total {}, vault balance {}",
+         simulation.payouts.len(), simulation.total, simulation.vault_balance);
+
+    anchor_lang::solana_program::program::set_return_data(&simulation.try_to_vec()?);
+    Ok(())
+}
+
+/// Returns what players paid into the vault against what has been paid out,
+/// so an auditor can reconcile the two with the vault balance
+pub fn get_audit_totals_handler(ctx: Context<ComputeEarnings>, _session_id: String) -> Result<()> {
+    let game_session = &ctx.accounts.game_session;
+
+    let totals = AuditTotals {
+        total_collected: game_session.total_collected,
+        total_distributed: game_session.total_distributed,
+        vault_balance: ctx.accounts.vault_token_account.amount,
+    };
+
+    msg!("Audit totals: collected {}, distributed {}, vault balance {}",
+         totals.total_collected, totals.total_distributed, totals.vault_balance);
+
+    anchor_lang::solana_program::program::set_return_data(&totals.try_to_vec()?);
+    Ok(())
+}
+
+/// Mirrors the payout math of each distribution path for a resolved session:
+/// pay-to-spawn earnings, the free-for-all pot, draw refunds, an empty winning
+/// team's settlement, or the winning team's rounded shares. Players already
+/// paid by an earlier run are left out, as a resumed run would skip them.
+fn simulate_payouts(game_session: &GameSession, vault_balance: u64) -> Result<Vec<(Pubkey, u64)>> {
+    require!(
+        game_session.status == GameStatus::AwaitingDistribution,
+        WagerError::InvalidGameState
+    );
+
+    if game_session.is_pay_to_spawn() {
+        let (player_distributions, _) = compute_pay_spawn_earnings(game_session)?;
+        return Ok(player_distributions
+            .into_iter()
+            .map(|(_, player, earnings)| (player, earnings))
+            .collect());
+    }
+
+    if game_session.game_mode.is_free_for_all() {
+        let winner = game_session
+            .free_for_all_winner()
+            .ok_or(WagerError::GameNotResolvable)?;
+        return Ok(vec![(winner, vault_balance)]);
+    }
+
+    if game_session.is_draw {
+        return game_session.get_pending_draw_refunds(MAX_PLAYERS_PER_TEAM * 2);
+    }
+
+    let winning_team = game_session
+        .winning_team
+        .ok_or(WagerError::GameNotResolvable)?;
+
+    if game_session.get_team_roster(winning_team)?.is_empty() {
+        let refunds = game_session.get_outstanding_contributions()?;
+        if game_session.empty_winner_policy == EmptyWinnerPolicy::SendToAuthority
+            || refunds.is_empty()
+        {
+            return Ok(vec![(game_session.authority, vault_balance)]);
+        }
+        return Ok(refunds);
+    }
+
+    let unclaimed_winners = game_session.get_unclaimed_winners(winning_team)?;
+    game_session
+        .winner_shares(winning_team)?
+        .into_iter()
+        .filter(|(winner, _)| unclaimed_winners.contains(winner))
+        .map(|(winner, share)| Ok((winner, game_session.round_payout(share)?.0)))
+        .collect()
+}
+
+/// SECURITY FIX: Function to get distribution summary without executing transfers
+pub fn get_distribution_summary<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributeWinnings<'info>>,
+    _session_id: String,
+) -> Result<()> {
+    let game_session = &ctx.accounts.game_session;
+    
+    if game_session.is_pay_to_spawn() {
+        let mut total_earnings = 0u64;
+        let mut eligible_players = 0u32;
+
+        for (player, score_and_spawns, _) in game_session.all_score_and_spawns()? {
+            if score_and_spawns > 0 {
+                let earnings = score_and_spawns
+                    .checked_mul(game_session.session_bet)
+                    .and_then(|x| x.checked_div(game_session.earnings_divisor as u64))
+                    .unwrap_or(0);
+                
+                if earnings > 0 {
+                    total_earnings = total_earnings.checked_add(earnings).unwrap_or(u64::MAX);
+                    eligible_players += 1;
+                    msg!("Player {} eligible for {} tokens", player, earnings);
+                }
+            }
+        }
+
+        msg!("Pay-to-spawn summary: {} players eligible for total of {} tokens", 
+             eligible_players, total_earnings);
+    } else {
+        let players_per_team = game_session.game_mode.players_per_team();
+        let winning_amount = game_session
+            .winner_payout(players_per_team)
+            .unwrap_or(u64::MAX);
+        let total_distribution = game_session.total_pot()?;
+            
+        msg!("Winner-takes-all summary: {} winners eligible for {} tokens each, total: {} tokens", 
+             players_per_team, winning_amount, total_distribution);
+    }
+
+    let vault_balance = ctx.accounts.vault_token_account.amount;
+    msg!("Current vault balance: {}", vault_balance);
+    
+    Ok(())
+}
+
+/// SECURITY FIX: Enhanced account validation structure
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct DistributeWinnings<'info> {
+    /// The game server authority that created the session
+    pub game_server: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+        constraint = game_session.authority == game_server.key() @ WagerError::UnauthorizedDistribution,
+        constraint = session_id.len() <= 32 @ WagerError::SessionIdTooLong,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: Vault PDA that holds the funds
     #[account(
         mut,
         seeds = [b"vault", session_id.as_bytes()],
@@ -500,7 +1364,7 @@ pub struct DistributeWinnings<'info> {
 
     #[account(
         mut,
-        associated_token::mint = TOKEN_ID,
+        associated_token::mint = game_session.token_mint,
         associated_token::authority = vault,
         constraint = vault_token_account.amount > 0 @ WagerError::EmptyVault,
     )]
@@ -511,6 +1375,111 @@ pub struct DistributeWinnings<'info> {
     pub system_program: Program<'info, System>,
 }
 
+/// Account structure for settlement report queries
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct GetSettlementReport<'info> {
+    #[account(
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+    )]
+    pub game_session: Account<'info, GameSession>,
+}
+
+/// Account structure for claimable amount queries
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct GetClaimable<'info> {
+    #[account(
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+    )]
+    pub game_session: Account<'info, GameSession>,
+}
+
+/// View that fails with InsufficientVaultBalance if the vault holds less than
+/// the contributions not yet paid out
+pub fn assert_vault_covers_contributions_handler(
+    ctx: Context<ComputeEarnings>,
+    _session_id: String,
+) -> Result<()> {
+    let game_session = &ctx.accounts.game_session;
+    let vault_balance = ctx.accounts.vault_token_account.amount;
+
+    msg!("Vault balance {}, required {}", vault_balance, game_session.required_vault_balance()?);
+    game_session.assert_vault_covers_contributions(vault_balance)
+}
+
+/// Returns the session's SessionHealth via return data for alerting systems
+pub fn get_health_handler(ctx: Context<ComputeEarnings>, _session_id: String) -> Result<()> {
+    let clock = Clock::get()?;
+    let health = ctx
+        .accounts
+        .game_session
+        .health(ctx.accounts.vault_token_account.amount, clock.unix_timestamp)?;
+
+    msg!("Health: solvent {}, drift {}, headroom {}, settled {}",
+         health.solvent, health.vault_drift, health.headroom, health.fully_settled);
+
+    anchor_lang::solana_program::program::set_return_data(&health.try_to_vec()?);
+    Ok(())
+}
+
+/// Returns the vault's balance, the balance the ledger expects and the session
+/// status via return data, so clients can spot discrepancies in one call
+pub fn get_vault_balance_handler(ctx: Context<ComputeEarnings>, _session_id: String) -> Result<()> {
+    let view = ctx
+        .accounts
+        .game_session
+        .vault_balance(ctx.accounts.vault_token_account.amount)?;
+
+    msg!("Vault balance {}, expected {}", view.balance, view.expected);
+
+    anchor_lang::solana_program::program::set_return_data(&view.try_to_vec()?);
+    Ok(())
+}
+
+/// Account structure for pay-to-spawn earnings previews
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct ComputeEarnings<'info> {
+    #[account(
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: Vault PDA that holds the funds
+    #[account(
+        seeds = [b"vault", session_id.as_bytes()],
+        bump = game_session.vault_bump,
+    )]
+    pub vault: AccountInfo<'info>,
+
+    #[account(
+        associated_token::mint = game_session.token_mint,
+        associated_token::authority = vault,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+}
+
+/// Account structure for updating distribution configuration
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct UpdateDistributionConfig<'info> {
+    #[account(
+        constraint = authority.key() == game_session.authority @ WagerError::UnauthorizedAction,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+    )]
+    pub game_session: Account<'info, GameSession>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -522,7 +1491,7 @@ mod tests {
         
         let earnings = (kills_and_spawns as u64)
             .checked_mul(session_bet)
-            .and_then(|x| x.checked_div(EARNINGS_DIVISOR))
+            .and_then(|x| x.checked_div(DEFAULT_EARNINGS_DIVISOR as u64))
             .unwrap();
             
         assert_eq!(earnings, 1500); // 15 * 1000 / 10 = 1500
@@ -535,11 +1504,419 @@ mod tests {
         
         let earnings = (kills_and_spawns as u64)
             .checked_mul(session_bet)
-            .and_then(|x| x.checked_div(EARNINGS_DIVISOR));
+            .and_then(|x| x.checked_div(DEFAULT_EARNINGS_DIVISOR as u64));
             
         assert!(earnings.is_none()); // Should overflow and return None
     }
 
+    #[test]
+    fn test_vault_residual_reconciles_correct_drain() {
+        // 2000 pot, 1990 paid out, 10 rounding dust left behind
+        assert!(check_vault_residual(2_000, 10, 1_990).is_ok());
+        assert!(check_vault_residual(2_000, 0, 2_000).is_ok());
+    }
+
+    #[test]
+    fn test_vault_residual_flags_unexpectedly_high_residual() {
+        // The ledger says 2000 was paid but 500 is still sitting in the vault
+        assert!(check_vault_residual(2_000, 500, 2_000).is_err());
+
+        // Or the vault gave out more than the ledger recorded
+        assert!(check_vault_residual(2_000, 0, 1_500).is_err());
+        assert!(check_vault_residual(1_000, 0, 2_000).is_err());
+    }
+
+    #[test]
+    fn test_vault_drain_verification_catches_under_transfer() {
+        assert!(verify_vault_drain(1_000, 400, 600).is_ok());
+
+        // The handler counted 600 as paid but only 500 left the vault
+        assert!(verify_vault_drain(1_000, 500, 600).is_err());
+
+        // A vault that grew can never match a payout
+        assert!(verify_vault_drain(1_000, 1_100, 0).is_err());
+    }
+
+    #[test]
+    fn test_short_player_account_list_rejected() {
+        // Three players owed earnings need six accounts
+        assert!(check_player_account_count(3, 6).is_ok());
+        assert_eq!(
+            check_player_account_count(3, 4).unwrap_err(),
+            error!(WagerError::MissingPlayerAccounts)
+        );
+        assert!(check_player_account_count(3, 0).is_err());
+        assert_eq!(
+            check_player_account_count(3, 8).unwrap_err(),
+            error!(WagerError::TooManyRemainingAccounts)
+        );
+
+        // Nobody owed anything needs no accounts
+        assert!(check_player_account_count(0, 0).is_ok());
+    }
+
+    #[test]
+    fn test_strict_account_order_accepts_matching_layout() {
+        let players = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        let account_keys = vec![
+            players[0],
+            Pubkey::new_unique(),
+            players[1],
+            Pubkey::new_unique(),
+        ];
+
+        assert!(validate_account_order(&players, &account_keys).is_ok());
+    }
+
+    #[test]
+    fn test_strict_account_order_rejects_misordered_layout() {
+        let players = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+
+        // Players swapped relative to get_all_players
+        let swapped = vec![
+            players[1],
+            Pubkey::new_unique(),
+            players[0],
+            Pubkey::new_unique(),
+        ];
+        assert!(validate_account_order(&players, &swapped).is_err());
+
+        // Missing the second player's pair
+        let truncated = vec![players[0], Pubkey::new_unique()];
+        assert!(validate_account_order(&players, &truncated).is_err());
+    }
+
+    #[test]
+    fn test_compute_earnings_matches_manual_calculation() {
+        let mut session = GameSession::new(
+            "test".to_string(),
+            Pubkey::new_unique(),
+            1000,
+            GameMode::PayToSpawnOneVsOne,
+            1000,
+            1,
+            2,
+        ).unwrap();
+        let player_a = Pubkey::new_unique();
+        let player_b = Pubkey::new_unique();
+        session.team_a.players[0] = player_a;
+        session.team_b.players[0] = player_b;
+        session.team_a.player_kills[0] = 4;
+        session.team_a.player_score[0] = 4;
+        session.team_a.player_spawns[0] = 6;
+        session.team_b.player_kills[0] = 1;
+        session.team_b.player_score[0] = 1;
+        session.team_b.player_spawns[0] = 2;
+
+        let (distributions, total_needed) =
+            compute_pay_spawn_earnings(&session).unwrap();
+        let earnings: Vec<(Pubkey, u64)> = distributions
+            .into_iter()
+            .map(|(_, player, earnings)| (player, earnings))
+            .collect();
+
+        // (score + spawns) * session_bet / earnings_divisor
+        assert_eq!(earnings, vec![(player_a, 1000), (player_b, 300)]);
+        assert_eq!(total_needed, 1300);
+
+        // Halving the divisor doubles every payout for the same kills and spawns
+        session.set_earnings_divisor(5).unwrap();
+        let (distributions, total_needed) =
+            compute_pay_spawn_earnings(&session).unwrap();
+        let earnings: Vec<u64> = distributions
+            .into_iter()
+            .map(|(_, _, earnings)| earnings)
+            .collect();
+        assert_eq!(earnings, vec![2000, 600]);
+        assert_eq!(total_needed, 2600);
+    }
+
+    #[test]
+    fn test_min_kills_to_earn_excludes_passive_player() {
+        let mut session = GameSession::new(
+            "test".to_string(),
+            Pubkey::new_unique(),
+            1000,
+            GameMode::PayToSpawnOneVsOne,
+            1000,
+            1,
+            2,
+        ).unwrap();
+        let player_a = Pubkey::new_unique();
+        let player_b = Pubkey::new_unique();
+        session.team_a.players[0] = player_a;
+        session.team_b.players[0] = player_b;
+        session.team_a.player_kills[0] = 2;
+        session.team_a.player_score[0] = 2;
+        session.team_a.player_spawns[0] = 3;
+        // Bought spawns but never scored
+        session.team_b.player_spawns[0] = 8;
+
+        let (distributions, _) = compute_pay_spawn_earnings(&session).unwrap();
+        assert_eq!(distributions.len(), 2);
+
+        session.set_min_kills_to_earn(1).unwrap();
+        let (distributions, total_needed) = compute_pay_spawn_earnings(&session).unwrap();
+        let earnings: Vec<(Pubkey, u64)> = distributions
+            .into_iter()
+            .map(|(_, player, earnings)| (player, earnings))
+            .collect();
+        assert_eq!(earnings, vec![(player_a, 500)]);
+        assert_eq!(total_needed, 500);
+        assert_eq!(session.get_score_and_spawns(player_b).unwrap(), 0);
+
+        session.status = GameStatus::InProgress;
+        assert!(session.set_min_kills_to_earn(0).is_err());
+    }
+
+    #[test]
+    fn test_earnings_divisor_bounds() {
+        let mut session = GameSession::new(
+            "test".to_string(),
+            Pubkey::new_unique(),
+            1000,
+            GameMode::PayToSpawnOneVsOne,
+            1000,
+            1,
+            2,
+        ).unwrap();
+        assert_eq!(session.earnings_divisor, DEFAULT_EARNINGS_DIVISOR);
+
+        assert!(session.set_earnings_divisor(0).is_err());
+        assert!(session.set_earnings_divisor(MAX_EARNINGS_DIVISOR + 1).is_err());
+        session.set_earnings_divisor(1).unwrap();
+        session.set_earnings_divisor(MAX_EARNINGS_DIVISOR).unwrap();
+        assert_eq!(session.earnings_divisor, MAX_EARNINGS_DIVISOR);
+    }
+
+    #[test]
+    fn test_resume_after_partial_failure_pays_remaining_players() {
+        let mut session = GameSession::new(
+            "test".to_string(),
+            Pubkey::new_unique(),
+            1000,
+            GameMode::PayToSpawnThreeVsThree,
+            1000,
+            1,
+            2,
+        ).unwrap();
+        let players: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        for (slot, player) in players.iter().enumerate() {
+            session.seat_player(0, slot, *player, 1000).unwrap();
+            session.team_a.player_kills[slot] = 1;
+            session.team_a.player_score[slot] = 1;
+        }
+        session.status = GameStatus::AwaitingDistribution;
+        assert!(!session.has_paid_earnings());
+
+        // The first run paid player 0 and failed on the other two
+        let (first_run, _) = compute_pay_spawn_earnings(&session).unwrap();
+        assert_eq!(first_run.len(), 3);
+        session.record_payout(players[0], first_run[0].2).unwrap();
+        session.mark_earnings_paid(players[0]).unwrap();
+        assert!(session.has_paid_earnings());
+        assert!(session.mark_earnings_paid(players[0]).is_err());
+
+        // The resumed run only owes the two unpaid players
+        let (resumed, total_needed) = compute_pay_spawn_earnings(&session).unwrap();
+        let resumed_players: Vec<Pubkey> = resumed.iter().map(|(_, player, _)| *player).collect();
+        assert_eq!(resumed_players, players[1..].to_vec());
+        assert_eq!(total_needed, first_run[1].2 + first_run[2].2);
+
+        for (_, player, _) in &resumed {
+            session.mark_earnings_paid(*player).unwrap();
+        }
+        let (remaining, _) = compute_pay_spawn_earnings(&session).unwrap();
+        assert!(remaining.is_empty());
+    }
+
+    fn three_vs_three_session() -> GameSession {
+        let mut session = GameSession::new(
+            "test".to_string(),
+            Pubkey::new_unique(),
+            1000,
+            GameMode::WinnerTakesAllThreeVsThree,
+            1000,
+            1,
+            2,
+        ).unwrap();
+        for slot in 0..3 {
+            session.seat_player(0, slot, Pubkey::new_unique(), 1000).unwrap();
+            session.seat_player(1, slot, Pubkey::new_unique(), 1000).unwrap();
+        }
+        session.status = GameStatus::InProgress;
+        session
+    }
+
+    #[test]
+    fn test_simulated_winner_payouts_match_distribution() {
+        let mut session = three_vs_three_session();
+        assert!(simulate_payouts(&session, 6000).is_err());
+        session.mark_resolved(Some(0)).unwrap();
+
+        let simulated = simulate_payouts(&session, 6000).unwrap();
+        let winners: Vec<Pubkey> = session.team_a.players[..3].to_vec();
+        assert_eq!(
+            simulated.iter().map(|(player, _)| *player).collect::<Vec<_>>(),
+            winners
+        );
+
+        // Pay the first winner as a partially failed run would have
+        let (paid, amount) = simulated[0];
+        session.record_payout(paid, amount).unwrap();
+        session.mark_claimed(paid).unwrap();
+
+        // The resumed run owes exactly the rest of the simulated table
+        let resumed = simulate_payouts(&session, 6000 - amount).unwrap();
+        assert_eq!(resumed, simulated[1..].to_vec());
+        for (player, amount) in &resumed {
+            session.record_payout(*player, *amount).unwrap();
+            session.mark_claimed(*player).unwrap();
+        }
+        assert_eq!(session.total_distributed, 6000);
+        assert_eq!(simulated.iter().map(|(_, amount)| amount).sum::<u64>(), 6000);
+    }
+
+    #[test]
+    fn test_simulated_draw_and_earnings_match_distribution() {
+        let mut session = three_vs_three_session();
+        session.mark_resolved(None).unwrap();
+        let simulated = simulate_payouts(&session, 6000).unwrap();
+        assert_eq!(simulated, session.settle_as_draw().unwrap());
+
+        let mut session = GameSession::new(
+            "test".to_string(),
+            Pubkey::new_unique(),
+            1000,
+            GameMode::PayToSpawnOneVsOne,
+            1000,
+            1,
+            2,
+        ).unwrap();
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.seat_player(1, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.team_a.player_kills[0] = 2;
+        session.team_a.player_score[0] = 2;
+        session.status = GameStatus::AwaitingDistribution;
+
+        let (earnings, total_needed) = compute_pay_spawn_earnings(&session).unwrap();
+        let simulated = simulate_payouts(&session, 2000).unwrap();
+        assert_eq!(
+            simulated,
+            earnings.into_iter().map(|(_, player, amount)| (player, amount)).collect::<Vec<_>>()
+        );
+        assert_eq!(simulated.iter().map(|(_, amount)| amount).sum::<u64>(), total_needed);
+    }
+
+    #[test]
+    fn test_empty_winning_team_refunds_other_team() {
+        let mut session = GameSession::new(
+            "test".to_string(),
+            Pubkey::new_unique(),
+            1000,
+            GameMode::WinnerTakesAllOneVsOne,
+            1000,
+            1,
+            2,
+        ).unwrap();
+        let survivor = Pubkey::new_unique();
+        session.seat_player(1, 0, survivor, 1000).unwrap();
+        session.status = GameStatus::InProgress;
+
+        // Team 0 is declared the winner but has nobody left in it
+        session.mark_resolved(Some(0)).unwrap();
+        assert!(session.get_team_roster(0).unwrap().is_empty());
+        assert!(session.winner_shares(0).is_err());
+
+        // The populated team is refunded, not awarded the pot
+        assert_eq!(simulate_payouts(&session, 1000).unwrap(), vec![(survivor, 1000)]);
+
+        // The policy is fixed before anyone joins, so flip it directly here
+        session.empty_winner_policy = EmptyWinnerPolicy::SendToAuthority;
+        assert_eq!(
+            simulate_payouts(&session, 1000).unwrap(),
+            vec![(session.authority, 1000)]
+        );
+    }
+
+    fn one_vs_one_session() -> GameSession {
+        let mut session = GameSession::new(
+            "test".to_string(),
+            Pubkey::new_unique(),
+            1000,
+            GameMode::WinnerTakesAllOneVsOne,
+            1000,
+            1,
+            2,
+        ).unwrap();
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.seat_player(1, 0, Pubkey::new_unique(), 1000).unwrap();
+        session
+    }
+
+    #[test]
+    fn test_claimable_refund_for_cancelled_session() {
+        let mut session = one_vs_one_session();
+        let player = session.team_a.players[0];
+        session.status = GameStatus::Cancelled;
+
+        let claimable = compute_claimable(&session, player).unwrap();
+        assert_eq!(claimable.refund, 1000);
+        assert_eq!(claimable.winnings, 0);
+        assert_eq!(claimable.total, 1000);
+        assert_eq!(claimable.claim_instruction_hint, ClaimHint::Refund as u8);
+    }
+
+    #[test]
+    fn test_claimable_unpaid_winnings_after_distribution() {
+        let mut session = one_vs_one_session();
+        let winner = session.team_a.players[0];
+        let loser = session.team_b.players[0];
+        session.status = GameStatus::InProgress;
+        session.mark_resolved(Some(0)).unwrap();
+        session.status = GameStatus::Distributed;
+
+        // The winner has not been paid yet
+        let claimable = compute_claimable(&session, winner).unwrap();
+        assert_eq!(claimable.refund, 0);
+        assert_eq!(claimable.winnings, 2000);
+        assert_eq!(claimable.claim_instruction_hint, ClaimHint::Winnings as u8);
+
+        let claimable = compute_claimable(&session, loser).unwrap();
+        assert_eq!(claimable.total, 0);
+        assert_eq!(claimable.claim_instruction_hint, ClaimHint::Nothing as u8);
+
+        // Once paid, nothing is left to claim
+        session.record_payout(winner, 2000).unwrap();
+        assert_eq!(compute_claimable(&session, winner).unwrap().total, 0);
+    }
+
+    #[test]
+    fn test_pay_spawn_distribution_requires_completed_match() {
+        let mut session = GameSession::new(
+            "test".to_string(),
+            Pubkey::new_unique(),
+            1000,
+            GameMode::PayToSpawnOneVsOne,
+            1000,
+            1,
+            2,
+        ).unwrap();
+        session.status = GameStatus::InProgress;
+
+        // Mid-match distribution is rejected
+        assert_eq!(
+            validate_pay_spawn_distribution(&session).unwrap_err(),
+            error!(WagerError::InvalidGameState)
+        );
+
+        session.end_match().unwrap();
+        assert!(validate_pay_spawn_distribution(&session).is_ok());
+
+        // Spawn purchases require an in-progress match
+        assert!(session.status != GameStatus::InProgress);
+    }
+
     #[test]
     fn test_winner_amount_calculation() {
         let session_bet = 1000u64;
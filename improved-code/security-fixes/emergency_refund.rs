@@ -0,0 +1,309 @@
+use crate::{
+    errors::WagerError,
+    events::*,
+    instructions::{vault_transfer, verify_vault_drain, UpdateSessionConfig},
+    state::*,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Token, TokenAccount};
+
+/// Prove the authority is still active, pushing back its liveness deadline (authority only)
+pub fn heartbeat_handler(ctx: Context<UpdateSessionConfig>, _session_id: String) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    let clock = Clock::get()?;
+
+    game_session.record_heartbeat(clock.unix_timestamp)?;
+
+    msg!("Heartbeat for session {}, liveness deadline now {}",
+         game_session.session_id, game_session.authority_liveness_deadline());
+    Ok(())
+}
+
+/// Update how long the authority may stay silent before emergency refunds open (authority only)
+pub fn update_liveness_window_handler(
+    ctx: Context<UpdateSessionConfig>,
+    _session_id: String,
+    seconds: i64,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    game_session.update_liveness_window(seconds)?;
+
+    msg!("Session {} liveness window set to {} seconds", game_session.session_id, seconds);
+    Ok(())
+}
+
+/// Permissionless refund of every player's outstanding contributions once the
+/// authority has been silent past its liveness window. remaining_accounts holds
+/// [player, player_token_account] pairs in get_all_players order.
+pub fn emergency_refund_all_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, EmergencyRefundAll<'info>>,
+    session_id: String,
+) -> Result<()> {
+    let game_session = &ctx.accounts.game_session;
+    let clock = Clock::get()?;
+
+    require!(
+        game_session.status != GameStatus::Distributed,
+        WagerError::SessionAlreadyFinalized
+    );
+
+    require!(
+        game_session.is_authority_silent(clock.unix_timestamp),
+        WagerError::AuthorityStillLive
+    );
+
+    // SECURITY FIX: Catch any external drain before moving funds
+    game_session.assert_vault_covers_contributions(ctx.accounts.vault_token_account.amount)?;
+
+    let refunds = game_session.get_outstanding_contributions()?;
+    require!(
+        ctx.remaining_accounts.len() == refunds.len() * 2,
+        WagerError::InvalidRemainingAccounts
+    );
+
+    let total_refund = refunds
+        .iter()
+        .try_fold(0u64, |total, (_, amount)| total.checked_add(*amount))
+        .ok_or(WagerError::ArithmeticError)?;
+
+    require!(
+        ctx.accounts.vault_token_account.amount >= total_refund,
+        WagerError::InsufficientVaultBalance
+    );
+
+    let vault_balance_before = ctx.accounts.vault_token_account.amount;
+    msg!("Authority silent since {}, refunding {} tokens to {} players",
+         game_session.last_heartbeat, total_refund, refunds.len());
+
+    for (i, (player, amount)) in refunds.iter().enumerate() {
+        let player_account = &ctx.remaining_accounts[i * 2];
+        let player_token_account_info = &ctx.remaining_accounts[i * 2 + 1];
+
+        require!(
+            player_account.key() == *player,
+            WagerError::InvalidPlayer
+        );
+
+        let player_token_account = Account::<TokenAccount>::try_from(player_token_account_info)
+            .map_err(|_| error!(WagerError::InvalidPlayerTokenAccount))?;
+
+        require!(
+            player_token_account.owner == *player,
+            WagerError::InvalidPlayerTokenAccount
+        );
+
+        require!(
+            player_token_account.mint == game_session.token_mint,
+            WagerError::InvalidTokenMint
+        );
+
+        if *amount == 0 {
+            continue;
+        }
+
+        vault_transfer(
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault_token_account,
+            &ctx.accounts.vault,
+            &session_id,
+            game_session.vault_bump,
+            player_token_account_info,
+            *amount,
+        )?;
+
+        emit!(TokenMoved {
+            session_id: session_id.clone(),
+            from: ctx.accounts.vault_token_account.key(),
+            to: player_token_account_info.key(),
+            amount: *amount,
+            reason: TokenMoveReason::Refund as u8,
+        });
+
+        msg!("Emergency refunded {} tokens to player {}", amount, player);
+    }
+
+    ctx.accounts.vault_token_account.reload()?;
+    verify_vault_drain(vault_balance_before, ctx.accounts.vault_token_account.amount, total_refund)?;
+
+    let game_session = &mut ctx.accounts.game_session;
+    for (player, amount) in &refunds {
+        game_session.record_payout(*player, *amount)?;
+    }
+    game_session.status = GameStatus::Distributed;
+
+    msg!("Emergency refund completed for abandoned session {}", session_id);
+    Ok(())
+}
+
+/// Lets the program admin settle a hung session in any status except
+/// Distributed. remaining_accounts holds [player, player_token_account] pairs
+/// in the order returned by GameSession::force_settle_payouts.
+pub fn admin_force_settle_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AdminForceSettle<'info>>,
+    session_id: String,
+    resolution: ForceSettleResolution,
+) -> Result<()> {
+    let game_session = &ctx.accounts.game_session;
+
+    let payouts = game_session.force_settle_payouts(resolution)?;
+    require!(
+        ctx.remaining_accounts.len() == payouts.len() * 2,
+        WagerError::InvalidRemainingAccounts
+    );
+
+    let total_paid = payouts
+        .iter()
+        .try_fold(0u64, |total, (_, amount)| total.checked_add(*amount))
+        .ok_or(WagerError::ArithmeticError)?;
+
+    let vault_balance_before = ctx.accounts.vault_token_account.amount;
+    require!(
+        vault_balance_before >= total_paid,
+        WagerError::InsufficientVaultBalance
+    );
+
+    let reason = match resolution {
+        ForceSettleResolution::RefundAll => TokenMoveReason::Refund,
+        _ => TokenMoveReason::Distribution,
+    };
+
+    for (i, (player, amount)) in payouts.iter().enumerate() {
+        let player_account = &ctx.remaining_accounts[i * 2];
+        let player_token_account_info = &ctx.remaining_accounts[i * 2 + 1];
+
+        require!(
+            player_account.key() == *player,
+            WagerError::InvalidPlayer
+        );
+
+        let player_token_account = Account::<TokenAccount>::try_from(player_token_account_info)
+            .map_err(|_| error!(WagerError::InvalidPlayerTokenAccount))?;
+
+        require!(
+            player_token_account.owner == *player,
+            WagerError::InvalidPlayerTokenAccount
+        );
+
+        require!(
+            player_token_account.mint == game_session.token_mint,
+            WagerError::InvalidTokenMint
+        );
+
+        if *amount == 0 {
+            continue;
+        }
+
+        vault_transfer(
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault_token_account,
+            &ctx.accounts.vault,
+            &session_id,
+            game_session.vault_bump,
+            player_token_account_info,
+            *amount,
+        )?;
+
+        emit!(TokenMoved {
+            session_id: session_id.clone(),
+            from: ctx.accounts.vault_token_account.key(),
+            to: player_token_account_info.key(),
+            amount: *amount,
+            reason: reason as u8,
+        });
+    }
+
+    ctx.accounts.vault_token_account.reload()?;
+    verify_vault_drain(vault_balance_before, ctx.accounts.vault_token_account.amount, total_paid)?;
+
+    let game_session = &mut ctx.accounts.game_session;
+    for (player, amount) in &payouts {
+        game_session.record_payout(*player, *amount)?;
+    }
+    game_session.mark_force_settled(resolution)?;
+
+    emit!(AdminForceSettled {
+        session_id: session_id.clone(),
+        admin: ctx.accounts.admin.key(),
+        resolution,
+        total_paid,
+    });
+
+    msg!("Session {} force-settled by admin, {} tokens paid", session_id, total_paid);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct EmergencyRefundAll<'info> {
+    /// Anyone may trigger refunds once the authority has gone silent
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: Vault PDA that holds the funds
+    #[account(
+        mut,
+        seeds = [b"vault", session_id.as_bytes()],
+        bump = game_session.vault_bump,
+    )]
+    pub vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = game_session.token_mint,
+        associated_token::authority = vault,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct AdminForceSettle<'info> {
+    #[account(
+        constraint = admin.key() == global_config.admin @ WagerError::UnauthorizedAdmin,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: Vault PDA that holds the funds
+    #[account(
+        mut,
+        seeds = [b"vault", session_id.as_bytes()],
+        bump = game_session.vault_bump,
+    )]
+    pub vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = game_session.token_mint,
+        associated_token::authority = vault,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
@@ -137,4 +137,145 @@ pub enum WagerError {
 
     #[msg("Concurrent modification detected")]
     ConcurrentModificationDetected,
+
+    #[msg("Game cannot be resolved until a team is eliminated or the session expires")]
+    GameNotResolvable,
+
+    #[msg("Minimum time before start has not elapsed")]
+    TooEarlyToStart,
+
+    #[msg("Invalid authority")]
+    InvalidAuthority,
+
+    #[msg("Player has not delegated enough tokens to the game server")]
+    InsufficientDelegation,
+
+    #[msg("Spawn cost scale exceeds the allowed maximum")]
+    InvalidSpawnCostScale,
+
+    #[msg("Token account is frozen")]
+    FrozenTokenAccount,
+
+    #[msg("Authority is still within its liveness window")]
+    AuthorityStillLive,
+
+    #[msg("Invalid liveness window")]
+    InvalidLivenessWindow,
+
+    #[msg("Leave penalty exceeds the allowed maximum")]
+    InvalidLeavePenalty,
+
+    #[msg("Allowed mint list is full")]
+    TooManyAllowedMints,
+
+    #[msg("Only the config admin can perform this action")]
+    UnauthorizedAdmin,
+
+    #[msg("Spectator bet has already been claimed")]
+    SpectatorBetAlreadyClaimed,
+
+    #[msg("Nothing to claim")]
+    NothingToClaim,
+
+    #[msg("Deposit would push the vault above its cap")]
+    VaultCapExceeded,
+
+    #[msg("Series rounds needed must be between 1 and the maximum")]
+    InvalidSeriesRounds,
+
+    #[msg("Series has already been decided")]
+    SeriesAlreadyDecided,
+
+    #[msg("Series has not been decided yet")]
+    SeriesNotDecided,
+
+    #[msg("Session cannot be used as this series round")]
+    SeriesRoundMismatch,
+
+    #[msg("Series rounds are paid out through the series")]
+    SeriesRoundDistribution,
+
+    #[msg("Session already exists with different parameters")]
+    SessionConflict,
+
+    #[msg("Kill weight must be between 1 and the maximum")]
+    InvalidKillWeight,
+
+    #[msg("Authority session index is full")]
+    SessionIndexFull,
+
+    #[msg("Earnings divisor must be between 1 and the maximum")]
+    InvalidEarningsDivisor,
+
+    #[msg("Purchase would leave projected earnings above the vault balance")]
+    ProjectedInsolvency,
+
+    #[msg("Session is frozen by its authority")]
+    SessionFrozen,
+
+    #[msg("Game session has not expired yet")]
+    SessionNotExpired,
+
+    #[msg("Kills on teammates are not allowed in this session")]
+    FriendlyFireNotAllowed,
+
+    #[msg("Session has no auto-start time")]
+    AutoStartNotConfigured,
+
+    #[msg("Player was not invited to this challenge")]
+    NotInvited,
+
+    #[msg("Walkover needs one full team and one empty team")]
+    NotAWalkover,
+
+    #[msg("Spawn configuration is locked once the game has started")]
+    ConfigLockedError,
+
+    #[msg("This kill is already awaiting the victim's acknowledgement")]
+    PendingKillExists,
+
+    #[msg("No pending kill for this killer and victim")]
+    PendingKillNotFound,
+
+    #[msg("Too many kills are awaiting acknowledgement")]
+    TooManyPendingKills,
+
+    #[msg("A kill batch must hold between 1 and MAX_KILLS_PER_BATCH kills")]
+    InvalidKillBatch,
+
+    #[msg("The session's mint can only change while its vault is empty")]
+    VaultNotEmpty,
+
+    #[msg("The lobby is full and locked; players cannot leave yet")]
+    LobbyLockedError,
+
+    #[msg("Lobby lock must be between 0 and MAX_LOBBY_LOCK_SECONDS")]
+    InvalidLobbyLock,
+
+    #[msg("Only the team captain can kick, and not themselves")]
+    NotTeamCaptain,
+
+    #[msg("Failure threshold cannot exceed 10000 bps")]
+    InvalidFailureThreshold,
+
+    #[msg("Joining this team would leave the teams unbalanced")]
+    TeamImbalance,
+
+    #[msg("Top-up exceeds the vault's projected shortfall")]
+    TopUpExceedsShortfall,
+
+    #[msg("remaining_accounts is missing a pair for a player owed earnings")]
+    MissingPlayerAccounts,
+
+    #[msg("Session is not wagered in the currency this instruction handles")]
+    CurrencyMismatch,
+
+    #[msg("Token account is not the owner's associated token account")]
+    NonCanonicalTokenAccount,
+
+    #[msg("Player has bought the most spawns allowed this session")]
+    LifetimeSpawnsExceeded,
+
+    #[msg("Series can only be refunded by its authority or once its current round has expired")]
+    SeriesStillActive,
 }
\ No newline at end of file
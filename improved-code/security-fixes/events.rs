@@ -0,0 +1,51 @@
+//! Events emitted by the betting program
+use crate::state::ForceSettleResolution;
+use anchor_lang::prelude::*;
+
+/// Why tokens moved, carried as `TokenMoved::reason`
+#[derive(Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum TokenMoveReason {
+    Join = 0,            // Player stake into the vault
+    PayToSpawn = 1,      // Spawn purchase into the vault
+    Distribution = 2,    // Winnings or earnings out of the vault
+    Refund = 3,          // Stake returned to a player
+    Sweep = 4,           // Residual vault balance swept out
+    SpectatorBet = 5,    // Spectator stake into the spectator vault
+    SpectatorPayout = 6, // Spectator winnings or refund out of the spectator vault
+    Bonus = 7,           // House bonus from the authority into the vault
+}
+
+/// Emitted when an in-progress game is resolved; `winning_team` is None on a draw
+#[event]
+pub struct GameResolved {
+    pub session_id: String,
+    pub winning_team: Option<u8>,
+}
+
+/// Emitted when a session's game server authority is handed off
+#[event]
+pub struct AuthorityTransferred {
+    pub session_id: String,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+/// Emitted when a program admin force-settles a hung session
+#[event]
+pub struct AdminForceSettled {
+    pub session_id: String,
+    pub admin: Pubkey,
+    pub resolution: ForceSettleResolution,
+    pub total_paid: u64,
+}
+
+/// Emitted for every token transfer so the money trail can be audited on-chain
+#[event]
+pub struct TokenMoved {
+    pub session_id: String,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+    pub reason: u8,
+}
@@ -0,0 +1,114 @@
+use crate::{errors::WagerError, events::*, instructions::ComputeEarnings, state::*};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::spl_token::state::AccountState;
+use anchor_spl::token::{Token, TokenAccount};
+
+/// Tops up the vault with house bonus tokens for a promotional match. The
+/// bonus is split among the winners on top of the player bets. An in-progress
+/// pay-to-spawn vault can instead be topped up by at most its required_topup
+/// to stay solvent (authority only)
+pub fn fund_vault_handler(ctx: Context<FundVault>, session_id: String, amount: u64) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    let vault_balance_before = ctx.accounts.vault_token_account.amount;
+
+    if game_session.is_pay_to_spawn() {
+        game_session.add_topup(amount, vault_balance_before)?;
+    } else {
+        game_session.add_bonus(amount)?;
+    }
+
+    let expected_vault_balance = vault_balance_before
+        .checked_add(amount)
+        .ok_or(WagerError::ArithmeticError)?;
+    game_session.check_vault_cap(expected_vault_balance)?;
+
+    anchor_spl::token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: ctx.accounts.authority_token_account.to_account_info(),
+                to: ctx.accounts.vault_token_account.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    // SECURITY FIX: Verify the bonus actually arrived
+    ctx.accounts.vault_token_account.reload()?;
+    require!(
+        ctx.accounts.vault_token_account.amount == expected_vault_balance,
+        WagerError::TransferVerificationFailed
+    );
+
+    emit!(TokenMoved {
+        session_id: session_id.clone(),
+        from: ctx.accounts.authority_token_account.key(),
+        to: ctx.accounts.vault_token_account.key(),
+        amount,
+        reason: TokenMoveReason::Bonus as u8,
+    });
+
+    msg!("Authority added {} bonus tokens to session {}, bonus pool now {}",
+         amount, session_id, ctx.accounts.game_session.bonus_pool);
+    Ok(())
+}
+
+/// Returns how many tokens the vault is short of projected pay-to-spawn
+/// earnings via return data, 0 while it is solvent
+pub fn get_required_topup_handler(ctx: Context<ComputeEarnings>, _session_id: String) -> Result<()> {
+    let game_session = &ctx.accounts.game_session;
+    let vault_balance = ctx.accounts.vault_token_account.amount;
+
+    require!(game_session.is_pay_to_spawn(), WagerError::InvalidGameMode);
+
+    let topup = game_session.required_topup(vault_balance)?;
+
+    msg!("Session {} vault balance {}, required top-up {}",
+         game_session.session_id, vault_balance, topup);
+
+    anchor_lang::solana_program::program::set_return_data(&topup.try_to_vec()?);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct FundVault<'info> {
+    #[account(
+        constraint = authority.key() == game_session.authority @ WagerError::UnauthorizedAction,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        mut,
+        constraint = authority_token_account.owner == authority.key() @ WagerError::InvalidTokenAccountOwner,
+        constraint = authority_token_account.mint == game_session.token_mint @ WagerError::InvalidTokenMint,
+        constraint = authority_token_account.state == AccountState::Initialized @ WagerError::FrozenTokenAccount,
+    )]
+    pub authority_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Vault PDA that holds the funds
+    #[account(
+        seeds = [b"vault", session_id.as_bytes()],
+        bump = game_session.vault_bump,
+    )]
+    pub vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = game_session.token_mint,
+        associated_token::authority = vault,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
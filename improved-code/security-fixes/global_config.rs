@@ -0,0 +1,83 @@
+use crate::{errors::WagerError, state::*};
+use anchor_lang::prelude::*;
+
+/// Space required for a GlobalConfig account
+pub const GLOBAL_CONFIG_SPACE: usize = 8 + // discriminator
+    32 +                   // admin (Pubkey)
+    4 + 32 * MAX_ALLOWED_MINTS + // allowed_mints (Vec<Pubkey>)
+    1 +                    // creation_paused (bool)
+    1;                     // bump (u8)
+
+/// Creates the program-wide config with an empty (allow all) mint whitelist.
+/// Run once at deployment; the caller becomes the admin.
+pub fn initialize_global_config_handler(ctx: Context<InitializeGlobalConfig>) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+
+    global_config.admin = ctx.accounts.admin.key();
+    global_config.allowed_mints = Vec::new();
+    global_config.creation_paused = false;
+    global_config.bump = ctx.bumps.global_config;
+
+    msg!("Global config initialized with admin {}", global_config.admin);
+    Ok(())
+}
+
+/// Allow sessions to be created in `mint` (admin only)
+pub fn add_allowed_mint_handler(ctx: Context<UpdateGlobalConfig>, mint: Pubkey) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+
+    global_config.add_allowed_mint(mint)?;
+
+    msg!("Mint {} allowed ({} on whitelist)", mint, global_config.allowed_mints.len());
+    Ok(())
+}
+
+/// Stop sessions from being created in `mint` (admin only)
+pub fn remove_allowed_mint_handler(ctx: Context<UpdateGlobalConfig>, mint: Pubkey) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+
+    global_config.remove_allowed_mint(mint)?;
+
+    msg!("Mint {} removed ({} on whitelist)", mint, global_config.allowed_mints.len());
+    Ok(())
+}
+
+/// Stop or resume new session creation without touching existing sessions (admin only)
+pub fn set_creation_paused_handler(ctx: Context<UpdateGlobalConfig>, paused: bool) -> Result<()> {
+    ctx.accounts.global_config.creation_paused = paused;
+
+    msg!("Session creation paused: {}", paused);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeGlobalConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = GLOBAL_CONFIG_SPACE,
+        seeds = [b"global_config"],
+        bump
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateGlobalConfig<'info> {
+    #[account(
+        constraint = admin.key() == global_config.admin @ WagerError::UnauthorizedAdmin,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
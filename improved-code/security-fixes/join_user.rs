@@ -1,6 +1,13 @@
-use crate::{errors::WagerError, state::*, TOKEN_ID};
+use crate::{
+    errors::WagerError,
+    events::*,
+    instructions::{vault_transfer, verify_vault_drain, UpdateSessionConfig},
+    state::*,
+};
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
 use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::spl_token::state::AccountState;
 use anchor_spl::token::{Token, TokenAccount};
 
 // SECURITY FIX: Added constants for better maintainability and validation
@@ -8,32 +15,23 @@ const MIN_BET_AMOUNT: u64 = 1000; // Minimum 1000 tokens
 const MAX_BET_AMOUNT: u64 = 1_000_000_000; // Maximum 1B tokens
 const MAX_SESSION_ID_LENGTH: usize = 32;
 
-/// SECURITY FIX: Comprehensive user joining with all security validations
-pub fn join_user_handler(ctx: Context<JoinUser>, session_id: String, team: u8) -> Result<()> {
-    let game_session = &mut ctx.accounts.game_session;
-    let clock = Clock::get()?;
-    let player_key = ctx.accounts.user.key();
-
-    msg!("Player {} attempting to join team {} in session {}", player_key, team, session_id);
-
-    // SECURITY FIX: Validate session hasn't expired
-    require!(
-        !game_session.is_expired(clock.unix_timestamp),
-        WagerError::GameSessionExpired
-    );
-
-    // SECURITY FIX: Validate session ID length
-    require!(
-        session_id.len() <= MAX_SESSION_ID_LENGTH,
-        WagerError::SessionIdTooLong
-    );
-
+/// Validates that a player can take a seat on the given team and returns the slot.
+/// Shared by the single, batch and SOL joins so they all enforce the same rules.
+pub fn validate_join(
+    game_session: &GameSession,
+    player_key: &Pubkey,
+    team: u8,
+    token_balance: u64,
+    current_time: i64,
+) -> Result<usize> {
     // SECURITY FIX: Atomic state validation to prevent race conditions
     require!(
-        game_session.status == GameStatus::WaitingForPlayers,
-        WagerError::InvalidGameState
+        game_session.is_joinable(current_time),
+        WagerError::GameNotJoinable
     );
 
+    require!(!game_session.frozen, WagerError::SessionFrozen);
+
     // Validate team number (0 for team A, 1 for team B)
     require!(
         team == 0 || team == 1, 
@@ -41,10 +39,15 @@ pub fn join_user_handler(ctx: Context<JoinUser>, session_id: String, team: u8) -
     );
 
     // SECURITY FIX: Prevent duplicate player registration across teams
-    game_session.validate_player_not_joined(&player_key)?;
+    game_session.validate_player_not_joined(player_key)?;
+
+    // A challenge reserves team B for the invited opponent
+    game_session.check_invited(team, player_key)?;
+
+    game_session.check_team_balance(team)?;
 
     // SECURITY FIX: Validate bet amount is within acceptable bounds
-    let session_bet = game_session.session_bet;
+    let session_bet = game_session.team_bet(team)?;
     require!(
         session_bet >= MIN_BET_AMOUNT,
         WagerError::BetAmountTooLow
@@ -56,12 +59,37 @@ pub fn join_user_handler(ctx: Context<JoinUser>, session_id: String, team: u8) -
 
     // SECURITY FIX: Enhanced user token account validation
     require!(
-        ctx.accounts.user_token_account.amount >= session_bet,
+        token_balance >= session_bet,
         WagerError::InsufficientUserBalance
     );
 
     // SECURITY FIX: Use improved slot finding with current time validation
-    let empty_index = game_session.get_player_empty_slot(team, clock.unix_timestamp)?;
+    game_session.get_player_empty_slot(team, current_time)
+}
+
+/// SECURITY FIX: Comprehensive user joining with all security validations
+pub fn join_user_handler(ctx: Context<JoinUser>, session_id: String, team: u8) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    let clock = Clock::get()?;
+    let player_key = ctx.accounts.user.key();
+
+    msg!("Player {} attempting to join team {} in session {}", player_key, team, session_id);
+
+    // SECURITY FIX: Validate session ID length
+    require!(
+        session_id.len() <= MAX_SESSION_ID_LENGTH,
+        WagerError::SessionIdTooLong
+    );
+
+    let empty_index = validate_join(
+        game_session,
+        &player_key,
+        team,
+        ctx.accounts.user_token_account.amount,
+        clock.unix_timestamp,
+    )?;
+    game_session.check_canonical_token_account(&player_key, &ctx.accounts.user_token_account.key())?;
+    let session_bet = game_session.team_bet(team)?;
 
     msg!("Found empty slot {} for player {} on team {}", empty_index, player_key, team);
 
@@ -70,6 +98,7 @@ pub fn join_user_handler(ctx: Context<JoinUser>, session_id: String, team: u8) -
     let expected_vault_balance = vault_balance_before
         .checked_add(session_bet)
         .ok_or(WagerError::ArithmeticError)?;
+    game_session.check_vault_cap(expected_vault_balance)?;
 
     msg!("Vault balance before: {}, expected after: {}", vault_balance_before, expected_vault_balance);
 
@@ -100,60 +129,164 @@ pub fn join_user_handler(ctx: Context<JoinUser>, session_id: String, team: u8) -
 
     msg!("Token transfer successful. Vault balance after: {}", vault_balance_after);
 
-    // SECURITY FIX: Bounds checking before array access
-    require!(
-        empty_index < MAX_PLAYERS_PER_TEAM,
-        WagerError::InvalidPlayerIndex
-    );
-
-    // Get reference to the selected team with bounds validation
-    let (selected_team, team_name) = if team == 0 {
-        (&mut game_session.team_a, "A")
-    } else {
-        (&mut game_session.team_b, "B")
-    };
-
-    // SECURITY FIX: Double-check that the slot is still empty (race condition protection)
-    require!(
-        selected_team.players[empty_index] == Pubkey::default(),
-        WagerError::TeamSlotNoLongerAvailable
-    );
-
-    // Add player to the first available slot with proper initialization
-    selected_team.players[empty_index] = player_key;
-    
-    // SECURITY FIX: Initialize spawns using configurable value from game mode
-    game_session.initialize_player_spawns(team, empty_index)?;
-    
-    // Initialize kills to zero
-    selected_team.player_kills[empty_index] = 0;
+    emit!(TokenMoved {
+        session_id: session_id.clone(),
+        from: ctx.accounts.user_token_account.key(),
+        to: ctx.accounts.vault_token_account.key(),
+        amount: session_bet,
+        reason: TokenMoveReason::Join as u8,
+    });
 
-    // SECURITY FIX: Update total bet for the team with overflow protection
-    selected_team.total_bet = selected_team.total_bet
-        .checked_add(session_bet)
-        .ok_or(WagerError::ArithmeticError)?;
+    game_session.seat_player(team, empty_index, player_key, clock.unix_timestamp)?;
 
-    msg!("Player {} successfully added to team {} at index {}", player_key, team_name, empty_index);
+    msg!("Player {} successfully added to team {} at index {}", player_key, team, empty_index);
 
     // SECURITY FIX: Atomic state transition check
-    if game_session.can_start()? {
-        game_session.status = GameStatus::InProgress;
+    if game_session.can_start()? && game_session.min_start_gap_elapsed(clock.unix_timestamp) {
+        game_session.start(clock.unix_timestamp)?;
         msg!("Game session {} is now in progress", session_id);
         
         // Log game start details for monitoring
         let players_per_team = game_session.game_mode.players_per_team();
-        let total_pot = session_bet
-            .checked_mul(players_per_team as u64)
-            .and_then(|x| x.checked_mul(2))
-            .ok_or(WagerError::ArithmeticError)?;
+        let total_pot = game_session.total_pot()?;
         
         msg!("Game started with {} players per team, total pot: {} tokens", 
              players_per_team, total_pot);
+    } else if game_session.can_start()? {
+        msg!("Game session {} is full, waiting for minimum start gap", session_id);
     }
 
     Ok(())
 }
 
+/// Seats a batch of pre-funded players in one transaction (authority only).
+/// remaining_accounts[i] is the token account for assignments[i], and each must
+/// have delegated at least session_bet to the game server. Any failure reverts
+/// the whole batch, so there is never a partial seating.
+pub fn join_batch_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, JoinBatch<'info>>,
+    session_id: String,
+    assignments: Vec<(Pubkey, u8)>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    msg!("Seating {} players in session {}", assignments.len(), session_id);
+
+    require!(
+        session_id.len() <= MAX_SESSION_ID_LENGTH,
+        WagerError::SessionIdTooLong
+    );
+
+    require!(
+        !assignments.is_empty() && ctx.remaining_accounts.len() == assignments.len(),
+        WagerError::InvalidRemainingAccounts
+    );
+
+    let game_server_key = ctx.accounts.game_server.key();
+
+    // SECURITY FIX: Hold the whole batch to the vault cap before moving any tokens
+    let vault_balance_before = ctx.accounts.vault_token_account.amount;
+    let expected_vault_balance = vault_balance_before
+        .checked_add(batch_stake_total(&ctx.accounts.game_session, &assignments)?)
+        .ok_or(WagerError::ArithmeticError)?;
+    ctx.accounts.game_session.check_vault_cap(expected_vault_balance)?;
+
+    for ((player_key, team), token_account_info) in
+        assignments.iter().zip(ctx.remaining_accounts.iter())
+    {
+        let player_token_account = Account::<TokenAccount>::try_from(token_account_info)
+            .map_err(|_| error!(WagerError::InvalidPlayerTokenAccount))?;
+
+        require!(
+            player_token_account.owner == *player_key,
+            WagerError::InvalidTokenAccountOwner
+        );
+
+        require!(
+            player_token_account.mint == ctx.accounts.game_session.token_mint,
+            WagerError::InvalidTokenMint
+        );
+
+        require!(
+            player_token_account.state == AccountState::Initialized,
+            WagerError::FrozenTokenAccount
+        );
+
+        ctx.accounts
+            .game_session
+            .check_canonical_token_account(player_key, &token_account_info.key())?;
+
+        let empty_index = validate_join(
+            &ctx.accounts.game_session,
+            player_key,
+            *team,
+            player_token_account.amount,
+            clock.unix_timestamp,
+        )?;
+        let session_bet = ctx.accounts.game_session.team_bet(*team)?;
+
+        // Pre-funded players have delegated their stake to the game server
+        require!(
+            player_token_account.delegate == COption::Some(game_server_key)
+                && player_token_account.delegated_amount >= session_bet,
+            WagerError::InsufficientDelegation
+        );
+
+        anchor_spl::token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                anchor_spl::token::Transfer {
+                    from: token_account_info.to_account_info(),
+                    to: ctx.accounts.vault_token_account.to_account_info(),
+                    authority: ctx.accounts.game_server.to_account_info(),
+                },
+            ),
+            session_bet,
+        ).map_err(|e| {
+            msg!("Token transfer failed: {:?}", e);
+            error!(WagerError::TokenTransferFailed)
+        })?;
+
+        emit!(TokenMoved {
+            session_id: session_id.clone(),
+            from: token_account_info.key(),
+            to: ctx.accounts.vault_token_account.key(),
+            amount: session_bet,
+            reason: TokenMoveReason::Join as u8,
+        });
+
+        ctx.accounts
+            .game_session
+            .seat_player(*team, empty_index, *player_key, clock.unix_timestamp)?;
+
+        msg!("Player {} seated on team {} at index {}", player_key, team, empty_index);
+    }
+
+    // SECURITY FIX: Verify the batch's transfers all landed in the vault
+    ctx.accounts.vault_token_account.reload()?;
+    require!(
+        ctx.accounts.vault_token_account.amount == expected_vault_balance,
+        WagerError::TransferVerificationFailed
+    );
+
+    let game_session = &mut ctx.accounts.game_session;
+    if game_session.can_start()? && game_session.min_start_gap_elapsed(clock.unix_timestamp) {
+        game_session.start(clock.unix_timestamp)?;
+        msg!("Game session {} is now in progress", session_id);
+    }
+
+    Ok(())
+}
+
+/// Total stake a join batch moves into the vault, each player paying their team's bet
+fn batch_stake_total(game_session: &GameSession, assignments: &[(Pubkey, u8)]) -> Result<u64> {
+    assignments.iter().try_fold(0u64, |total, (_, team)| {
+        total
+            .checked_add(game_session.team_bet(*team)?)
+            .ok_or(error!(WagerError::ArithmeticError))
+    })
+}
+
 /// SECURITY FIX: Emergency function to leave game before it starts
 pub fn leave_game_handler(ctx: Context<LeaveGame>, session_id: String, team: u8) -> Result<()> {
     let game_session = &mut ctx.accounts.game_session;
@@ -180,56 +313,176 @@ pub fn leave_game_handler(ctx: Context<LeaveGame>, session_id: String, team: u8)
         WagerError::InvalidTeamSelection
     );
 
+    // A lobby that just filled up is held together until its lock lapses
+    game_session.check_lobby_unlocked(clock.unix_timestamp)?;
+
     // Find player in the team
     let player_index = game_session.get_player_index(team, player_key)?;
 
-    // Get team reference
-    let selected_team = if team == 0 {
-        &mut game_session.team_a
-    } else {
-        &mut game_session.team_b
-    };
+    // Get refund amount, keeping any leave penalty in the vault
+    let (refund_amount, penalty) = game_session.leave_refund_split(team, player_index)?;
+
+    refund_and_unseat(
+        game_session,
+        &mut ctx.accounts.vault_token_account,
+        &ctx.accounts.vault,
+        &ctx.accounts.token_program,
+        &ctx.accounts.user_token_account.to_account_info(),
+        &session_id,
+        team,
+        player_index,
+        refund_amount,
+    )?;
+
+    game_session.leave_penalties = game_session.leave_penalties
+        .checked_add(penalty)
+        .ok_or(WagerError::ArithmeticError)?;
+    // The penalty stayed in the vault, so it is still collected
+    game_session.total_collected = game_session.total_collected
+        .checked_add(penalty)
+        .ok_or(WagerError::ArithmeticError)?;
+
+    msg!("Player {} successfully left the game and received refund of {} tokens ({} penalty)", 
+         player_key, refund_amount, penalty);
+
+    Ok(())
+}
+
+/// The team captain (slot 0) removes a teammate before the game starts. The
+/// kicked player did not choose to leave, so everything they paid in is refunded.
+pub fn kick_player_handler(
+    ctx: Context<KickPlayer>,
+    session_id: String,
+    team: u8,
+    target: Pubkey,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    let clock = Clock::get()?;
+
+    require!(
+        !game_session.is_expired(clock.unix_timestamp),
+        WagerError::GameSessionExpired
+    );
+    game_session.check_captain(team, ctx.accounts.captain.key(), target)?;
+    game_session.check_lobby_unlocked(clock.unix_timestamp)?;
+
+    let player_index = game_session.get_player_index(team, target)?;
+    let refund_amount = game_session.player_contribution(team, player_index)?;
+
+    refund_and_unseat(
+        game_session,
+        &mut ctx.accounts.vault_token_account,
+        &ctx.accounts.vault,
+        &ctx.accounts.token_program,
+        &ctx.accounts.target_token_account.to_account_info(),
+        &session_id,
+        team,
+        player_index,
+        refund_amount,
+    )?;
 
-    // Get refund amount
-    let refund_amount = game_session.session_bet;
+    msg!("Captain {} kicked {} from team {}, refunding {} tokens",
+         ctx.accounts.captain.key(), target, team, refund_amount);
+    Ok(())
+}
 
+/// Pays `refund_amount` from the vault to a departing player and frees their
+/// slot, taking their stake off the team's total bet. The slot is fully
+/// reset, so the player may join again later.
+#[allow(clippy::too_many_arguments)]
+fn refund_and_unseat<'info>(
+    game_session: &mut Account<'info, GameSession>,
+    vault_token_account: &mut Account<'info, TokenAccount>,
+    vault: &AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+    player_token_account: &AccountInfo<'info>,
+    session_id: &str,
+    team: u8,
+    player_index: usize,
+    refund_amount: u64,
+) -> Result<()> {
     // Validate vault has sufficient balance for refund
+    let vault_balance_before = vault_token_account.amount;
     require!(
-        ctx.accounts.vault_token_account.amount >= refund_amount,
+        vault_balance_before >= refund_amount,
         WagerError::InsufficientVaultBalance
     );
 
-    // Transfer tokens back to user
-    anchor_spl::token::transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            anchor_spl::token::Transfer {
-                from: ctx.accounts.vault_token_account.to_account_info(),
-                to: ctx.accounts.user_token_account.to_account_info(),
-                authority: ctx.accounts.vault.to_account_info(),
-            },
-            &[&[
-                b"vault",
-                session_id.as_bytes(),
-                &[game_session.vault_bump],
-            ]],
-        ),
+    vault_transfer(
+        token_program,
+        vault_token_account,
+        vault,
+        session_id,
+        game_session.vault_bump,
+        player_token_account,
         refund_amount,
     )?;
 
-    // Remove player from team
-    selected_team.players[player_index] = Pubkey::default();
-    selected_team.player_spawns[player_index] = 0;
-    selected_team.player_kills[player_index] = 0;
+    emit!(TokenMoved {
+        session_id: session_id.to_string(),
+        from: vault_token_account.key(),
+        to: player_token_account.key(),
+        amount: refund_amount,
+        reason: TokenMoveReason::Refund as u8,
+    });
 
-    // Update team's total bet
-    selected_team.total_bet = selected_team.total_bet
-        .checked_sub(refund_amount)
-        .ok_or(WagerError::ArithmeticError)?;
+    vault_token_account.reload()?;
+    verify_vault_drain(vault_balance_before, vault_token_account.amount, refund_amount)?;
+
+    game_session.unseat_player(team, player_index)
+}
+
+/// Set the share of the bet kept in the vault when a player leaves (authority only, before anyone joins)
+pub fn update_leave_penalty_handler(
+    ctx: Context<UpdateSessionConfig>,
+    _session_id: String,
+    penalty_bps: u16,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
 
-    msg!("Player {} successfully left the game and received refund of {} tokens", 
-         player_key, refund_amount);
+    game_session.update_leave_penalty(penalty_bps)?;
 
+    msg!("Session {} leave penalty set to {} bps", game_session.session_id, penalty_bps);
+    Ok(())
+}
+
+/// Set how long a freshly filled lobby rejects leaves (authority only, before the game starts)
+pub fn update_lobby_lock_handler(
+    ctx: Context<UpdateSessionConfig>,
+    _session_id: String,
+    lock_seconds: i64,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    game_session.update_lobby_lock(lock_seconds)?;
+
+    msg!("Session {} lobby lock set to {} seconds", game_session.session_id, lock_seconds);
+    Ok(())
+}
+
+/// Returns (player, spawns, kills) for each seated player on `team` via return data
+pub fn get_team_players_handler(
+    ctx: Context<GetGameStatus>,
+    _session_id: String,
+    team: u8,
+) -> Result<()> {
+    let roster = ctx.accounts.game_session.get_team_roster(team)?;
+
+    msg!("Team {}: {} seated players", team, roster.len());
+
+    anchor_lang::solana_program::program::set_return_data(&roster.try_to_vec()?);
+    Ok(())
+}
+
+/// Returns the session's GamePhase via return data, for clients routing on a single value
+pub fn get_game_phase_handler(ctx: Context<GetGameStatus>, _session_id: String) -> Result<()> {
+    let game_session = &ctx.accounts.game_session;
+    let clock = Clock::get()?;
+
+    let phase = game_session.game_phase(clock.unix_timestamp);
+    msg!("Session {} phase: {:?}", game_session.session_id, phase);
+
+    anchor_lang::solana_program::program::set_return_data(&phase.try_to_vec()?);
     Ok(())
 }
 
@@ -248,6 +501,10 @@ pub fn get_game_status(ctx: Context<GetGameStatus>, _session_id: String) -> Resu
     msg!("Expires At: {}", game_session.expires_at);
     msg!("Is Expired: {}", game_session.is_expired(clock.unix_timestamp));
 
+    // Countdown synced to the on-chain clock, returned for UIs
+    let seconds_until_expiry = game_session.seconds_until_expiry(clock.unix_timestamp);
+    msg!("Seconds Until Expiry: {}", seconds_until_expiry);
+
     let players_per_team = game_session.game_mode.players_per_team();
     
     // Team A status
@@ -287,6 +544,18 @@ pub fn get_game_status(ctx: Context<GetGameStatus>, _session_id: String) -> Resu
         }
     }
 
+    anchor_lang::solana_program::program::set_return_data(&seconds_until_expiry.try_to_vec()?);
+    Ok(())
+}
+
+/// Returns a SessionSnapshot of the session via return data, for consumers
+/// that should not depend on the GameSession account layout
+pub fn read_session_handler(ctx: Context<GetGameStatus>, _session_id: String) -> Result<()> {
+    let snapshot = ctx.accounts.game_session.snapshot();
+
+    msg!("Session {} snapshot, status {}", snapshot.session_id, snapshot.status.clone() as u8);
+
+    anchor_lang::solana_program::program::set_return_data(&snapshot.try_to_vec()?);
     Ok(())
 }
 
@@ -307,7 +576,7 @@ pub struct JoinUser<'info> {
         mut,
         seeds = [b"game_session", session_id.as_bytes()],
         bump = game_session.bump,
-        constraint = game_session.status == GameStatus::WaitingForPlayers @ WagerError::GameNotJoinable,
+        constraint = game_session.is_joinable(Clock::get()?.unix_timestamp) @ WagerError::GameNotJoinable,
         constraint = session_id.len() <= MAX_SESSION_ID_LENGTH @ WagerError::SessionIdTooLong,
     )]
     pub game_session: Account<'info, GameSession>,
@@ -315,8 +584,9 @@ pub struct JoinUser<'info> {
     #[account(
         mut,
         constraint = user_token_account.owner == user.key() @ WagerError::InvalidTokenAccountOwner,
-        constraint = user_token_account.mint == TOKEN_ID @ WagerError::InvalidTokenMint,
-        constraint = user_token_account.amount >= game_session.session_bet @ WagerError::InsufficientUserBalance,
+        constraint = user_token_account.mint == game_session.token_mint @ WagerError::InvalidTokenMint,
+        constraint = user_token_account.state == AccountState::Initialized @ WagerError::FrozenTokenAccount,
+        constraint = user_token_account.amount >= game_session.team_a_bet.min(game_session.team_b_bet) @ WagerError::InsufficientUserBalance,
     )]
     pub user_token_account: Account<'info, TokenAccount>,
 
@@ -337,7 +607,7 @@ pub struct JoinUser<'info> {
 
     #[account(
         mut,
-        address = TOKEN_ID @ WagerError::InvalidMint
+        address = game_session.token_mint @ WagerError::InvalidMint
     )]
     pub mint: Account<'info, anchor_spl::token::Mint>,
     
@@ -346,6 +616,41 @@ pub struct JoinUser<'info> {
     pub system_program: Program<'info, System>,
 }
 
+/// Account structure for seating a batch of players
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct JoinBatch<'info> {
+    #[account(
+        constraint = game_server.key() == game_session.authority @ WagerError::UnauthorizedGameServer
+    )]
+    pub game_server: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+        constraint = game_session.is_joinable(Clock::get()?.unix_timestamp) @ WagerError::GameNotJoinable,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: Vault PDA that holds the funds
+    #[account(
+        mut,
+        seeds = [b"vault", session_id.as_bytes()],
+        bump = game_session.vault_bump,
+    )]
+    pub vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = game_session.token_mint,
+        associated_token::authority = vault,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
 /// SECURITY FIX: Account structure for leaving games
 #[derive(Accounts)]
 #[instruction(session_id: String)]
@@ -364,7 +669,7 @@ pub struct LeaveGame<'info> {
     #[account(
         mut,
         constraint = user_token_account.owner == user.key() @ WagerError::InvalidTokenAccountOwner,
-        constraint = user_token_account.mint == TOKEN_ID @ WagerError::InvalidTokenMint,
+        constraint = user_token_account.mint == game_session.token_mint @ WagerError::InvalidTokenMint,
     )]
     pub user_token_account: Account<'info, TokenAccount>,
 
@@ -378,7 +683,46 @@ pub struct LeaveGame<'info> {
 
     #[account(
         mut,
-        associated_token::mint = TOKEN_ID,
+        associated_token::mint = game_session.token_mint,
+        associated_token::authority = vault,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Account structure for a captain kicking a teammate
+#[derive(Accounts)]
+#[instruction(session_id: String, team: u8, target: Pubkey)]
+pub struct KickPlayer<'info> {
+    pub captain: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+        constraint = game_session.status == GameStatus::WaitingForPlayers @ WagerError::GameAlreadyInProgress,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        mut,
+        constraint = target_token_account.owner == target @ WagerError::InvalidTokenAccountOwner,
+        constraint = target_token_account.mint == game_session.token_mint @ WagerError::InvalidTokenMint,
+    )]
+    pub target_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Vault PDA
+    #[account(
+        mut,
+        seeds = [b"vault", session_id.as_bytes()],
+        bump = game_session.vault_bump,
+    )]
+    pub vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = game_session.token_mint,
         associated_token::authority = vault,
     )]
     pub vault_token_account: Account<'info, TokenAccount>,
@@ -448,6 +792,35 @@ mod tests {
         assert!(overflow_result.is_none());
     }
 
+    #[test]
+    fn test_batch_stake_total_held_to_vault_cap() {
+        let mut session = GameSession::new(
+            "test".to_string(),
+            Pubkey::new_unique(),
+            100,
+            GameMode::WinnerTakesAllThreeVsThree,
+            1000,
+            1,
+            2,
+        ).unwrap();
+        session.max_vault_balance = 250;
+
+        let assignments = vec![
+            (Pubkey::new_unique(), 0),
+            (Pubkey::new_unique(), 1),
+            (Pubkey::new_unique(), 0),
+        ];
+        let total = batch_stake_total(&session, &assignments).unwrap();
+        assert_eq!(total, 300);
+
+        // Seating all three would push the vault over its cap, two fit
+        assert!(session.check_vault_cap(total).is_err());
+        let total = batch_stake_total(&session, &assignments[..2]).unwrap();
+        assert!(session.check_vault_cap(total).is_ok());
+
+        assert!(batch_stake_total(&session, &[(Pubkey::new_unique(), 2)]).is_err());
+    }
+
     #[test]
     fn test_vault_balance_calculation() {
         let initial_balance = 1000u64;
@@ -0,0 +1,89 @@
+use crate::{errors::WagerError, state::*};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+/// Moves a waiting session onto a new mint after a token migration. The old
+/// vault token account must be empty; it is closed and the vault's account
+/// for the new mint takes its place.
+pub fn migrate_mint_handler(ctx: Context<MigrateMint>, session_id: String) -> Result<()> {
+    let old_mint = ctx.accounts.game_session.token_mint;
+    let new_mint = ctx.accounts.new_mint.key();
+
+    ctx.accounts.game_session.migrate_mint(
+        new_mint,
+        ctx.accounts.new_mint.decimals,
+        ctx.accounts.old_vault_token_account.amount,
+    )?;
+
+    // Return the old account's rent to the authority paying for the new one
+    anchor_spl::token::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        anchor_spl::token::CloseAccount {
+            account: ctx.accounts.old_vault_token_account.to_account_info(),
+            destination: ctx.accounts.authority.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        },
+        &[&[
+            b"vault",
+            session_id.as_bytes(),
+            &[ctx.accounts.game_session.vault_bump],
+        ]],
+    ))?;
+
+    msg!("Session {} migrated from mint {} to {}", session_id, old_mint, new_mint);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct MigrateMint<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == game_session.authority @ WagerError::UnauthorizedAction,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: Vault PDA that owns both token accounts
+    #[account(
+        seeds = [b"vault", session_id.as_bytes()],
+        bump = game_session.vault_bump,
+    )]
+    pub vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = game_session.token_mint,
+        associated_token::authority = vault,
+    )]
+    pub old_vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = new_mint,
+        associated_token::authority = vault,
+    )]
+    pub new_vault_token_account: Account<'info, TokenAccount>,
+
+    pub new_mint: Account<'info, Mint>,
+
+    /// Program-wide config whose whitelist the new mint must be on
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = global_config.is_mint_allowed(&new_mint.key()) @ WagerError::InvalidMint,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
@@ -1,10 +1,10 @@
-use crate::{errors::WagerError, state::*, TOKEN_ID};
+use crate::{errors::WagerError, events::*, state::*};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::spl_token::state::AccountState;
 use anchor_spl::token::{Token, TokenAccount};
 
 // SECURITY FIX: Added constants for better maintainability and validation
-const MAX_SPAWNS_PER_PLAYER: u16 = 100; // Prevent excessive spawn purchases
 const MIN_BET_AMOUNT: u64 = 1000; // Minimum bet amount
 const MAX_BET_AMOUNT: u64 = 1_000_000_000; // Maximum bet amount
 const MAX_SESSION_ID_LENGTH: usize = 32;
@@ -12,12 +12,45 @@ const MAX_SPAWN_PURCHASES_PER_TRANSACTION: u8 = 5; // Rate limiting
 
 /// SECURITY FIX: Comprehensive pay-to-spawn with all security validations
 pub fn pay_to_spawn_handler(ctx: Context<PayToSpawn>, session_id: String, team: u8) -> Result<()> {
+    purchase_spawn_bundles(ctx, session_id, team, 1)
+}
+
+/// Buys up to MAX_SPAWN_PURCHASES_PER_TRANSACTION spawn bundles with a single transfer
+pub fn purchase_spawns_handler(
+    ctx: Context<PayToSpawn>,
+    session_id: String,
+    team: u8,
+    count: u8,
+) -> Result<()> {
+    validate_purchase_count(count)?;
+
+    purchase_spawn_bundles(ctx, session_id, team, count)
+}
+
+/// Enforces the per-transaction spawn purchase limit
+fn validate_purchase_count(count: u8) -> Result<()> {
+    require!(count > 0, WagerError::InvalidSpawnCount);
+    require!(
+        count <= MAX_SPAWN_PURCHASES_PER_TRANSACTION,
+        WagerError::RateLimitExceeded
+    );
+    Ok(())
+}
+
+/// Shared purchase path: validates, charges the aggregate cost of `count`
+/// bundles in one transfer, then credits spawns_per_purchase per bundle
+fn purchase_spawn_bundles(
+    ctx: Context<PayToSpawn>,
+    session_id: String,
+    team: u8,
+    count: u8,
+) -> Result<()> {
     let game_session = &mut ctx.accounts.game_session;
     let clock = Clock::get()?;
     let player_key = ctx.accounts.user.key();
 
-    msg!("Player {} attempting to purchase spawns for team {} in session {}", 
-         player_key, team, session_id);
+    msg!("Player {} attempting to purchase {} spawn bundles for team {} in session {}", 
+         player_key, count, team, session_id);
 
     // SECURITY FIX: Validate session hasn't expired
     require!(
@@ -42,6 +75,8 @@ pub fn pay_to_spawn_handler(ctx: Context<PayToSpawn>, session_id: String, team:
         WagerError::InvalidGameMode
     );
 
+    require!(!game_session.frozen, WagerError::SessionFrozen);
+
     // Validate team number (0 for team A, 1 for team B)
     require!(
         team == 0 || team == 1, 
@@ -65,7 +100,7 @@ pub fn pay_to_spawn_handler(ctx: Context<PayToSpawn>, session_id: String, team:
     };
 
     require!(
-        current_spawns < MAX_SPAWNS_PER_PLAYER,
+        current_spawns < game_session.max_concurrent_spawns,
         WagerError::MaxSpawnsExceeded
     );
 
@@ -76,22 +111,26 @@ pub fn pay_to_spawn_handler(ctx: Context<PayToSpawn>, session_id: String, team:
         WagerError::InvalidBetAmount
     );
 
+    // Each successive purchase costs more when spawn cost scaling is enabled
+    let purchase_cost = game_session.spawn_bundle_cost(team, player_index, count)?;
+
     // SECURITY FIX: Comprehensive user balance validation
     require!(
-        ctx.accounts.user_token_account.amount >= session_bet,
+        ctx.accounts.user_token_account.amount >= purchase_cost,
         WagerError::InsufficientUserBalance
     );
 
-    // SECURITY FIX: Validate that spawns to be added won't exceed maximum
-    let spawns_to_add = game_session.spawns_per_purchase;
+    // SECURITY FIX: Validate that spawns to be added won't exceed the concurrent
+    // and lifetime caps
+    let spawns_to_add = game_session
+        .spawns_per_purchase
+        .checked_mul(count as u16)
+        .ok_or(WagerError::ArithmeticError)?;
     let new_spawn_count = current_spawns
         .checked_add(spawns_to_add)
         .ok_or(WagerError::ArithmeticError)?;
 
-    require!(
-        new_spawn_count <= MAX_SPAWNS_PER_PLAYER,
-        WagerError::MaxSpawnsExceeded
-    );
+    game_session.check_spawn_caps(team, player_index, spawns_to_add)?;
 
     msg!("Current spawns: {}, Adding: {}, New total: {}", 
          current_spawns, spawns_to_add, new_spawn_count);
@@ -99,8 +138,9 @@ pub fn pay_to_spawn_handler(ctx: Context<PayToSpawn>, session_id: String, team:
     // SECURITY FIX: Validate vault can receive tokens
     let vault_balance_before = ctx.accounts.vault_token_account.amount;
     let expected_vault_balance = vault_balance_before
-        .checked_add(session_bet)
+        .checked_add(purchase_cost)
         .ok_or(WagerError::ArithmeticError)?;
+    game_session.check_vault_cap(expected_vault_balance)?;
 
     msg!("Vault balance before: {}, expected after: {}", 
          vault_balance_before, expected_vault_balance);
@@ -115,7 +155,7 @@ pub fn pay_to_spawn_handler(ctx: Context<PayToSpawn>, session_id: String, team:
                 authority: ctx.accounts.user.to_account_info(),
             },
         ),
-        session_bet,
+        purchase_cost,
     ).map_err(|e| {
         msg!("Token transfer failed: {:?}", e);
         error!(WagerError::TokenTransferFailed)
@@ -131,8 +171,19 @@ pub fn pay_to_spawn_handler(ctx: Context<PayToSpawn>, session_id: String, team:
 
     msg!("Token transfer successful. Vault balance after: {}", vault_balance_after);
 
-    // Add spawns to the player using the secure method
-    game_session.add_spawns(team, player_index)?;
+    emit!(TokenMoved {
+        session_id: session_id.clone(),
+        from: ctx.accounts.user_token_account.key(),
+        to: ctx.accounts.vault_token_account.key(),
+        amount: purchase_cost,
+        reason: TokenMoveReason::PayToSpawn as u8,
+    });
+
+    // Add spawns to the player using the secure method, one bundle at a time
+    for _ in 0..count {
+        game_session.add_spawns(team, player_index)?;
+    }
+    game_session.record_contribution(team, player_index, purchase_cost)?;
 
     // SECURITY FIX: Update team's total collected funds with overflow protection
     let team_total_bet = if team == 0 {
@@ -142,16 +193,19 @@ pub fn pay_to_spawn_handler(ctx: Context<PayToSpawn>, session_id: String, team:
     };
 
     *team_total_bet = team_total_bet
-        .checked_add(session_bet)
+        .checked_add(purchase_cost)
         .ok_or(WagerError::ArithmeticError)?;
 
     msg!("Player {} successfully purchased {} spawns for {} tokens", 
-         player_key, spawns_to_add, session_bet);
+         player_key, spawns_to_add, purchase_cost);
 
     // SECURITY FIX: Log important metrics for monitoring
     msg!("Team {} total collected: {} tokens", team, team_total_bet);
     msg!("Player spawn count updated to: {}", new_spawn_count);
 
+    // SECURITY FIX: Cashing everyone out now must still be payable from the vault
+    game_session.check_projected_solvency(vault_balance_after)?;
+
     Ok(())
 }
 
@@ -168,7 +222,9 @@ pub fn get_spawn_info(ctx: Context<GetSpawnInfo>, _session_id: String) -> Result
     msg!("Session Expired: {}", game_session.is_expired(clock.unix_timestamp));
     msg!("Cost per spawn purchase: {} tokens", game_session.session_bet);
     msg!("Spawns per purchase: {}", game_session.spawns_per_purchase);
-    msg!("Maximum spawns per player: {}", MAX_SPAWNS_PER_PLAYER);
+    msg!("Cost scale per prior purchase: {} bps", game_session.spawn_cost_scale_bps);
+    msg!("Maximum spawns held at once: {}", game_session.max_concurrent_spawns);
+    msg!("Maximum spawns bought per player: {}", game_session.max_lifetime_spawns);
     
     if !game_session.is_pay_to_spawn() {
         msg!("WARNING: This is not a pay-to-spawn game mode!");
@@ -211,9 +267,9 @@ pub fn get_player_spawn_count(
             msg!("Team: {}", team);
             msg!("Current spawns: {}", spawn_count);
             msg!("Current kills: {}", kill_count);
-            msg!("Can purchase more spawns: {}", spawn_count < MAX_SPAWNS_PER_PLAYER);
+            msg!("Can purchase more spawns: {}", spawn_count < game_session.max_concurrent_spawns);
             msg!("Spawns remaining until max: {}", 
-                 MAX_SPAWNS_PER_PLAYER.saturating_sub(spawn_count));
+                 game_session.max_concurrent_spawns.saturating_sub(spawn_count));
         }
         Err(_) => {
             msg!("Player {} not found in team {}", player, team);
@@ -224,6 +280,74 @@ pub fn get_player_spawn_count(
     Ok(())
 }
 
+/// Returns a SpawnEligibility via return data so a client can decide whether
+/// to offer a purchase with the same checks pay_to_spawn applies
+pub fn can_purchase_spawns_handler(
+    ctx: Context<GetPlayerSpawnCount>,
+    _session_id: String,
+    team: u8,
+    player: Pubkey,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let eligibility =
+        spawn_eligibility(&ctx.accounts.game_session, team, player, clock.unix_timestamp)?;
+
+    msg!("Player {} can purchase spawns: {} ({} of {}, next costs {})",
+         player, eligibility.eligible, eligibility.current_spawns,
+         eligibility.max_spawns, eligibility.cost);
+
+    anchor_lang::solana_program::program::set_return_data(&eligibility.try_to_vec()?);
+    Ok(())
+}
+
+/// A purchase is open to a seated player of a live, unfrozen pay-to-spawn
+/// session while one more bundle stays within the concurrent and lifetime caps
+fn spawn_eligibility(
+    game_session: &GameSession,
+    team: u8,
+    player: Pubkey,
+    current_time: i64,
+) -> Result<SpawnEligibility> {
+    require!(team == 0 || team == 1, WagerError::InvalidTeamSelection);
+
+    let player_index = game_session.get_player_index(team, player)?;
+    let current_spawns = if team == 0 {
+        game_session.team_a.player_spawns[player_index]
+    } else {
+        game_session.team_b.player_spawns[player_index]
+    };
+    let within_cap = game_session.spawns_per_purchase > 0
+        && game_session
+            .check_spawn_caps(team, player_index, game_session.spawns_per_purchase)
+            .is_ok();
+
+    Ok(SpawnEligibility {
+        eligible: game_session.status == GameStatus::InProgress
+            && game_session.is_pay_to_spawn()
+            && !game_session.is_expired(current_time)
+            && !game_session.frozen
+            && within_cap,
+        current_spawns,
+        max_spawns: game_session.max_concurrent_spawns,
+        cost: game_session.spawn_purchase_cost(team, player_index)?,
+    })
+}
+
+/// Returns a single player's scoreboard stats as a PlayerStats
+pub fn get_player_stats_handler(
+    ctx: Context<GetPlayerSpawnCount>,
+    _session_id: String,
+    player: Pubkey,
+) -> Result<()> {
+    let stats = ctx.accounts.game_session.player_stats(player)?;
+
+    msg!("Player {}: team {}, kills {}, spawns {}, KD {} bps",
+         player, stats.team, stats.kills, stats.spawns, stats.kd_ratio_bps);
+
+    anchor_lang::solana_program::program::set_return_data(&stats.try_to_vec()?);
+    Ok(())
+}
+
 /// SECURITY FIX: Emergency function to disable spawn purchases (authority only)
 pub fn disable_spawn_purchases(
     ctx: Context<DisableSpawnPurchases>, 
@@ -245,6 +369,41 @@ pub fn disable_spawn_purchases(
     Ok(())
 }
 
+/// Set how many spawns each player starts with (authority only, before anyone joins)
+pub fn update_initial_spawns_handler(
+    ctx: Context<UpdateSpawnConfig>,
+    _session_id: String,
+    initial_spawns: u16,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    require!(
+        game_session.authority == ctx.accounts.authority.key(),
+        WagerError::UnauthorizedAction
+    );
+
+    game_session.update_initial_spawns(initial_spawns)?;
+
+    msg!("Session {} players start with {} spawns", game_session.session_id, initial_spawns);
+    Ok(())
+}
+
+/// Set the caps on spawns held at once and bought over the session (authority only)
+pub fn update_spawn_caps_handler(
+    ctx: Context<UpdateSpawnConfig>,
+    _session_id: String,
+    max_concurrent_spawns: u16,
+    max_lifetime_spawns: u16,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    game_session.update_spawn_caps(max_concurrent_spawns, max_lifetime_spawns)?;
+
+    msg!("Session {} spawn caps: {} held at once, {} bought in total",
+         game_session.session_id, max_concurrent_spawns, max_lifetime_spawns);
+    Ok(())
+}
+
 /// SECURITY FIX: Function to update spawn purchase configuration (authority only)
 pub fn update_spawn_config(
     ctx: Context<UpdateSpawnConfig>, 
@@ -272,6 +431,21 @@ pub fn update_spawn_config(
     Ok(())
 }
 
+/// Function to update how much each successive spawn purchase costs (authority only)
+pub fn update_spawn_cost_scale(
+    ctx: Context<UpdateSpawnConfig>,
+    _session_id: String,
+    scale_bps: u16,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    game_session.update_spawn_cost_scale(scale_bps)?;
+
+    msg!("Spawn cost scale updated: {} bps per prior purchase", scale_bps);
+
+    Ok(())
+}
+
 /// SECURITY FIX: Enhanced account validation with proper authority checks
 #[derive(Accounts)]
 #[instruction(session_id: String)]
@@ -298,7 +472,8 @@ pub struct PayToSpawn<'info> {
     #[account(
         mut,
         constraint = user_token_account.owner == user.key() @ WagerError::InvalidTokenAccountOwner,
-        constraint = user_token_account.mint == TOKEN_ID @ WagerError::InvalidTokenMint,
+        constraint = user_token_account.mint == game_session.token_mint @ WagerError::InvalidTokenMint,
+        constraint = user_token_account.state == AccountState::Initialized @ WagerError::FrozenTokenAccount,
         constraint = user_token_account.amount >= game_session.session_bet @ WagerError::InsufficientUserBalance,
     )]
     pub user_token_account: Account<'info, TokenAccount>,
@@ -313,7 +488,7 @@ pub struct PayToSpawn<'info> {
 
     #[account(
         mut,
-        associated_token::mint = TOKEN_ID,
+        associated_token::mint = game_session.token_mint,
         associated_token::authority = vault,
     )]
     pub vault_token_account: Account<'info, TokenAccount>,
@@ -402,6 +577,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_at_cap_player_not_eligible() {
+        let mut session = GameSession::new(
+            "test".to_string(),
+            Pubkey::new_unique(),
+            MIN_BET_AMOUNT,
+            GameMode::PayToSpawnOneVsOne,
+            1000,
+            1,
+            2,
+        ).unwrap();
+        let player = Pubkey::new_unique();
+        session.seat_player(0, 0, player, 1000).unwrap();
+        session.seat_player(1, 0, Pubkey::new_unique(), 1000).unwrap();
+
+        // Not purchasable before the game starts
+        assert!(!spawn_eligibility(&session, 0, player, 1000).unwrap().eligible);
+
+        session.status = GameStatus::InProgress;
+        let open = spawn_eligibility(&session, 0, player, 1000).unwrap();
+        assert!(open.eligible);
+        assert_eq!(open.max_spawns, MAX_SPAWNS_PER_PLAYER);
+        assert_eq!(open.cost, MIN_BET_AMOUNT);
+
+        session.team_a.player_spawns[0] = MAX_SPAWNS_PER_PLAYER;
+        let capped = spawn_eligibility(&session, 0, player, 1000).unwrap();
+        assert!(!capped.eligible);
+        assert_eq!(capped.current_spawns, MAX_SPAWNS_PER_PLAYER);
+
+        // One bundle short of the cap is still too many
+        session.team_a.player_spawns[0] = MAX_SPAWNS_PER_PLAYER - 1;
+        assert!(!spawn_eligibility(&session, 0, player, 1000).unwrap().eligible);
+
+        assert!(spawn_eligibility(&session, 1, player, 1000).is_err());
+    }
+
     #[test]
     fn test_bet_amount_validation() {
         // Test valid bet amounts
@@ -450,6 +661,16 @@ mod tests {
         assert!(invalid_id.len() > MAX_SESSION_ID_LENGTH);
     }
 
+    #[test]
+    fn test_purchase_count_rate_limit() {
+        assert!(validate_purchase_count(MAX_SPAWN_PURCHASES_PER_TRANSACTION).is_ok());
+        assert_eq!(
+            validate_purchase_count(MAX_SPAWN_PURCHASES_PER_TRANSACTION + 1).unwrap_err(),
+            error!(WagerError::RateLimitExceeded)
+        );
+        assert!(validate_purchase_count(0).is_err());
+    }
+
     #[test]
     fn test_spawn_purchase_arithmetic() {
         let current_spawns = 15u16;
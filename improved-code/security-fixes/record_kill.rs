@@ -0,0 +1,122 @@
+use crate::{errors::WagerError, events::*, state::*};
+use anchor_lang::prelude::*;
+
+/// Records a kill reported by the game server. When the session requires
+/// victim acks the kill is held until the victim acknowledges it or the ack
+/// window lapses; any earlier kills whose window has lapsed are applied first.
+pub fn record_kill_handler(
+    ctx: Context<RecordKill>,
+    _session_id: String,
+    killer_team: u8,
+    killer: Pubkey,
+    victim_team: u8,
+    victim: Pubkey,
+    kill_weight: Option<u16>,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    let clock = Clock::get()?;
+    require!(!game_session.frozen, WagerError::SessionFrozen);
+
+    game_session.confirm_timed_out_kills(clock.unix_timestamp)?;
+
+    let applied = game_session.report_kill(
+        killer_team,
+        killer,
+        victim_team,
+        victim,
+        kill_weight.unwrap_or(DEFAULT_KILL_WEIGHT),
+        clock.unix_timestamp,
+    )?;
+    if !applied {
+        msg!("Kill of {} by {} awaiting victim acknowledgement", victim, killer);
+    }
+    Ok(())
+}
+
+/// Records a round's kills in one transaction. Any invalid entry fails the
+/// whole batch, and a batch that eliminates a team resolves the game.
+pub fn record_kills_batch_handler(
+    ctx: Context<RecordKill>,
+    session_id: String,
+    kills: Vec<KillRecord>,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    let clock = Clock::get()?;
+    require!(!game_session.frozen, WagerError::SessionFrozen);
+
+    game_session.confirm_timed_out_kills(clock.unix_timestamp)?;
+
+    let winning_team = game_session.record_kills_batch(&kills, clock.unix_timestamp)?;
+
+    msg!("Session {} recorded a batch of {} kills", session_id, kills.len());
+    if let Some(team) = winning_team {
+        msg!("Session {} resolved, team {} wins", session_id, team);
+        emit!(GameResolved {
+            session_id,
+            winning_team,
+        });
+    }
+    Ok(())
+}
+
+/// The victim co-signs a pending kill reported against them, applying it
+pub fn ack_kill_handler(ctx: Context<AckKill>, _session_id: String, killer: Pubkey) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    require!(!game_session.frozen, WagerError::SessionFrozen);
+
+    game_session.ack_kill(killer, ctx.accounts.victim.key())
+}
+
+/// Applies every pending kill whose ack window has lapsed (anyone may call)
+pub fn confirm_kills_handler(ctx: Context<ConfirmKills>, _session_id: String) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    let clock = Clock::get()?;
+    require!(!game_session.frozen, WagerError::SessionFrozen);
+
+    let confirmed = game_session.confirm_timed_out_kills(clock.unix_timestamp)?;
+
+    msg!("Confirmed {} unacknowledged kills, {} still pending",
+         confirmed, game_session.pending_kills.len());
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(_session_id: String)]
+pub struct RecordKill<'info> {
+    #[account(
+        mut,
+        seeds = [b"game_session", _session_id.as_bytes()],
+        bump = game_session.bump,
+        constraint = game_session.authority == game_server.key() @ WagerError::UnauthorizedKill,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    pub game_server: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_session_id: String)]
+pub struct AckKill<'info> {
+    #[account(
+        mut,
+        seeds = [b"game_session", _session_id.as_bytes()],
+        bump = game_session.bump,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    pub victim: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_session_id: String)]
+pub struct ConfirmKills<'info> {
+    #[account(
+        mut,
+        seeds = [b"game_session", _session_id.as_bytes()],
+        bump = game_session.bump,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// Anyone may confirm kills whose ack window has lapsed
+    pub caller: Signer<'info>,
+}
@@ -0,0 +1,435 @@
+use crate::{
+    errors::WagerError,
+    events::*,
+    instructions::{vault_transfer, verify_vault_drain},
+    state::*,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Token, TokenAccount};
+
+pub fn refund_wager_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RefundWager<'info>>,
+    session_id: String,
+) -> Result<()> {
+    let game_session = &ctx.accounts.game_session;
+    msg!("Starting Refund for session: {}", session_id);
+
+    let players = game_session.get_all_players();
+    msg!("Number of players: {}", players.len());
+    msg!(
+        "Number of remaining accounts: {}",
+        ctx.remaining_accounts.len()
+    );
+
+    // SECURITY FIX: Catch any external drain before moving funds
+    game_session.assert_vault_covers_contributions(ctx.accounts.vault_token_account.amount)?;
+
+    // We need at least one player and their token account
+    require!(
+        !ctx.remaining_accounts.is_empty(),
+        WagerError::InvalidRemainingAccounts
+    );
+
+    // Make sure remaining accounts are in pairs
+    require!(
+        ctx.remaining_accounts.len() % 2 == 0,
+        WagerError::InvalidRemainingAccounts
+    );
+
+    let vault_balance_before = ctx.accounts.vault_token_account.amount;
+    let mut refunded = Vec::new();
+
+    for player in players {
+        // Skip default player
+        if player == Pubkey::default() {
+            continue;
+        }
+
+        let (team, _) = game_session
+            .find_player(&player)
+            .ok_or(WagerError::PlayerNotFound)?;
+        let refund = game_session.team_bet(team)?;
+        msg!("Earnings for player {}: {}", player, refund);
+
+        // Find the player's account and token account in remaining_accounts
+        let player_index = ctx
+            .remaining_accounts
+            .iter()
+            .step_by(2) // Skip token accounts to only look at player accounts
+            .position(|acc| acc.key() == player)
+            .ok_or(WagerError::InvalidPlayer)?;
+
+        // Get player and token account from remaining accounts
+        let player_account = &ctx.remaining_accounts[player_index * 2];
+        let player_token_account_info = &ctx.remaining_accounts[player_index * 2 + 1];
+        let player_token_account = Account::<TokenAccount>::try_from(player_token_account_info)?;
+
+        // Verify player token account constraints
+        require!(
+            player_token_account.owner == player_account.key(),
+            WagerError::InvalidPlayerTokenAccount
+        );
+
+        // Verify token account mint
+        require!(
+            player_token_account.mint == game_session.token_mint,
+            WagerError::InvalidTokenMint
+        );
+
+        // Get vault balance before transfer
+        let vault_balance = ctx.accounts.vault_token_account.amount;
+        msg!("Vault balance before transfer: {}", vault_balance);
+
+        // Transfer tokens from vault to player
+        vault_transfer(
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault_token_account,
+            &ctx.accounts.vault,
+            &session_id,
+            ctx.accounts.game_session.vault_bump,
+            &player_token_account.to_account_info(),
+            refund,
+        )?;
+
+        emit!(TokenMoved {
+            session_id: session_id.clone(),
+            from: ctx.accounts.vault_token_account.key(),
+            to: player_token_account.key(),
+            amount: refund,
+            reason: TokenMoveReason::Refund as u8,
+        });
+
+        refunded.push((player, refund));
+    }
+
+    // Verify the vault dropped by exactly what was refunded
+    let total_transferred = refunded
+        .iter()
+        .try_fold(0u64, |total, (_, amount)| total.checked_add(*amount))
+        .ok_or(WagerError::ArithmeticError)?;
+    ctx.accounts.vault_token_account.reload()?;
+    verify_vault_drain(vault_balance_before, ctx.accounts.vault_token_account.amount, total_transferred)?;
+
+    // Mark session as completed
+    let game_session = &mut ctx.accounts.game_session;
+    for (player, refund) in refunded {
+        game_session.record_payout(player, refund)?;
+    }
+    game_session.status = GameStatus::Completed;
+
+    Ok(())
+}
+/// Refunds a drawn game in batches of up to MAX_REFUNDS_PER_BATCH players so a
+/// full 5v5 fits in compute. remaining_accounts holds [player, player_token_account]
+/// pairs for the next unrefunded players in get_all_players order; call again
+/// until the session is Distributed.
+pub fn refund_draw_batch_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RefundWager<'info>>,
+    session_id: String,
+) -> Result<()> {
+    let game_session = &ctx.accounts.game_session;
+
+    require!(
+        game_session.status == GameStatus::AwaitingDistribution && game_session.is_draw,
+        WagerError::InvalidGameState
+    );
+
+    // SECURITY FIX: Catch any external drain before moving funds
+    game_session.assert_vault_covers_contributions(ctx.accounts.vault_token_account.amount)?;
+
+    let batch = game_session.get_pending_draw_refunds(MAX_REFUNDS_PER_BATCH)?;
+    require!(
+        ctx.remaining_accounts.len() == batch.len() * 2,
+        WagerError::InvalidRemainingAccounts
+    );
+
+    let total_refund = batch
+        .iter()
+        .try_fold(0u64, |total, (_, amount)| total.checked_add(*amount))
+        .ok_or(WagerError::ArithmeticError)?;
+
+    let vault_balance_before = ctx.accounts.vault_token_account.amount;
+    require!(
+        vault_balance_before >= total_refund,
+        WagerError::InsufficientVaultBalance
+    );
+
+    for (i, (player, amount)) in batch.iter().enumerate() {
+        let player_account = &ctx.remaining_accounts[i * 2];
+        let player_token_account_info = &ctx.remaining_accounts[i * 2 + 1];
+
+        require!(
+            player_account.key() == *player,
+            WagerError::InvalidPlayer
+        );
+
+        let player_token_account = Account::<TokenAccount>::try_from(player_token_account_info)
+            .map_err(|_| error!(WagerError::InvalidPlayerTokenAccount))?;
+
+        require!(
+            player_token_account.owner == *player,
+            WagerError::InvalidPlayerTokenAccount
+        );
+
+        require!(
+            player_token_account.mint == game_session.token_mint,
+            WagerError::InvalidTokenMint
+        );
+
+        if *amount == 0 {
+            continue;
+        }
+
+        vault_transfer(
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault_token_account,
+            &ctx.accounts.vault,
+            &session_id,
+            game_session.vault_bump,
+            player_token_account_info,
+            *amount,
+        )?;
+
+        emit!(TokenMoved {
+            session_id: session_id.clone(),
+            from: ctx.accounts.vault_token_account.key(),
+            to: player_token_account_info.key(),
+            amount: *amount,
+            reason: TokenMoveReason::Refund as u8,
+        });
+    }
+
+    ctx.accounts.vault_token_account.reload()?;
+    verify_vault_drain(vault_balance_before, ctx.accounts.vault_token_account.amount, total_refund)?;
+
+    let game_session = &mut ctx.accounts.game_session;
+    for (player, amount) in &batch {
+        game_session.record_payout(*player, *amount)?;
+        game_session.mark_refunded(*player)?;
+    }
+
+    let remaining = game_session.get_pending_draw_refunds(MAX_REFUNDS_PER_BATCH)?.len();
+    if remaining == 0 {
+        game_session.status = GameStatus::Distributed;
+        msg!("Draw refund completed for session {}", session_id);
+    } else {
+        msg!("Refunded {} players, more remain for session {}", batch.len(), session_id);
+    }
+
+    Ok(())
+}
+
+/// Permissionless keeper call that flips a lobby left waiting past its expiry
+/// to Expired, closing it to joins. Its players are then refunded through
+/// settle_unfilled or claim_walkover.
+pub fn expire_session_handler(ctx: Context<ExpireSession>, session_id: String) -> Result<()> {
+    let clock = Clock::get()?;
+
+    ctx.accounts.game_session.expire(clock.unix_timestamp)?;
+
+    msg!("Session {} expired by keeper {}", session_id, ctx.accounts.keeper.key());
+    Ok(())
+}
+
+/// Permissionless refund of a session that expired before it filled. Every
+/// seated player gets back exactly what they contributed, whichever teams
+/// they sat on, and the session is Cancelled. remaining_accounts holds
+/// [player, player_token_account] pairs in get_all_players order.
+pub fn settle_unfilled_handler<'info>(
+    mut ctx: Context<'_, '_, 'info, 'info, SettleUnfilled<'info>>,
+    session_id: String,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let refunds = ctx.accounts.game_session.get_unfilled_refunds(clock.unix_timestamp)?;
+
+    let total_refund = refund_and_cancel(&mut ctx, &session_id, &refunds)?;
+
+    msg!("Unfilled session {} settled: {} tokens refunded to {} players",
+         session_id, total_refund, refunds.len());
+    Ok(())
+}
+
+/// Permissionless walkover once an expired session has one team full and the
+/// other empty: the full team's bets are refunded, nobody is paid winnings,
+/// and the session is Cancelled. A partial fill on both sides is not a
+/// walkover and goes through settle_unfilled instead.
+/// remaining_accounts holds [player, player_token_account] pairs in get_all_players order.
+pub fn claim_walkover_handler<'info>(
+    mut ctx: Context<'_, '_, 'info, 'info, SettleUnfilled<'info>>,
+    session_id: String,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let refunds = ctx.accounts.game_session.get_walkover_refunds(clock.unix_timestamp)?;
+
+    let total_refund = refund_and_cancel(&mut ctx, &session_id, &refunds)?;
+
+    msg!("Walkover claimed in session {}: {} tokens refunded to {} players",
+         session_id, total_refund, refunds.len());
+    Ok(())
+}
+
+/// Pays each (player, amount) refund from the vault against the matching
+/// remaining_accounts pair, records the payouts and cancels the session.
+/// Returns the total refunded.
+fn refund_and_cancel<'info>(
+    ctx: &mut Context<'_, '_, 'info, 'info, SettleUnfilled<'info>>,
+    session_id: &str,
+    refunds: &[(Pubkey, u64)],
+) -> Result<u64> {
+    let game_session = &ctx.accounts.game_session;
+
+    require!(
+        ctx.remaining_accounts.len() == refunds.len() * 2,
+        WagerError::InvalidRemainingAccounts
+    );
+
+    // SECURITY FIX: Catch any external drain before moving funds
+    game_session.assert_vault_covers_contributions(ctx.accounts.vault_token_account.amount)?;
+
+    let total_refund = refunds
+        .iter()
+        .try_fold(0u64, |total, (_, amount)| total.checked_add(*amount))
+        .ok_or(WagerError::ArithmeticError)?;
+
+    let vault_balance_before = ctx.accounts.vault_token_account.amount;
+    require!(
+        vault_balance_before >= total_refund,
+        WagerError::InsufficientVaultBalance
+    );
+
+    for (i, (player, amount)) in refunds.iter().enumerate() {
+        let player_account = &ctx.remaining_accounts[i * 2];
+        let player_token_account_info = &ctx.remaining_accounts[i * 2 + 1];
+
+        require!(
+            player_account.key() == *player,
+            WagerError::InvalidPlayer
+        );
+
+        let player_token_account = Account::<TokenAccount>::try_from(player_token_account_info)
+            .map_err(|_| error!(WagerError::InvalidPlayerTokenAccount))?;
+
+        require!(
+            player_token_account.owner == *player,
+            WagerError::InvalidPlayerTokenAccount
+        );
+
+        require!(
+            player_token_account.mint == game_session.token_mint,
+            WagerError::InvalidTokenMint
+        );
+
+        if *amount == 0 {
+            continue;
+        }
+
+        vault_transfer(
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault_token_account,
+            &ctx.accounts.vault,
+            session_id,
+            game_session.vault_bump,
+            player_token_account_info,
+            *amount,
+        )?;
+
+        emit!(TokenMoved {
+            session_id: session_id.to_string(),
+            from: ctx.accounts.vault_token_account.key(),
+            to: player_token_account_info.key(),
+            amount: *amount,
+            reason: TokenMoveReason::Refund as u8,
+        });
+    }
+
+    ctx.accounts.vault_token_account.reload()?;
+    verify_vault_drain(vault_balance_before, ctx.accounts.vault_token_account.amount, total_refund)?;
+
+    let game_session = &mut ctx.accounts.game_session;
+    for (player, amount) in refunds {
+        game_session.record_payout(*player, *amount)?;
+    }
+    game_session.status = GameStatus::Cancelled;
+
+    Ok(total_refund)
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct RefundWager<'info> {
+    /// The game server authority that created the session
+    pub game_server: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+        constraint = game_session.authority == game_server.key() @ WagerError::UnauthorizedDistribution,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: Vault PDA that holds the funds
+    #[account(
+        mut,
+        seeds = [b"vault", session_id.as_bytes()],
+        bump = game_session.vault_bump,
+    )]
+    pub vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = game_session.token_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct ExpireSession<'info> {
+    /// Anyone may expire a stale lobby
+    pub keeper: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+    )]
+    pub game_session: Account<'info, GameSession>,
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct SettleUnfilled<'info> {
+    /// Anyone may settle a session that expired unfilled or claim a walkover
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: Vault PDA that holds the funds
+    #[account(
+        seeds = [b"vault", session_id.as_bytes()],
+        bump = game_session.vault_bump,
+    )]
+    pub vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = game_session.token_mint,
+        associated_token::authority = vault,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
@@ -0,0 +1,68 @@
+use crate::{
+    errors::WagerError,
+    instructions::{sol_vault_balance, validate_new_session},
+    state::*,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+/// Recycles a Distributed or Cancelled session's accounts for a new game with
+/// a fresh lobby, bet, mode and expiry, saving the rent of a new PDA
+/// (authority only). The vault must already be empty; sweep any dust first.
+pub fn reset_session_handler(
+    ctx: Context<ResetSession>,
+    session_id: String,
+    new_bet: u64,
+    new_mode: GameMode,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    validate_new_session(&session_id, new_bet, new_mode)?;
+
+    let vault_balance = if ctx.accounts.game_session.is_sol {
+        sol_vault_balance(&ctx.accounts.vault)?
+    } else {
+        ctx.accounts
+            .vault_token_account
+            .as_ref()
+            .ok_or(WagerError::InvalidVaultTokenAccount)?
+            .amount
+    };
+
+    let game_session = &mut ctx.accounts.game_session;
+    game_session.reset(new_bet, new_mode, clock.unix_timestamp, vault_balance)?;
+
+    msg!("Session {} reset: {} per player, mode {}, expires at {}",
+         session_id, new_bet, new_mode as u8, game_session.expires_at);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct ResetSession<'info> {
+    #[account(
+        constraint = authority.key() == game_session.authority @ WagerError::UnauthorizedAction,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: Vault PDA, holding lamports directly for SOL sessions
+    #[account(
+        seeds = [b"vault", session_id.as_bytes()],
+        bump = game_session.vault_bump,
+    )]
+    pub vault: AccountInfo<'info>,
+
+    /// The vault's token account; omitted for SOL sessions
+    #[account(
+        associated_token::mint = game_session.token_mint,
+        associated_token::authority = vault,
+    )]
+    pub vault_token_account: Option<Account<'info, TokenAccount>>,
+}
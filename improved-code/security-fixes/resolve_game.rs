@@ -0,0 +1,124 @@
+use crate::{errors::WagerError, events::*, instructions::{GetGameStatus, UpdateSessionConfig}, state::*};
+use anchor_lang::prelude::*;
+
+/// Resolves an in-progress game once a team is eliminated or the session expires.
+/// Mutual eliminations and expiries fall back to total kills, and an exact kill
+/// tie goes to the session's tie breaker, settling as a draw if still unbroken.
+pub fn resolve_game_handler(ctx: Context<ResolveGame>, session_id: String) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    let player_count = game_session.game_mode.players_per_team();
+    let clock = Clock::get()?;
+
+    require!(
+        game_session.status == GameStatus::InProgress,
+        WagerError::GameNotInProgress
+    );
+
+    // Kills whose ack window lapsed count toward the result
+    game_session.confirm_timed_out_kills(clock.unix_timestamp)?;
+
+    // Free-for-all ends with the last survivor or at expiry; the top
+    // kills-plus-spawns player wins
+    if game_session.game_mode.is_free_for_all() {
+        require!(
+            game_session.get_free_for_all_survivors() <= 1
+                || game_session.is_expired(clock.unix_timestamp),
+            WagerError::GameNotResolvable
+        );
+
+        game_session.mark_free_for_all_resolved()?;
+
+        match game_session.free_for_all_winner() {
+            Some(player) => msg!("Session {} resolved, player {} wins", session_id, player),
+            None => msg!("Session {} resolved as a draw on top score", session_id),
+        }
+
+        emit!(GameResolved {
+            session_id,
+            winning_team: None,
+        });
+
+        return Ok(());
+    }
+
+    require!(
+        game_session.team_a.is_eliminated(player_count)
+            || game_session.team_b.is_eliminated(player_count)
+            || game_session.is_expired(clock.unix_timestamp),
+        WagerError::GameNotResolvable
+    );
+
+    let winning_team = game_session
+        .check_winner()
+        .or_else(|| game_session.resolve_by_kills())
+        .or_else(|| game_session.resolve_expired_by_kills(clock.unix_timestamp));
+
+    game_session.mark_resolved(winning_team)?;
+
+    match winning_team {
+        Some(team) => msg!("Session {} resolved, team {} wins", session_id, team),
+        None => msg!("Session {} resolved as a draw on kills", session_id),
+    }
+
+    emit!(GameResolved {
+        session_id,
+        winning_team,
+    });
+
+    Ok(())
+}
+
+/// Ends an in-progress pay-to-spawn match before elimination or expiry so its
+/// earnings can be distributed. Further spawn purchases are rejected afterwards.
+pub fn end_match_handler(ctx: Context<ResolveGame>, session_id: String) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    game_session.end_match()?;
+
+    msg!("Session {} match ended by authority", session_id);
+    Ok(())
+}
+
+/// Returns the outcome determine_final_winner would settle on and each team's
+/// kill and spawn totals via return data, without touching the session
+pub fn get_winner_handler(ctx: Context<GetGameStatus>, _session_id: String) -> Result<()> {
+    let game_session = &ctx.accounts.game_session;
+    let clock = Clock::get()?;
+
+    let view = game_session.winner_view(clock.unix_timestamp)?;
+
+    msg!("Session {} winner: {:?}, kills {}-{}, spawns {}-{}",
+         game_session.session_id, view.outcome, view.team_a_kills, view.team_b_kills,
+         view.team_a_spawns, view.team_b_spawns);
+
+    anchor_lang::solana_program::program::set_return_data(&view.try_to_vec()?);
+    Ok(())
+}
+
+/// Update how exact kill ties are broken (authority only, before the game starts)
+pub fn update_kill_tie_breaker_handler(
+    ctx: Context<UpdateSessionConfig>,
+    _session_id: String,
+    tie_breaker: KillTieBreaker,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    game_session.update_kill_tie_breaker(tie_breaker)?;
+
+    msg!("Kill tie breaker updated for session {}", game_session.session_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct ResolveGame<'info> {
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+        constraint = game_session.authority == game_server.key() @ WagerError::UnauthorizedAction,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    pub game_server: Signer<'info>,
+}
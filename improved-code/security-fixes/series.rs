@@ -0,0 +1,487 @@
+use crate::instructions::{vault_transfer, verify_vault_drain};
+use crate::{errors::WagerError, events::*, state::*};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+/// Space required for a GameSeries account
+pub const GAME_SERIES_SPACE: usize = 8 + // discriminator
+    4 + MAX_SESSION_ID_LENGTH + // series_id (String)
+    32 +                   // authority (Pubkey)
+    1 +                    // rounds_needed (u8)
+    1 +                    // team_a_wins (u8)
+    1 +                    // team_b_wins (u8)
+    1 +                    // rounds_played (u8)
+    32 +                   // current_session (Pubkey)
+    2 +                    // winner (Option<u8>)
+    1 +                    // finalized (bool)
+    1 +                    // bump (u8)
+    1 +                    // vault_bump (u8)
+    32 * MAX_PLAYERS_PER_TEAM + // team_a_players ([Pubkey; MAX_PLAYERS_PER_TEAM])
+    32 * MAX_PLAYERS_PER_TEAM;  // team_b_players ([Pubkey; MAX_PLAYERS_PER_TEAM])
+
+/// Starts a best-of-N series with `first_session` as round one. Rounds must
+/// be winner-takes-all team games so each has a single winning side.
+pub fn start_series_handler(
+    ctx: Context<StartSeries>,
+    series_id: String,
+    rounds_needed: u8,
+) -> Result<()> {
+    require!(
+        series_id.len() <= MAX_SESSION_ID_LENGTH,
+        WagerError::SessionIdTooLong
+    );
+    require!(
+        rounds_needed > 0 && rounds_needed <= MAX_SERIES_ROUNDS_NEEDED,
+        WagerError::InvalidSeriesRounds
+    );
+
+    let first_session = &mut ctx.accounts.first_session;
+    require!(
+        !first_session.is_pay_to_spawn() && !first_session.game_mode.is_free_for_all(),
+        WagerError::InvalidGameMode
+    );
+    require!(!first_session.is_series_round(), WagerError::SeriesRoundMismatch);
+    require!(
+        first_session.status == GameStatus::WaitingForPlayers,
+        WagerError::SeriesRoundMismatch
+    );
+
+    let series = &mut ctx.accounts.series;
+    series.series_id = series_id.clone();
+    series.authority = ctx.accounts.authority.key();
+    series.rounds_needed = rounds_needed;
+    series.team_a_wins = 0;
+    series.team_b_wins = 0;
+    series.rounds_played = 0;
+    series.current_session = first_session.key();
+    series.winner = None;
+    series.finalized = false;
+    series.bump = ctx.bumps.series;
+    series.vault_bump = ctx.bumps.series_vault;
+    series.team_a_players = [Pubkey::default(); MAX_PLAYERS_PER_TEAM];
+    series.team_b_players = [Pubkey::default(); MAX_PLAYERS_PER_TEAM];
+
+    first_session.series = series.key();
+
+    msg!("Series {} started: first to {} round wins, round one is {}",
+         series_id, rounds_needed, first_session.session_id);
+    Ok(())
+}
+
+/// Records the finished current round, sweeps its pot into the series vault
+/// and, while the series is undecided, links `next_session` as the next round
+pub fn advance_series_handler(ctx: Context<AdvanceSeries>, series_id: String) -> Result<()> {
+    let round_session = &ctx.accounts.round_session;
+    require!(
+        round_session.status == GameStatus::AwaitingDistribution,
+        WagerError::InvalidGameState
+    );
+
+    // Every round is played by round one's players on the same sides
+    ctx.accounts.series.check_round_roster(round_session)?;
+
+    let round_winner = if round_session.is_draw {
+        None
+    } else {
+        round_session.winning_team
+    };
+    let round_session_id = round_session.session_id.clone();
+    let round_vault_bump = round_session.vault_bump;
+
+    // Move the round's whole pot into the series vault
+    let pot = ctx.accounts.round_vault_token_account.amount;
+    if pot > 0 {
+        vault_transfer(
+            &ctx.accounts.token_program,
+            &ctx.accounts.round_vault_token_account,
+            &ctx.accounts.round_vault,
+            &round_session_id,
+            round_vault_bump,
+            &ctx.accounts.series_vault_token_account.to_account_info(),
+            pot,
+        )?;
+
+        ctx.accounts.round_vault_token_account.reload()?;
+        verify_vault_drain(pot, ctx.accounts.round_vault_token_account.amount, pot)?;
+
+        emit!(TokenMoved {
+            session_id: round_session_id.clone(),
+            from: ctx.accounts.round_vault_token_account.key(),
+            to: ctx.accounts.series_vault_token_account.key(),
+            amount: pot,
+            reason: TokenMoveReason::Sweep as u8,
+        });
+    }
+
+    let round_session = &mut ctx.accounts.round_session;
+    round_session.total_distributed = round_session
+        .total_distributed
+        .checked_add(pot)
+        .ok_or(WagerError::ArithmeticError)?;
+    round_session.status = GameStatus::Distributed;
+
+    let series = &mut ctx.accounts.series;
+    series.record_round(round_winner)?;
+
+    msg!("Series {} round {} recorded: winner {:?}, score {}-{}",
+         series_id, series.rounds_played, round_winner, series.team_a_wins, series.team_b_wins);
+
+    if series.winner.is_some() {
+        msg!("Series {} decided for team {:?}", series_id, series.winner);
+        return Ok(());
+    }
+
+    let next_session = ctx
+        .accounts
+        .next_session
+        .as_mut()
+        .ok_or(WagerError::SeriesRoundMismatch)?;
+    ctx.accounts.round_session.validate_next_series_round(next_session)?;
+    next_session.series = series.key();
+    series.current_session = next_session.key();
+
+    msg!("Series {} next round is {}", series_id, next_session.session_id);
+    Ok(())
+}
+
+/// Pays the series pot to the winning side once it has reached rounds_needed.
+/// The winners are the winning team of the deciding round.
+/// remaining_accounts: [winner, winner_token_account] per active winner
+pub fn finalize_series_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, FinalizeSeries<'info>>,
+    series_id: String,
+) -> Result<()> {
+    let series = &ctx.accounts.series;
+    let winning_team = series.winner.ok_or(WagerError::SeriesNotDecided)?;
+    require!(!series.finalized, WagerError::SessionAlreadyFinalized);
+
+    let winners: Vec<Pubkey> = ctx
+        .accounts
+        .final_session
+        .get_team_roster(winning_team)?
+        .into_iter()
+        .map(|(player, _, _)| player)
+        .collect();
+    require!(!winners.is_empty(), WagerError::InvalidWinner);
+    require!(
+        ctx.remaining_accounts.len() == winners.len() * 2,
+        WagerError::InvalidRemainingAccounts
+    );
+
+    let pot = ctx.accounts.series_vault_token_account.amount;
+    let shares = GameSeries::split_pot(pot, winners.len())?;
+
+    for (i, (winner, share)) in winners.iter().zip(shares.iter()).enumerate() {
+        let winner_account = &ctx.remaining_accounts[i * 2];
+        let winner_token_account_info = &ctx.remaining_accounts[i * 2 + 1];
+
+        require!(winner_account.key() == *winner, WagerError::InvalidWinner);
+
+        let winner_token_account = Account::<TokenAccount>::try_from(winner_token_account_info)
+            .map_err(|_| error!(WagerError::InvalidWinnerTokenAccount))?;
+        require!(
+            winner_token_account.owner == *winner,
+            WagerError::InvalidWinnerTokenAccount
+        );
+        require!(
+            winner_token_account.mint == ctx.accounts.series_vault_token_account.mint,
+            WagerError::InvalidTokenMint
+        );
+
+        if *share == 0 {
+            continue;
+        }
+
+        anchor_spl::token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                anchor_spl::token::Transfer {
+                    from: ctx.accounts.series_vault_token_account.to_account_info(),
+                    to: winner_token_account_info.to_account_info(),
+                    authority: ctx.accounts.series_vault.to_account_info(),
+                },
+                &[&[
+                    b"series_vault",
+                    series_id.as_bytes(),
+                    &[ctx.accounts.series.vault_bump],
+                ]],
+            ),
+            *share,
+        )?;
+
+        emit!(TokenMoved {
+            session_id: series_id.clone(),
+            from: ctx.accounts.series_vault_token_account.key(),
+            to: winner_token_account_info.key(),
+            amount: *share,
+            reason: TokenMoveReason::Distribution as u8,
+        });
+    }
+
+    ctx.accounts.series_vault_token_account.reload()?;
+    verify_vault_drain(pot, ctx.accounts.series_vault_token_account.amount, pot)?;
+
+    ctx.accounts.series.finalized = true;
+
+    msg!("Series {} finalized: {} tokens paid to {} winners on team {}",
+         series_id, pot, winners.len(), winning_team);
+    Ok(())
+}
+
+/// Refunds the pot of a series that will never be decided, either abandoned
+/// by its authority or stalled past the current round's expiry (anyone may
+/// call then). The pot goes back to round one's players by team bet, and the
+/// current round is released from the series so its own stakes settle
+/// through the standalone paths.
+/// remaining_accounts: [player, player_token_account] per refund_shares entry
+pub fn refund_series_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RefundSeries<'info>>,
+    series_id: String,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let series = &ctx.accounts.series;
+    let current_session = &ctx.accounts.current_session;
+
+    require!(series.winner.is_none(), WagerError::SeriesAlreadyDecided);
+    require!(!series.finalized, WagerError::SessionAlreadyFinalized);
+    require!(
+        ctx.accounts.caller.key() == series.authority
+            || current_session.is_expired(clock.unix_timestamp),
+        WagerError::SeriesStillActive
+    );
+
+    let pot = ctx.accounts.series_vault_token_account.amount;
+    let refunds = series.refund_shares(current_session.team_a_bet, current_session.team_b_bet, pot)?;
+    require!(
+        ctx.remaining_accounts.len() == refunds.len() * 2,
+        WagerError::InvalidRemainingAccounts
+    );
+
+    for (i, (player, amount)) in refunds.iter().enumerate() {
+        let player_account = &ctx.remaining_accounts[i * 2];
+        let player_token_account_info = &ctx.remaining_accounts[i * 2 + 1];
+
+        require!(player_account.key() == *player, WagerError::InvalidPlayer);
+
+        let player_token_account = Account::<TokenAccount>::try_from(player_token_account_info)
+            .map_err(|_| error!(WagerError::InvalidPlayerTokenAccount))?;
+        require!(
+            player_token_account.owner == *player,
+            WagerError::InvalidPlayerTokenAccount
+        );
+        require!(
+            player_token_account.mint == ctx.accounts.series_vault_token_account.mint,
+            WagerError::InvalidTokenMint
+        );
+
+        if *amount == 0 {
+            continue;
+        }
+
+        anchor_spl::token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                anchor_spl::token::Transfer {
+                    from: ctx.accounts.series_vault_token_account.to_account_info(),
+                    to: player_token_account_info.to_account_info(),
+                    authority: ctx.accounts.series_vault.to_account_info(),
+                },
+                &[&[
+                    b"series_vault",
+                    series_id.as_bytes(),
+                    &[ctx.accounts.series.vault_bump],
+                ]],
+            ),
+            *amount,
+        )?;
+
+        emit!(TokenMoved {
+            session_id: series_id.clone(),
+            from: ctx.accounts.series_vault_token_account.key(),
+            to: player_token_account_info.key(),
+            amount: *amount,
+            reason: TokenMoveReason::Refund as u8,
+        });
+    }
+
+    ctx.accounts.series_vault_token_account.reload()?;
+    verify_vault_drain(pot, ctx.accounts.series_vault_token_account.amount, pot)?;
+
+    ctx.accounts.series.finalized = true;
+    ctx.accounts.current_session.series = Pubkey::default();
+
+    msg!("Series {} refunded: {} tokens returned to {} players after {} rounds",
+         series_id, pot, refunds.len(), ctx.accounts.series.rounds_played);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(series_id: String)]
+pub struct StartSeries<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == first_session.authority @ WagerError::UnauthorizedAction,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = GAME_SERIES_SPACE,
+        seeds = [b"series", series_id.as_bytes()],
+        bump
+    )]
+    pub series: Account<'info, GameSeries>,
+
+    #[account(mut)]
+    pub first_session: Account<'info, GameSession>,
+
+    /// CHECK: Series vault PDA that holds the swept round pots
+    #[account(
+        init,
+        payer = authority,
+        space = 0,
+        seeds = [b"series_vault", series_id.as_bytes()],
+        bump
+    )]
+    pub series_vault: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = series_vault,
+    )]
+    pub series_vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(address = first_session.token_mint @ WagerError::InvalidMint)]
+    pub mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(series_id: String)]
+pub struct AdvanceSeries<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"series", series_id.as_bytes()],
+        bump = series.bump,
+        has_one = authority @ WagerError::UnauthorizedAction,
+    )]
+    pub series: Account<'info, GameSeries>,
+
+    #[account(
+        mut,
+        address = series.current_session @ WagerError::SeriesRoundMismatch,
+    )]
+    pub round_session: Account<'info, GameSession>,
+
+    /// CHECK: Vault PDA of the round being recorded
+    #[account(
+        seeds = [b"vault", round_session.session_id.as_bytes()],
+        bump = round_session.vault_bump,
+    )]
+    pub round_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = round_session.token_mint,
+        associated_token::authority = round_vault,
+    )]
+    pub round_vault_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Series vault PDA that holds the swept round pots
+    #[account(
+        seeds = [b"series_vault", series_id.as_bytes()],
+        bump = series.vault_bump,
+    )]
+    pub series_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = round_session.token_mint,
+        associated_token::authority = series_vault,
+    )]
+    pub series_vault_token_account: Account<'info, TokenAccount>,
+
+    /// Next round, required unless this round decides the series
+    #[account(mut)]
+    pub next_session: Option<Account<'info, GameSession>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(series_id: String)]
+pub struct FinalizeSeries<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"series", series_id.as_bytes()],
+        bump = series.bump,
+        has_one = authority @ WagerError::UnauthorizedAction,
+    )]
+    pub series: Account<'info, GameSeries>,
+
+    #[account(address = series.current_session @ WagerError::SeriesRoundMismatch)]
+    pub final_session: Account<'info, GameSession>,
+
+    /// CHECK: Series vault PDA that holds the swept round pots
+    #[account(
+        seeds = [b"series_vault", series_id.as_bytes()],
+        bump = series.vault_bump,
+    )]
+    pub series_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = final_session.token_mint,
+        associated_token::authority = series_vault,
+    )]
+    pub series_vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(series_id: String)]
+pub struct RefundSeries<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"series", series_id.as_bytes()],
+        bump = series.bump,
+    )]
+    pub series: Account<'info, GameSeries>,
+
+    #[account(
+        mut,
+        address = series.current_session @ WagerError::SeriesRoundMismatch,
+    )]
+    pub current_session: Account<'info, GameSession>,
+
+    /// CHECK: Series vault PDA that holds the swept round pots
+    #[account(
+        seeds = [b"series_vault", series_id.as_bytes()],
+        bump = series.vault_bump,
+    )]
+    pub series_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = current_session.token_mint,
+        associated_token::authority = series_vault,
+    )]
+    pub series_vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
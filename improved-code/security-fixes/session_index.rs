@@ -0,0 +1,117 @@
+use crate::{errors::WagerError, state::*};
+use anchor_lang::prelude::*;
+
+/// Space required for an AuthoritySessionIndex account
+pub const SESSION_INDEX_SPACE: usize = 8 + // discriminator
+    32 +                   // authority (Pubkey)
+    4 + 32 * MAX_SESSIONS_PER_AUTHORITY + // sessions (Vec<Pubkey>)
+    1;                     // bump (u8)
+
+/// Creates the index a game server passes to create_game_session and
+/// cancel_session to keep its list of active sessions
+pub fn initialize_session_index_handler(ctx: Context<InitializeSessionIndex>) -> Result<()> {
+    let session_index = &mut ctx.accounts.session_index;
+
+    session_index.authority = ctx.accounts.authority.key();
+    session_index.sessions = Vec::new();
+    session_index.bump = ctx.bumps.session_index;
+
+    msg!("Session index initialized for {}", session_index.authority);
+    Ok(())
+}
+
+/// Drops a distributed session from the index once it is settled (authority only)
+pub fn prune_session_index_handler(ctx: Context<PruneSessionIndex>, _session_id: String) -> Result<()> {
+    let game_session = &ctx.accounts.game_session;
+    require!(
+        game_session.status == GameStatus::Distributed
+            || game_session.status == GameStatus::Cancelled,
+        WagerError::InvalidGameState
+    );
+
+    let session_key = game_session.key();
+    ctx.accounts.session_index.remove_session(session_key);
+
+    msg!("Session {} removed from index of {}",
+         game_session.session_id, ctx.accounts.authority.key());
+    Ok(())
+}
+
+/// Returns the authority's active session PDAs via return data
+pub fn get_active_sessions_handler(ctx: Context<GetActiveSessions>) -> Result<()> {
+    let session_index = &ctx.accounts.session_index;
+
+    msg!("Authority {} has {} active sessions",
+         session_index.authority, session_index.sessions.len());
+
+    anchor_lang::solana_program::program::set_return_data(&session_index.sessions.try_to_vec()?);
+    Ok(())
+}
+
+/// Returns how many sessions `authority` has active and the most it may have
+/// via return data, for a quota display
+pub fn get_authority_stats_handler(ctx: Context<GetAuthorityStats>, authority: Pubkey) -> Result<()> {
+    let quota = ctx.accounts.session_index.quota();
+
+    msg!("Authority {} has {} of {} sessions active",
+         authority, quota.active_sessions, quota.max_sessions);
+
+    anchor_lang::solana_program::program::set_return_data(&quota.try_to_vec()?);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeSessionIndex<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = SESSION_INDEX_SPACE,
+        seeds = [b"session_index", authority.key().as_ref()],
+        bump
+    )]
+    pub session_index: Account<'info, AuthoritySessionIndex>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct PruneSessionIndex<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"session_index", authority.key().as_ref()],
+        bump = session_index.bump,
+    )]
+    pub session_index: Account<'info, AuthoritySessionIndex>,
+
+    #[account(
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+        constraint = game_session.authority == authority.key() @ WagerError::UnauthorizedAction,
+    )]
+    pub game_session: Account<'info, GameSession>,
+}
+
+#[derive(Accounts)]
+pub struct GetActiveSessions<'info> {
+    #[account(
+        seeds = [b"session_index", session_index.authority.as_ref()],
+        bump = session_index.bump,
+    )]
+    pub session_index: Account<'info, AuthoritySessionIndex>,
+}
+
+#[derive(Accounts)]
+#[instruction(authority: Pubkey)]
+pub struct GetAuthorityStats<'info> {
+    #[account(
+        seeds = [b"session_index", authority.as_ref()],
+        bump = session_index.bump,
+    )]
+    pub session_index: Account<'info, AuthoritySessionIndex>,
+}
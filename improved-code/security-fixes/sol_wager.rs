@@ -0,0 +1,629 @@
+use crate::{
+    errors::WagerError,
+    events::*,
+    instructions::{validate_join, validate_new_session, GAME_SESSION_SPACE},
+    state::*,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+/// SOL sessions are priced in lamports
+const LAMPORT_DECIMALS: u8 = 9;
+
+/// Creates a session wagered in native SOL. Stakes are escrowed as lamports on
+/// the vault PDA itself, which is created rent-exempt so that reserve is never
+/// counted as pot or paid out.
+pub fn create_sol_session_handler(
+    ctx: Context<CreateSolSession>,
+    session_id: String,
+    bet_amount: u64,
+    game_mode: GameMode,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let authority = ctx.accounts.game_server.key();
+
+    validate_new_session(&session_id, bet_amount, game_mode)?;
+
+    let game_session = &mut ctx.accounts.game_session;
+    game_session.set_inner(GameSession::new(
+        session_id.clone(),
+        authority,
+        bet_amount,
+        game_mode,
+        clock.unix_timestamp,
+        ctx.bumps.game_session,
+        ctx.bumps.vault,
+    )?);
+    game_session.token_decimals = LAMPORT_DECIMALS;
+    game_session.is_sol = true;
+
+    msg!("SOL game session '{}' created by {}: {} lamports per player, mode {}",
+         session_id, authority, bet_amount, game_mode as u8);
+    Ok(())
+}
+
+/// Joins a SOL session, moving the team's bet in lamports from the player to the vault
+pub fn join_sol_handler(ctx: Context<JoinSol>, session_id: String, team: u8) -> Result<()> {
+    let clock = Clock::get()?;
+    let player_key = ctx.accounts.user.key();
+    let game_session = &mut ctx.accounts.game_session;
+
+    require!(game_session.is_sol, WagerError::CurrencyMismatch);
+
+    let empty_index = validate_join(
+        game_session,
+        &player_key,
+        team,
+        ctx.accounts.user.lamports(),
+        clock.unix_timestamp,
+    )?;
+    let session_bet = game_session.team_bet(team)?;
+
+    let vault_balance_before = sol_vault_balance(&ctx.accounts.vault)?;
+    let expected_vault_balance = vault_balance_before
+        .checked_add(session_bet)
+        .ok_or(WagerError::ArithmeticError)?;
+    game_session.check_vault_cap(expected_vault_balance)?;
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.user.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+            },
+        ),
+        session_bet,
+    )?;
+
+    require!(
+        sol_vault_balance(&ctx.accounts.vault)? == expected_vault_balance,
+        WagerError::TransferVerificationFailed
+    );
+
+    emit!(TokenMoved {
+        session_id: session_id.clone(),
+        from: player_key,
+        to: ctx.accounts.vault.key(),
+        amount: session_bet,
+        reason: TokenMoveReason::Join as u8,
+    });
+
+    game_session.seat_player(team, empty_index, player_key, clock.unix_timestamp)?;
+    msg!("Player {} joined SOL session {} on team {} at index {}",
+         player_key, session_id, team, empty_index);
+
+    if game_session.can_start()? && game_session.min_start_gap_elapsed(clock.unix_timestamp) {
+        game_session.start(clock.unix_timestamp)?;
+        msg!("Game session {} is now in progress", session_id);
+    }
+
+    Ok(())
+}
+
+/// Pays a resolved winner-takes-all SOL session in lamports: the winning
+/// team's shares of the pot, or every player's stake back when `winning_team`
+/// is DRAW_TEAM. remaining_accounts holds one writable account per recipient,
+/// in winner_shares order for a win and get_all_players order for a draw.
+pub fn distribute_sol_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributeSol<'info>>,
+    session_id: String,
+    winning_team: u8,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    require!(game_session.is_sol, WagerError::CurrencyMismatch);
+    require!(
+        game_session.status == GameStatus::AwaitingDistribution,
+        WagerError::InvalidGameState
+    );
+    require!(
+        !game_session.is_pay_to_spawn() && !game_session.game_mode.is_free_for_all(),
+        WagerError::InvalidGameMode
+    );
+    require!(
+        winning_team == 0 || winning_team == 1 || winning_team == DRAW_TEAM,
+        WagerError::InvalidWinningTeam
+    );
+    // The payout follows the result recorded at resolution, not the caller
+    game_session.check_recorded_winner(winning_team)?;
+
+    game_session.begin_distribution()?;
+
+    let vault_balance = sol_vault_balance(&ctx.accounts.vault)?;
+    game_session.assert_vault_covers_contributions(vault_balance)?;
+
+    let is_draw = winning_team == DRAW_TEAM;
+    let payouts = if is_draw {
+        game_session.settle_as_draw()?
+    } else {
+        let unclaimed_winners = game_session.get_unclaimed_winners(winning_team)?;
+        game_session
+            .winner_shares(winning_team)?
+            .into_iter()
+            .filter(|(winner, _)| unclaimed_winners.contains(winner))
+            .collect()
+    };
+
+    require!(
+        ctx.remaining_accounts.len() == payouts.len(),
+        WagerError::InvalidRemainingAccounts
+    );
+
+    let total_payout = payouts
+        .iter()
+        .try_fold(0u64, |total, (_, amount)| total.checked_add(*amount))
+        .ok_or(WagerError::ArithmeticError)?;
+    require!(
+        vault_balance >= total_payout,
+        WagerError::InsufficientVaultBalance
+    );
+
+    for ((player, amount), recipient) in payouts.iter().zip(ctx.remaining_accounts.iter()) {
+        require!(recipient.key() == *player, WagerError::InvalidPlayer);
+
+        pay_lamports(&ctx.accounts.vault, recipient, *amount)?;
+
+        emit!(TokenMoved {
+            session_id: session_id.clone(),
+            from: ctx.accounts.vault.key(),
+            to: *player,
+            amount: *amount,
+            reason: if is_draw {
+                TokenMoveReason::Refund as u8
+            } else {
+                TokenMoveReason::Distribution as u8
+            },
+        });
+    }
+
+    require!(
+        sol_vault_balance(&ctx.accounts.vault)? == vault_balance - total_payout,
+        WagerError::TransferVerificationFailed
+    );
+
+    let game_session = &mut ctx.accounts.game_session;
+    for (player, amount) in &payouts {
+        game_session.record_payout(*player, *amount)?;
+        if is_draw {
+            game_session.mark_refunded(*player)?;
+        } else {
+            game_session.mark_claimed(*player)?;
+        }
+    }
+    game_session.end_distribution();
+    game_session.status = GameStatus::Distributed;
+
+    msg!("SOL session {} distributed {} lamports to {} players",
+         session_id, total_payout, payouts.len());
+    Ok(())
+}
+
+/// Leaves a SOL lobby before it starts, refunding the stake in lamports less
+/// any leave penalty, which stays in the vault as for token sessions
+pub fn leave_sol_handler(ctx: Context<LeaveSol>, session_id: String, team: u8) -> Result<()> {
+    let clock = Clock::get()?;
+    let player_key = ctx.accounts.user.key();
+    let game_session = &mut ctx.accounts.game_session;
+
+    require!(game_session.is_sol, WagerError::CurrencyMismatch);
+    require!(
+        game_session.status == GameStatus::WaitingForPlayers,
+        WagerError::GameAlreadyInProgress
+    );
+    require!(
+        !game_session.is_expired(clock.unix_timestamp),
+        WagerError::GameSessionExpired
+    );
+    require!(team == 0 || team == 1, WagerError::InvalidTeamSelection);
+    game_session.check_lobby_unlocked(clock.unix_timestamp)?;
+
+    let player_index = game_session.get_player_index(team, player_key)?;
+    let (refund_amount, penalty) = game_session.leave_refund_split(team, player_index)?;
+
+    let vault_balance_before = sol_vault_balance(&ctx.accounts.vault)?;
+    require!(
+        vault_balance_before >= refund_amount,
+        WagerError::InsufficientVaultBalance
+    );
+
+    pay_lamports(
+        &ctx.accounts.vault,
+        &ctx.accounts.user.to_account_info(),
+        refund_amount,
+    )?;
+
+    require!(
+        sol_vault_balance(&ctx.accounts.vault)? == vault_balance_before - refund_amount,
+        WagerError::TransferVerificationFailed
+    );
+
+    emit!(TokenMoved {
+        session_id: session_id.clone(),
+        from: ctx.accounts.vault.key(),
+        to: player_key,
+        amount: refund_amount,
+        reason: TokenMoveReason::Refund as u8,
+    });
+
+    game_session.unseat_player(team, player_index)?;
+    game_session.leave_penalties = game_session.leave_penalties
+        .checked_add(penalty)
+        .ok_or(WagerError::ArithmeticError)?;
+    game_session.total_collected = game_session.total_collected
+        .checked_add(penalty)
+        .ok_or(WagerError::ArithmeticError)?;
+
+    msg!("Player {} left SOL session {} with a refund of {} lamports ({} penalty)",
+         player_key, session_id, refund_amount, penalty);
+    Ok(())
+}
+
+/// Cancels a SOL session before it starts, refunding every seated player
+/// (authority only). remaining_accounts holds one writable account per
+/// player in get_all_players order.
+pub fn cancel_sol_session_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RefundSol<'info>>,
+    session_id: String,
+) -> Result<()> {
+    require!(
+        ctx.accounts.game_session.authority == ctx.accounts.caller.key(),
+        WagerError::UnauthorizedAction
+    );
+
+    let refunds = ctx.accounts.game_session.get_cancellation_refunds()?;
+    let total_refund =
+        refund_sol_players(ctx.accounts, ctx.remaining_accounts, &session_id, &refunds)?;
+    ctx.accounts.game_session.status = GameStatus::Cancelled;
+
+    msg!("SOL session {} cancelled, refunded {} lamports to {} players",
+         session_id, total_refund, refunds.len());
+    Ok(())
+}
+
+/// Permissionless refund of a SOL session that expired before it filled,
+/// the lamport counterpart of settle_unfilled. remaining_accounts holds one
+/// writable account per player in get_all_players order.
+pub fn settle_unfilled_sol_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RefundSol<'info>>,
+    session_id: String,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let refunds = ctx.accounts.game_session.get_unfilled_refunds(clock.unix_timestamp)?;
+    let total_refund =
+        refund_sol_players(ctx.accounts, ctx.remaining_accounts, &session_id, &refunds)?;
+    ctx.accounts.game_session.status = GameStatus::Cancelled;
+
+    msg!("Unfilled SOL session {} settled: {} lamports refunded to {} players",
+         session_id, total_refund, refunds.len());
+    Ok(())
+}
+
+/// Permissionless refund of every outstanding SOL contribution once the
+/// authority has been silent past its liveness window, the lamport
+/// counterpart of emergency_refund_all. remaining_accounts holds one
+/// writable account per player in get_all_players order.
+pub fn emergency_refund_sol_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RefundSol<'info>>,
+    session_id: String,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let game_session = &ctx.accounts.game_session;
+
+    require!(
+        game_session.status != GameStatus::Distributed,
+        WagerError::SessionAlreadyFinalized
+    );
+    require!(
+        game_session.is_authority_silent(clock.unix_timestamp),
+        WagerError::AuthorityStillLive
+    );
+
+    let refunds = game_session.get_outstanding_contributions()?;
+    let total_refund =
+        refund_sol_players(ctx.accounts, ctx.remaining_accounts, &session_id, &refunds)?;
+    ctx.accounts.game_session.status = GameStatus::Distributed;
+
+    msg!("Emergency refunded {} lamports to {} players in abandoned SOL session {}",
+         total_refund, refunds.len(), session_id);
+    Ok(())
+}
+
+/// Pays each (player, amount) refund from the SOL vault to the matching
+/// remaining account and records the payouts. Returns the total refunded.
+fn refund_sol_players<'info>(
+    accounts: &mut RefundSol<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    session_id: &str,
+    refunds: &[(Pubkey, u64)],
+) -> Result<u64> {
+    require!(accounts.game_session.is_sol, WagerError::CurrencyMismatch);
+    require!(
+        remaining_accounts.len() == refunds.len(),
+        WagerError::InvalidRemainingAccounts
+    );
+
+    let vault_balance = sol_vault_balance(&accounts.vault)?;
+    accounts.game_session.assert_vault_covers_contributions(vault_balance)?;
+
+    let total_refund = refunds
+        .iter()
+        .try_fold(0u64, |total, (_, amount)| total.checked_add(*amount))
+        .ok_or(WagerError::ArithmeticError)?;
+    require!(
+        vault_balance >= total_refund,
+        WagerError::InsufficientVaultBalance
+    );
+
+    for ((player, amount), recipient) in refunds.iter().zip(remaining_accounts.iter()) {
+        require!(recipient.key() == *player, WagerError::InvalidPlayer);
+
+        if *amount == 0 {
+            continue;
+        }
+
+        pay_lamports(&accounts.vault, recipient, *amount)?;
+
+        emit!(TokenMoved {
+            session_id: session_id.to_string(),
+            from: accounts.vault.key(),
+            to: *player,
+            amount: *amount,
+            reason: TokenMoveReason::Refund as u8,
+        });
+    }
+
+    require!(
+        sol_vault_balance(&accounts.vault)? == vault_balance - total_refund,
+        WagerError::TransferVerificationFailed
+    );
+
+    for (player, amount) in refunds {
+        accounts.game_session.record_payout(*player, *amount)?;
+    }
+    Ok(total_refund)
+}
+
+/// Moves lamports out of the SOL vault. The vault is owned by this program,
+/// so its lamports are debited directly rather than through a transfer CPI.
+fn pay_lamports(vault: &AccountInfo, recipient: &AccountInfo, amount: u64) -> Result<()> {
+    let vault_lamports = vault
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(WagerError::InsufficientVaultBalance)?;
+    let recipient_lamports = recipient
+        .lamports()
+        .checked_add(amount)
+        .ok_or(WagerError::ArithmeticError)?;
+
+    **vault.try_borrow_mut_lamports()? = vault_lamports;
+    **recipient.try_borrow_mut_lamports()? = recipient_lamports;
+    Ok(())
+}
+
+/// Lamports a SOL vault holds above its rent-exempt reserve
+pub fn sol_vault_balance(vault: &AccountInfo) -> Result<u64> {
+    let reserve = Rent::get()?.minimum_balance(vault.data_len());
+    Ok(escrowed_lamports(vault.lamports(), reserve))
+}
+
+fn escrowed_lamports(vault_lamports: u64, rent_reserve: u64) -> u64 {
+    vault_lamports.saturating_sub(rent_reserve)
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct CreateSolSession<'info> {
+    #[account(mut)]
+    pub game_server: Signer<'info>,
+
+    #[account(
+        init,
+        payer = game_server,
+        space = GAME_SESSION_SPACE,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: Vault PDA escrowing the lamports, created rent-exempt and owned by this program
+    #[account(
+        init,
+        payer = game_server,
+        space = 0,
+        seeds = [b"vault", session_id.as_bytes()],
+        bump
+    )]
+    pub vault: AccountInfo<'info>,
+
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = !global_config.creation_paused @ WagerError::EmergencyPauseActive,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct JoinSol<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: Vault PDA escrowing the lamports
+    #[account(
+        mut,
+        seeds = [b"vault", session_id.as_bytes()],
+        bump = game_session.vault_bump,
+    )]
+    pub vault: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct DistributeSol<'info> {
+    pub game_server: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+        constraint = game_session.authority == game_server.key() @ WagerError::UnauthorizedDistribution,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: Vault PDA escrowing the lamports
+    #[account(
+        mut,
+        seeds = [b"vault", session_id.as_bytes()],
+        bump = game_session.vault_bump,
+    )]
+    pub vault: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct LeaveSol<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: Vault PDA escrowing the lamports
+    #[account(
+        mut,
+        seeds = [b"vault", session_id.as_bytes()],
+        bump = game_session.vault_bump,
+    )]
+    pub vault: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct RefundSol<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: Vault PDA escrowing the lamports
+    #[account(
+        mut,
+        seeds = [b"vault", session_id.as_bytes()],
+        bump = game_session.vault_bump,
+    )]
+    pub vault: AccountInfo<'info>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sol_one_vs_one_lamport_accounting() {
+        let bet = 50_000_000;
+        let reserve = 890_880;
+        let mut session = GameSession::new(
+            "sol_test".to_string(),
+            Pubkey::new_unique(),
+            bet,
+            GameMode::WinnerTakesAllOneVsOne,
+            1000,
+            1,
+            2,
+        ).unwrap();
+        session.is_sol = true;
+
+        // The rent reserve is never part of the escrow
+        let mut vault_lamports = reserve;
+        assert_eq!(escrowed_lamports(vault_lamports, reserve), 0);
+
+        let (winner, loser) = (Pubkey::new_unique(), Pubkey::new_unique());
+        for (team, player) in [(0, winner), (1, loser)] {
+            vault_lamports += bet;
+            session.seat_player(team, 0, player, 1000).unwrap();
+        }
+        assert_eq!(escrowed_lamports(vault_lamports, reserve), 2 * bet);
+        assert_eq!(
+            session.expected_vault_balance().unwrap(),
+            escrowed_lamports(vault_lamports, reserve)
+        );
+
+        session.start(1000).unwrap();
+        session.mark_resolved(Some(0)).unwrap();
+        let shares = session.winner_shares(0).unwrap();
+        assert_eq!(shares, vec![(winner, 2 * bet)]);
+
+        vault_lamports -= shares[0].1;
+        session.record_payout(winner, shares[0].1).unwrap();
+        assert_eq!(vault_lamports, reserve);
+        assert_eq!(session.required_vault_balance().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_sol_cancellation_refunds_lamports() {
+        let bet = 50_000_000;
+        let reserve = 890_880;
+        let mut session = GameSession::new(
+            "sol_cancel".to_string(),
+            Pubkey::new_unique(),
+            bet,
+            GameMode::WinnerTakesAllOneVsOne,
+            1000,
+            1,
+            2,
+        ).unwrap();
+        session.is_sol = true;
+
+        let player = Pubkey::new_unique();
+        session.seat_player(0, 0, player, 1000).unwrap();
+        let refunds = session.get_cancellation_refunds().unwrap();
+        assert_eq!(refunds, vec![(player, bet)]);
+
+        let program_id = Pubkey::new_unique();
+        let (vault_key, player_key) = (Pubkey::new_unique(), player);
+        let (mut vault_lamports, mut player_lamports) = (reserve + bet, 0u64);
+        let (mut vault_data, mut player_data) = (vec![], vec![]);
+        let vault = AccountInfo::new(
+            &vault_key, false, true, &mut vault_lamports, &mut vault_data,
+            &program_id, false, 0,
+        );
+        let recipient = AccountInfo::new(
+            &player_key, false, true, &mut player_lamports, &mut player_data,
+            &program_id, false, 0,
+        );
+
+        // Overdrawing the vault fails without moving anything
+        assert_eq!(
+            pay_lamports(&vault, &recipient, reserve + bet + 1).unwrap_err(),
+            error!(WagerError::InsufficientVaultBalance)
+        );
+        assert_eq!(vault.lamports(), reserve + bet);
+
+        pay_lamports(&vault, &recipient, refunds[0].1).unwrap();
+        session.record_payout(player, refunds[0].1).unwrap();
+        assert_eq!(vault.lamports(), reserve);
+        assert_eq!(recipient.lamports(), bet);
+        assert_eq!(session.required_vault_balance().unwrap(), 0);
+    }
+}
@@ -0,0 +1,267 @@
+use crate::{errors::WagerError, events::*, state::*};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::spl_token::state::AccountState;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+/// Space required for a SpectatorBet account
+pub const SPECTATOR_BET_SPACE: usize = 8 + // discriminator
+    32 +                   // bettor (Pubkey)
+    32 +                   // game_session (Pubkey)
+    1 +                    // team (u8)
+    8 +                    // amount (u64)
+    1 +                    // claimed (bool)
+    1;                     // bump (u8)
+
+/// Opens the spectator vault that escrows spectator stakes, kept apart from
+/// the players' vault (authority only)
+pub fn open_spectator_pool_handler(ctx: Context<OpenSpectatorPool>, session_id: String) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    game_session.spectator_vault_bump = ctx.bumps.spectator_vault;
+
+    msg!("Spectator pool opened for session {}", session_id);
+    Ok(())
+}
+
+/// Escrows a spectator's stake on `team` winning the session
+pub fn place_spectator_bet_handler(
+    ctx: Context<PlaceSpectatorBet>,
+    session_id: String,
+    team: u8,
+    amount: u64,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    let bettor = ctx.accounts.bettor.key();
+
+    game_session.record_spectator_bet(team, amount)?;
+
+    let spectator_vault_balance_before = ctx.accounts.spectator_vault_token_account.amount;
+
+    anchor_spl::token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: ctx.accounts.bettor_token_account.to_account_info(),
+                to: ctx.accounts.spectator_vault_token_account.to_account_info(),
+                authority: ctx.accounts.bettor.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    // SECURITY FIX: Verify the stake actually arrived
+    ctx.accounts.spectator_vault_token_account.reload()?;
+    require!(
+        ctx.accounts.spectator_vault_token_account.amount
+            == spectator_vault_balance_before
+                .checked_add(amount)
+                .ok_or(WagerError::ArithmeticError)?,
+        WagerError::TransferVerificationFailed
+    );
+
+    let spectator_bet = &mut ctx.accounts.spectator_bet;
+    spectator_bet.bettor = bettor;
+    spectator_bet.game_session = game_session.key();
+    spectator_bet.team = team;
+    spectator_bet.amount = amount;
+    spectator_bet.claimed = false;
+    spectator_bet.bump = ctx.bumps.spectator_bet;
+
+    emit!(TokenMoved {
+        session_id: session_id.clone(),
+        from: ctx.accounts.bettor_token_account.key(),
+        to: ctx.accounts.spectator_vault_token_account.key(),
+        amount,
+        reason: TokenMoveReason::SpectatorBet as u8,
+    });
+
+    msg!("Spectator {} bet {} tokens on team {} in session {}", bettor, amount, team, session_id);
+    Ok(())
+}
+
+/// Pays a spectator their stake plus their share of the losing pool once the
+/// session is settled, or refunds the stake on a draw, cancellation or expiry
+pub fn claim_spectator_winnings_handler(
+    ctx: Context<ClaimSpectatorWinnings>,
+    session_id: String,
+) -> Result<()> {
+    let game_session = &ctx.accounts.game_session;
+    let spectator_bet = &ctx.accounts.spectator_bet;
+
+    require!(!spectator_bet.claimed, WagerError::SpectatorBetAlreadyClaimed);
+
+    let payout = game_session.spectator_payout(spectator_bet.team, spectator_bet.amount)?;
+    require!(payout > 0, WagerError::NothingToClaim);
+
+    require!(
+        ctx.accounts.spectator_vault_token_account.amount >= payout,
+        WagerError::InsufficientVaultBalance
+    );
+
+    anchor_spl::token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: ctx.accounts.spectator_vault_token_account.to_account_info(),
+                to: ctx.accounts.bettor_token_account.to_account_info(),
+                authority: ctx.accounts.spectator_vault.to_account_info(),
+            },
+            &[&[
+                b"spectator_vault",
+                session_id.as_bytes(),
+                &[game_session.spectator_vault_bump],
+            ]],
+        ),
+        payout,
+    )?;
+
+    emit!(TokenMoved {
+        session_id: session_id.clone(),
+        from: ctx.accounts.spectator_vault_token_account.key(),
+        to: ctx.accounts.bettor_token_account.key(),
+        amount: payout,
+        reason: TokenMoveReason::SpectatorPayout as u8,
+    });
+
+    ctx.accounts.spectator_bet.claimed = true;
+
+    msg!("Spectator {} claimed {} tokens from session {}",
+         ctx.accounts.bettor.key(), payout, session_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct OpenSpectatorPool<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == game_session.authority @ WagerError::UnauthorizedAction,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: Spectator vault PDA that holds spectator stakes
+    #[account(
+        init,
+        payer = authority,
+        space = 0,
+        seeds = [b"spectator_vault", session_id.as_bytes()],
+        bump
+    )]
+    pub spectator_vault: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = spectator_vault,
+    )]
+    pub spectator_vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(address = game_session.token_mint @ WagerError::InvalidMint)]
+    pub mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct PlaceSpectatorBet<'info> {
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        init,
+        payer = bettor,
+        space = SPECTATOR_BET_SPACE,
+        seeds = [b"spec", session_id.as_bytes(), bettor.key().as_ref()],
+        bump
+    )]
+    pub spectator_bet: Account<'info, SpectatorBet>,
+
+    #[account(
+        mut,
+        constraint = bettor_token_account.owner == bettor.key() @ WagerError::InvalidTokenAccountOwner,
+        constraint = bettor_token_account.mint == game_session.token_mint @ WagerError::InvalidTokenMint,
+        constraint = bettor_token_account.state == AccountState::Initialized @ WagerError::FrozenTokenAccount,
+    )]
+    pub bettor_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Spectator vault PDA that holds spectator stakes
+    #[account(
+        seeds = [b"spectator_vault", session_id.as_bytes()],
+        bump = game_session.spectator_vault_bump,
+    )]
+    pub spectator_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = game_session.token_mint,
+        associated_token::authority = spectator_vault,
+    )]
+    pub spectator_vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct ClaimSpectatorWinnings<'info> {
+    pub bettor: Signer<'info>,
+
+    #[account(
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        mut,
+        seeds = [b"spec", session_id.as_bytes(), bettor.key().as_ref()],
+        bump = spectator_bet.bump,
+        has_one = bettor,
+    )]
+    pub spectator_bet: Account<'info, SpectatorBet>,
+
+    #[account(
+        mut,
+        constraint = bettor_token_account.owner == bettor.key() @ WagerError::InvalidTokenAccountOwner,
+        constraint = bettor_token_account.mint == game_session.token_mint @ WagerError::InvalidTokenMint,
+    )]
+    pub bettor_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Spectator vault PDA that holds spectator stakes
+    #[account(
+        seeds = [b"spectator_vault", session_id.as_bytes()],
+        bump = game_session.spectator_vault_bump,
+    )]
+    pub spectator_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = game_session.token_mint,
+        associated_token::authority = spectator_vault,
+    )]
+    pub spectator_vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
@@ -1,12 +1,37 @@
 //! State accounts for the betting program
 use crate::errors::WagerError;
 use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
 
 // Constants to replace magic numbers
 pub const DEFAULT_SPAWN_COUNT: u16 = 10;
+pub const MAX_SPAWNS_PER_PLAYER: u16 = 100; // Prevent excessive spawn purchases
 pub const MAX_PLAYERS_PER_TEAM: usize = 5;
 pub const MAX_SESSION_ID_LENGTH: usize = 32;
 pub const SESSION_TIMEOUT_SECONDS: i64 = 7200; // 2 hours
+pub const MAX_SESSION_EXTENSION_SECONDS: i64 = 86_400; // 24 hours per extend_session call
+pub const MAX_SESSION_LIFETIME_SECONDS: i64 = 604_800; // 7 days; no extension reaches past this
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const MAX_SPAWN_COST_SCALE_BPS: u16 = 10_000; // At most +100% per prior purchase
+pub const MAX_LEAVE_PENALTY_BPS: u16 = 5_000; // At most half the bet is kept on leave
+pub const MAX_ALLOWED_MINTS: usize = 16; // Capacity of the program-wide mint whitelist
+pub const DEFAULT_LIVENESS_WINDOW_SECONDS: i64 = 86_400; // 24 hours
+pub const MAX_LIVENESS_WINDOW_SECONDS: i64 = 604_800; // 7 days
+pub const FREE_FOR_ALL_MIN_PLAYERS: usize = 2;
+pub const MAX_REFUNDS_PER_BATCH: usize = 5; // Draw refunds processed per call
+pub const DEFAULT_MAX_FAILURE_BPS: u16 = 5_000; // Failed transfer share that fails a distribution run
+pub const DRAW_TEAM: u8 = 2; // winning_team passed to distribute_all_winnings to settle a draw
+pub const MAX_SERIES_ROUNDS_NEEDED: u8 = 4; // Longest series is best-of-7
+pub const DEFAULT_KILL_WEIGHT: u16 = 1; // Score for a kill reported without a weapon weight
+pub const MAX_KILL_WEIGHT: u16 = 10; // Highest score a single kill can award
+pub const MAX_KILLS_PER_BATCH: usize = 16; // Kills one record_kills_batch call may carry
+pub const MAX_SESSIONS_PER_AUTHORITY: usize = 100; // Prevent spam; capacity of an authority's session index
+pub const DEFAULT_EARNINGS_DIVISOR: u16 = 10; // (score + spawns) * bet / divisor is a player's earnings
+pub const MAX_EARNINGS_DIVISOR: u16 = 100;
+pub const MAX_PENDING_KILLS: usize = 8; // Kills awaiting a victim ack at once
+pub const KILL_ACK_TIMEOUT_SECONDS: i64 = 300; // Unacknowledged kills confirm after 5 minutes
+pub const DEFAULT_LOBBY_LOCK_SECONDS: i64 = 30; // A full lobby cannot be left for this long
+pub const MAX_LOBBY_LOCK_SECONDS: i64 = 600;
 
 /// Game mode defining the team sizes
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
@@ -17,6 +42,7 @@ pub enum GameMode {
     PayToSpawnOneVsOne,         // 1v1 game mode
     PayToSpawnThreeVsThree,     // 3v3 game mode
     PayToSpawnFiveVsFive,       // 5v5 game mode
+    FreeForAll,                 // Up to 10 individuals, top player takes the pot
 }
 
 impl GameMode {
@@ -29,6 +55,7 @@ impl GameMode {
             Self::PayToSpawnOneVsOne => 1,
             Self::PayToSpawnThreeVsThree => 3,
             Self::PayToSpawnFiveVsFive => 5,
+            Self::FreeForAll => MAX_PLAYERS_PER_TEAM, // Both teams are slot banks
         }
     }
 
@@ -42,12 +69,18 @@ impl GameMode {
         )
     }
 
+    /// Returns whether players compete as individuals rather than teams
+    pub fn is_free_for_all(&self) -> bool {
+        matches!(self, Self::FreeForAll)
+    }
+
     /// Returns the default spawn count for this game mode
     pub fn default_spawn_count(&self) -> u16 {
         match self {
             Self::WinnerTakesAllOneVsOne
             | Self::WinnerTakesAllThreeVsThree
-            | Self::WinnerTakesAllFiveVsFive => 1, // Winner takes all: single life
+            | Self::WinnerTakesAllFiveVsFive
+            | Self::FreeForAll => 1, // Winner takes all: single life
             Self::PayToSpawnOneVsOne
             | Self::PayToSpawnThreeVsThree
             | Self::PayToSpawnFiveVsFive => DEFAULT_SPAWN_COUNT, // Pay-to-spawn: multiple lives
@@ -60,10 +93,11 @@ impl GameMode {
 pub enum GameStatus {
     WaitingForPlayers, // Waiting for players to join
     InProgress,        // Game is active with all players joined
-    Completed,         // Game has finished
+    Completed,         // Game has finished and its stakes were refunded
     Distributed,       // Rewards have been distributed
     Expired,           // Game session has expired
     Cancelled,         // Game was cancelled
+    AwaitingDistribution, // Game has finished, payouts not yet made
 }
 
 impl Default for GameStatus {
@@ -72,6 +106,63 @@ impl Default for GameStatus {
     }
 }
 
+/// Policy applied when the declared winning team has no active players left
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Default)]
+pub enum EmptyWinnerPolicy {
+    #[default]
+    RefundContributors, // Refund every remaining player what they paid in
+    SendToAuthority,    // Route the whole pot to the session authority
+}
+
+/// Single high-level phase of a session for client routing, collapsing the
+/// status with expiry and start readiness
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Debug)]
+pub enum GamePhase {
+    Lobby,              // Waiting for players to join
+    ReadyToStart,       // Enough players seated and the start gap has elapsed
+    Live,               // Game in progress
+    AwaitingSettlement, // Finished or run past expiry, payouts not yet made
+    Settled,            // Payouts or refunds made
+    Expired,            // Lobby expired without starting
+    Cancelled,          // Cancelled before completion
+}
+
+/// Final outcome of a finished game, always decided
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Debug)]
+pub enum WinnerOutcome {
+    TeamA,
+    TeamB,
+    Draw,
+}
+
+impl WinnerOutcome {
+    /// The winning team index, None on a draw
+    pub fn winning_team(&self) -> Option<u8> {
+        match self {
+            WinnerOutcome::TeamA => Some(0),
+            WinnerOutcome::TeamB => Some(1),
+            WinnerOutcome::Draw => None,
+        }
+    }
+}
+
+/// How a program admin settles a hung session
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub enum ForceSettleResolution {
+    RefundAll,  // Return every player's outstanding contributions
+    AwardTeamA, // Split the outstanding pot across team A
+    AwardTeamB, // Split the outstanding pot across team B
+}
+
+/// How an exact kill tie is broken when resolving a game by kills
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Default)]
+pub enum KillTieBreaker {
+    #[default]
+    Draw,                  // Leave the tie unresolved
+    EarliestJoin,          // Team whose earliest player joined first wins
+    HigherSpawnsRemaining, // Team with more spawns left wins
+}
+
 /// Represents a team in the game
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
 pub struct Team {
@@ -79,6 +170,14 @@ pub struct Team {
     pub total_bet: u64,                          // Total amount bet by team (in lamports)
     pub player_spawns: [u16; MAX_PLAYERS_PER_TEAM], // Number of spawns remaining for each player
     pub player_kills: [u16; MAX_PLAYERS_PER_TEAM], // Number of kills for each player
+    pub player_score: [u32; MAX_PLAYERS_PER_TEAM], // Weapon-weighted kill score for each player
+    pub player_contributions: [u64; MAX_PLAYERS_PER_TEAM], // Tokens paid in by each player
+    pub player_payouts: [u64; MAX_PLAYERS_PER_TEAM], // Tokens paid out to each player
+    pub player_joined_at: [i64; MAX_PLAYERS_PER_TEAM], // Join timestamp for each player
+    pub player_spawn_purchases: [u16; MAX_PLAYERS_PER_TEAM], // Spawn purchases made by each player
+    pub player_spawns_bought: [u16; MAX_PLAYERS_PER_TEAM], // Spawns bought by each player over the session
+    pub claimed: [bool; MAX_PLAYERS_PER_TEAM], // Whether each winner has been paid
+    pub paid: [bool; MAX_PLAYERS_PER_TEAM], // Whether each player's pay-to-spawn earnings were paid
 }
 
 impl Team {
@@ -147,6 +246,442 @@ impl Team {
             .map(|&kills| kills as u32)
             .sum()
     }
+
+    /// Gets total spawns remaining for the team
+    pub fn get_total_spawns(&self, max_players: usize) -> u32 {
+        self.player_spawns
+            .iter()
+            .take(max_players)
+            .map(|&spawns| spawns as u32)
+            .sum()
+    }
+
+    /// Gets the join timestamp of the team's earliest active player
+    pub fn get_earliest_join(&self, max_players: usize) -> Option<i64> {
+        self.players
+            .iter()
+            .take(max_players)
+            .enumerate()
+            .filter(|(_, player)| **player != Pubkey::default())
+            .map(|(i, _)| self.player_joined_at[i])
+            .min()
+    }
+}
+
+/// One player's post-match ledger entry
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+pub struct PlayerSettlement {
+    pub player: Pubkey,
+    pub contributed: u64,
+    pub paid_out: u64,
+    pub net: i64,
+}
+
+/// Which instruction a player's wallet should call to collect what it is owed
+#[derive(Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum ClaimHint {
+    Nothing = 0,  // Nothing owed
+    Refund = 1,   // refund_wager or emergency_refund_all
+    Winnings = 2, // The session's distribute instruction
+}
+
+/// Everything a player is currently owed in a session
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+pub struct Claimable {
+    pub refund: u64,
+    pub winnings: u64,
+    pub total: u64,
+    pub claim_instruction_hint: u8,
+}
+
+/// One-call session health summary polled by monitoring
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct SessionHealth {
+    pub solvent: bool,       // Vault covers every contribution not yet paid out
+    pub vault_drift: i128,   // Vault balance minus what the ledger says it should hold
+    pub is_expired: bool,
+    pub status: u8,
+    pub fully_settled: bool, // Distributed with nothing left owed
+    pub headroom: i128,      // Vault balance minus the solvency requirement
+}
+
+/// Vault balance next to what the ledger expects, returned by get_vault_balance
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct VaultBalance {
+    pub balance: u64,  // Tokens actually in the vault token account
+    pub expected: u64, // Tokens the session's ledger says it should hold
+    pub status: u8,
+}
+
+/// Whether a player can buy spawns right now, returned by can_purchase_spawns
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct SpawnEligibility {
+    pub eligible: bool,
+    pub current_spawns: u16,
+    pub max_spawns: u16,
+    pub cost: u64, // Price of the player's next single purchase
+}
+
+/// One player's scoreboard line
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct PlayerStats {
+    pub team: u8,
+    pub index: u8,
+    pub spawns: u16,
+    pub kills: u16,
+    pub kd_ratio_bps: u64, // Kills per spawn lost, in basis points
+}
+
+/// Per-player pay-to-spawn earnings preview returned before distribution
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+pub struct EarningsPreview {
+    pub earnings: Vec<(Pubkey, u64)>,
+    pub total_needed: u64,
+    pub vault_balance: u64,
+}
+
+/// Tokens players paid in against tokens paid out, returned by get_audit_totals
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct AuditTotals {
+    pub total_collected: u64,
+    pub total_distributed: u64,
+    pub vault_balance: u64,
+}
+
+/// Payout table a distribution would make right now, returned before any transfer
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct DistributionSimulation {
+    pub payouts: Vec<(Pubkey, u64)>,
+    pub total: u64,
+    pub vault_balance: u64,
+}
+
+/// Computed outcome of a team game with each side's totals, returned by get_winner
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct WinnerView {
+    pub outcome: WinnerOutcome,
+    pub team_a_kills: u32,
+    pub team_b_kills: u32,
+    pub team_a_spawns: u32,
+    pub team_b_spawns: u32,
+}
+
+/// Operations folded into GameSession::state_hash, tagging each hashed record
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[repr(u8)]
+pub enum ReplayEvent {
+    Join = 0,          // team, slot, player
+    Kill = 1,          // killer_team, killer, victim_team, victim, weight
+    SpawnPurchase = 2, // team, slot, spawns added
+}
+
+/// A kill reported under require_victim_ack, applied once the victim co-signs
+/// or KILL_ACK_TIMEOUT_SECONDS pass without a response
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct PendingKill {
+    pub killer_team: u8,
+    pub killer: Pubkey,
+    pub victim_team: u8,
+    pub victim: Pubkey,
+    pub kill_weight: u16,
+    pub reported_at: i64,
+}
+
+/// One kill in a record_kills_batch reconciliation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct KillRecord {
+    pub killer_team: u8,
+    pub killer: Pubkey,
+    pub victim_team: u8,
+    pub victim: Pubkey,
+    pub weight: u16,
+}
+
+/// A team's seats, lives, kills and stake as exposed by read_session
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+pub struct TeamSnapshot {
+    pub players: [Pubkey; MAX_PLAYERS_PER_TEAM],
+    pub player_spawns: [u16; MAX_PLAYERS_PER_TEAM],
+    pub player_kills: [u16; MAX_PLAYERS_PER_TEAM],
+    pub total_bet: u64,
+}
+
+/// Stable client-facing view of a GameSession, independent of the account
+/// layout and without PDA bumps. Sized to fit in 1024 bytes of return data.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+pub struct SessionSnapshot {
+    pub session_id: String,
+    pub authority: Pubkey,
+    pub token_mint: Pubkey,
+    pub game_mode: GameMode,
+    pub status: GameStatus,
+    pub session_bet: u64,
+    pub team_a_bet: u64,
+    pub team_b_bet: u64,
+    pub team_a: TeamSnapshot,
+    pub team_b: TeamSnapshot,
+    pub created_at: i64,
+    pub expires_at: i64,
+    pub winning_team: Option<u8>,
+    pub is_draw: bool,
+    pub total_distributed: u64,
+}
+
+/// Running aggregates over every session created by one authority
+#[account]
+pub struct AuthorityStats {
+    pub authority: Pubkey,      // Game server these stats belong to
+    pub total_bet_volume: u128, // Sum of session bets across all created sessions
+    pub sessions_created: u64,  // Number of sessions created
+    pub bump: u8,               // PDA bump
+}
+
+impl AuthorityStats {
+    /// Folds a newly created session's bet into the running aggregates
+    pub fn record_session(&mut self, session_bet: u64) -> Result<()> {
+        self.total_bet_volume = self
+            .total_bet_volume
+            .checked_add(session_bet as u128)
+            .ok_or(WagerError::ArithmeticError)?;
+        self.sessions_created = self
+            .sessions_created
+            .checked_add(1)
+            .ok_or(WagerError::ArithmeticError)?;
+        Ok(())
+    }
+
+    /// Average bet per created session, zero when none have been created
+    pub fn average_bet(&self) -> u64 {
+        if self.sessions_created == 0 {
+            return 0;
+        }
+        (self.total_bet_volume / self.sessions_created as u128) as u64
+    }
+}
+
+/// A spectator's escrowed stake on one team's victory
+#[account]
+pub struct SpectatorBet {
+    pub bettor: Pubkey,       // Spectator who placed the bet
+    pub game_session: Pubkey, // Session the bet is on
+    pub team: u8,             // Team backed to win
+    pub amount: u64,          // Tokens escrowed in the spectator vault
+    pub claimed: bool,        // Whether the payout has been collected
+    pub bump: u8,             // PDA bump
+}
+
+/// Program-wide settings managed by the deployer
+#[account]
+pub struct GlobalConfig {
+    pub admin: Pubkey,               // Key allowed to manage this config
+    pub allowed_mints: Vec<Pubkey>,  // Mints sessions may wager in; empty allows all
+    pub creation_paused: bool,       // Blocks new sessions; existing ones continue
+    pub bump: u8,                    // PDA bump
+}
+
+impl GlobalConfig {
+    /// Whether sessions may be created in `mint`
+    pub fn is_mint_allowed(&self, mint: &Pubkey) -> bool {
+        self.allowed_mints.is_empty() || self.allowed_mints.contains(mint)
+    }
+
+    /// Adds a mint to the whitelist
+    pub fn add_allowed_mint(&mut self, mint: Pubkey) -> Result<()> {
+        require!(
+            !self.allowed_mints.contains(&mint),
+            WagerError::InvalidMint
+        );
+        require!(
+            self.allowed_mints.len() < MAX_ALLOWED_MINTS,
+            WagerError::TooManyAllowedMints
+        );
+
+        self.allowed_mints.push(mint);
+        Ok(())
+    }
+
+    /// Removes a mint from the whitelist
+    pub fn remove_allowed_mint(&mut self, mint: Pubkey) -> Result<()> {
+        let index = self
+            .allowed_mints
+            .iter()
+            .position(|allowed| *allowed == mint)
+            .ok_or(WagerError::InvalidMint)?;
+
+        self.allowed_mints.remove(index);
+        Ok(())
+    }
+}
+
+/// The active sessions of one authority, so a dashboard can list them from a
+/// single account instead of scanning every program account
+#[account]
+pub struct AuthoritySessionIndex {
+    pub authority: Pubkey,     // Game server whose sessions are listed
+    pub sessions: Vec<Pubkey>, // Active GameSession PDAs, oldest first
+    pub bump: u8,              // PDA bump
+}
+
+impl AuthoritySessionIndex {
+    /// Adds a newly created session to the index
+    pub fn add_session(&mut self, session: Pubkey) -> Result<()> {
+        require!(
+            !self.sessions.contains(&session),
+            WagerError::SessionConflict
+        );
+        require!(
+            self.sessions.len() < MAX_SESSIONS_PER_AUTHORITY,
+            WagerError::SessionIndexFull
+        );
+
+        self.sessions.push(session);
+        Ok(())
+    }
+
+    /// Drops a session from the index, doing nothing if it is not listed
+    pub fn remove_session(&mut self, session: Pubkey) {
+        self.sessions.retain(|listed| *listed != session);
+    }
+
+    /// Active sessions alongside the per-authority limit
+    pub fn quota(&self) -> SessionQuota {
+        SessionQuota {
+            active_sessions: self.sessions.len() as u32,
+            max_sessions: MAX_SESSIONS_PER_AUTHORITY as u32,
+        }
+    }
+}
+
+/// A best-of-N series between the same teams, one GameSession per round.
+/// Each finished round's pot is swept into the series vault and paid out
+/// to the side that first wins rounds_needed rounds.
+#[account]
+pub struct GameSeries {
+    pub series_id: String,       // Unique identifier for the series
+    pub authority: Pubkey,       // Game server running every round
+    pub rounds_needed: u8,       // Round wins that take the series, 2 for best-of-3
+    pub team_a_wins: u8,         // Rounds won by team A
+    pub team_b_wins: u8,         // Rounds won by team B
+    pub rounds_played: u8,       // Rounds recorded so far, draws included
+    pub current_session: Pubkey, // GameSession of the round being played
+    pub winner: Option<u8>,      // Team that took the series, once decided
+    pub finalized: bool,         // Series pot has been paid out
+    pub bump: u8,                // PDA bump
+    pub vault_bump: u8,          // Series vault PDA bump
+    pub team_a_players: [Pubkey; MAX_PLAYERS_PER_TEAM], // Round one's team A roster, sorted
+    pub team_b_players: [Pubkey; MAX_PLAYERS_PER_TEAM], // Round one's team B roster, sorted
+}
+
+impl GameSeries {
+    /// Records a finished round; a draw counts as played but wins nothing
+    pub fn record_round(&mut self, winning_team: Option<u8>) -> Result<()> {
+        require!(self.winner.is_none(), WagerError::SeriesAlreadyDecided);
+
+        let wins = match winning_team {
+            Some(0) => Some(&mut self.team_a_wins),
+            Some(1) => Some(&mut self.team_b_wins),
+            Some(_) => return Err(error!(WagerError::InvalidTeam)),
+            None => None,
+        };
+        if let Some(wins) = wins {
+            *wins = wins.checked_add(1).ok_or(WagerError::ArithmeticError)?;
+            if *wins >= self.rounds_needed {
+                self.winner = winning_team;
+            }
+        }
+
+        self.rounds_played = self
+            .rounds_played
+            .checked_add(1)
+            .ok_or(WagerError::ArithmeticError)?;
+        Ok(())
+    }
+
+    /// Pins the series to round one's rosters and requires every later round
+    /// to be played by the same players on the same sides
+    pub fn check_round_roster(&mut self, round: &GameSession) -> Result<()> {
+        let [team_a, team_b] = round.sorted_rosters();
+        if self.rounds_played == 0 {
+            self.team_a_players = team_a;
+            self.team_b_players = team_b;
+            return Ok(());
+        }
+
+        require!(
+            team_a == self.team_a_players && team_b == self.team_b_players,
+            WagerError::SeriesRoundMismatch
+        );
+        Ok(())
+    }
+
+    /// Splits the pot of a series that was never decided back across round
+    /// one's players in proportion to their team's per-round bet, the
+    /// indivisible remainder going to the first so nothing is stranded
+    pub fn refund_shares(&self, team_a_bet: u64, team_b_bet: u64, pot: u64) -> Result<Vec<(Pubkey, u64)>> {
+        let weighted: Vec<(Pubkey, u64)> = self
+            .team_a_players
+            .iter()
+            .map(|player| (*player, team_a_bet))
+            .chain(self.team_b_players.iter().map(|player| (*player, team_b_bet)))
+            .filter(|(player, _)| *player != Pubkey::default())
+            .collect();
+        if pot == 0 {
+            return Ok(vec![]);
+        }
+
+        let total_weight = weighted
+            .iter()
+            .try_fold(0u128, |total, (_, weight)| total.checked_add(*weight as u128))
+            .ok_or(WagerError::ArithmeticError)?;
+        require!(total_weight > 0, WagerError::ArithmeticError);
+
+        let mut shares = weighted
+            .iter()
+            .map(|(player, weight)| {
+                let share = (pot as u128)
+                    .checked_mul(*weight as u128)
+                    .and_then(|x| x.checked_div(total_weight))
+                    .ok_or(WagerError::ArithmeticError)?;
+                Ok((*player, share as u64))
+            })
+            .collect::<Result<Vec<(Pubkey, u64)>>>()?;
+
+        let paid = shares.iter().map(|(_, share)| share).sum::<u64>();
+        shares[0].1 = shares[0]
+            .1
+            .checked_add(pot - paid)
+            .ok_or(WagerError::ArithmeticError)?;
+        Ok(shares)
+    }
+
+    /// Splits the series pot equally among the winners, the indivisible
+    /// remainder going to the first so nothing is stranded in the vault
+    pub fn split_pot(pot: u64, winner_count: usize) -> Result<Vec<u64>> {
+        let share = pot
+            .checked_div(winner_count as u64)
+            .ok_or(WagerError::ArithmeticError)?;
+        let mut shares = vec![share; winner_count];
+        shares[0] = share
+            .checked_add(pot % winner_count as u64)
+            .ok_or(WagerError::ArithmeticError)?;
+        Ok(shares)
+    }
+}
+
+/// Authority lobby economics returned by the analytics view
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+pub struct AuthorityAnalytics {
+    pub average_bet: u64,
+    pub total_bet_volume: u128,
+    pub sessions_created: u64,
+}
+
+/// An authority's active session count against its creation limit
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct SessionQuota {
+    pub active_sessions: u32,
+    pub max_sessions: u32,
 }
 
 /// Represents a game session between teams with its own pool
@@ -156,19 +691,66 @@ pub struct GameSession {
     pub authority: Pubkey,       // Creator of the game session
     pub session_bet: u64,        // Required bet amount per player
     pub game_mode: GameMode,     // Game configuration (1v1, 3v3, 5v5)
+    pub token_mint: Pubkey,      // SPL token the session is wagered in
+    pub token_decimals: u8,      // Decimals of token_mint
     pub team_a: Team,            // First team
     pub team_b: Team,            // Second team
     pub status: GameStatus,      // Current game state
     pub created_at: i64,         // Creation timestamp
     pub expires_at: i64,         // SECURITY FIX: Added expiration timestamp
     pub spawns_per_purchase: u16, // SECURITY FIX: Configurable spawn increment
+    pub empty_winner_policy: EmptyWinnerPolicy, // Settlement when the winning team is empty
+    pub strict_account_order: bool, // Require remaining_accounts in get_all_players order
+    pub min_time_before_start_seconds: i64, // Minimum gap between creation and game start
+    pub total_distributed: u64,  // Tokens paid out of the vault so far
+    pub kill_tie_breaker: KillTieBreaker, // Tie breaker when resolving by kills
+    pub spawn_cost_scale_bps: u16, // Extra cost per prior spawn purchase, in basis points
+    pub last_heartbeat: i64,     // Last time the authority proved it is still active
+    pub liveness_window_seconds: i64, // Silence after which anyone may trigger refunds
+    pub is_draw: bool,           // Resolved without a winning team
+    pub winning_team: Option<u8>, // Team declared the winner at resolution
+    pub distribution_in_progress: bool, // Set while a distribute handler is running
+    pub round_to_whole_tokens: bool, // Floor winner payouts to whole-token multiples
+    pub rounding_remainder: u64, // Sub-token remainder left in the vault for sweeping
+    pub leave_penalty_bps: u16,  // Share of the bet kept in the vault when a player leaves
+    pub leave_penalties: u64,    // Leave penalties held in the vault
+    pub match_seed: [u8; 32],    // Seed both teams' clients derive the map from
+    pub refunded_bitmap: u16,    // Draw refunds paid, one bit per team slot
+    pub metadata: [u8; 32],      // Off-chain match reference, e.g. a bracket ID
+    pub spectator_pools: [u64; 2], // Spectator stakes backing team A and team B
+    pub spectator_vault_bump: u8, // Spectator vault PDA bump, 0 until the pool is opened
+    pub max_vault_balance: u64,  // Most the vault may ever hold, u64::MAX for no cap
+    pub bonus_pool: u64,         // House bonus added to the pot by the authority
+    pub series: Pubkey,          // GameSeries this session is a round of, default when standalone
+    pub team_a_bet: u64,         // Per-player bet charged to team A, session_bet unless handicapped
+    pub team_b_bet: u64,         // Per-player bet charged to team B, session_bet unless handicapped
+    pub earnings_divisor: u16,   // Pay-to-spawn earnings divisor, lower pays more
+    pub frozen: bool,            // Authority froze play and payouts while investigating
+    pub allow_friendly_fire: bool, // Count kills on teammates, off by default
+    pub auto_start_at: Option<i64>, // When a casual queue may start without full teams
+    pub hard_deadline: i64,      // Latest expires_at any extension may reach
+    pub designated_opponent: Option<Pubkey>, // Only player who may take team B in a 1v1 challenge
+    pub require_victim_ack: bool, // Kills wait for the victim's co-signature or a timeout
+    pub pending_kills: Vec<PendingKill>, // Reported kills not yet applied, at most MAX_PENDING_KILLS
+    pub min_kills_to_earn: u16,  // Pay-to-spawn players with fewer kills earn nothing, 0 disables
+    pub lobby_lock_seconds: i64, // How long after filling up a lobby rejects leaves
+    pub state_hash: [u8; 32],    // Running hash over every join, kill and spawn purchase
+    pub max_failure_bps: u16,    // Failed transfers above this share of a run abort it
+    pub enforce_balance: bool,   // Joins may not put a team more than one player ahead
+    pub initial_spawns: u16,     // Lives each player is seated with
+    pub is_sol: bool,            // Wagered in native lamports held by the vault PDA
+    pub require_ata: bool,       // Player token accounts must be their canonical ATA
+    pub total_collected: u64,    // Player stakes and spawn purchases kept in the vault, net of leave refunds
+    pub max_concurrent_spawns: u16, // Most lives a player may hold at once
+    pub max_lifetime_spawns: u16, // Most spawns a player may buy over the session
     pub bump: u8,                // PDA bump
     pub vault_bump: u8,          // Vault PDA bump
     pub vault_token_bump: u8,    // Vault token account PDA bump
 }
 
 impl GameSession {
-    /// Creates a new game session with proper validation
+    /// Creates a new game session with proper validation. SPL sessions set
+    /// `token_mint` and, for a PDA vault token account, `vault_token_bump` after.
     pub fn new(
         session_id: String,
         authority: Pubkey,
@@ -177,7 +759,6 @@ impl GameSession {
         current_time: i64,
         bump: u8,
         vault_bump: u8,
-        vault_token_bump: u8,
     ) -> Result<Self> {
         // SECURITY FIX: Validate session ID length
         require!(
@@ -191,401 +772,4912 @@ impl GameSession {
         let expires_at = current_time
             .checked_add(SESSION_TIMEOUT_SECONDS)
             .ok_or(WagerError::ArithmeticError)?;
+        let hard_deadline = current_time
+            .checked_add(MAX_SESSION_LIFETIME_SECONDS)
+            .ok_or(WagerError::ArithmeticError)?;
 
         Ok(Self {
             session_id,
             authority,
             session_bet,
             game_mode,
+            token_mint: Pubkey::default(),
+            token_decimals: 0,
             team_a: Team::default(),
             team_b: Team::default(),
             status: GameStatus::WaitingForPlayers,
             created_at: current_time,
             expires_at,
             spawns_per_purchase: DEFAULT_SPAWN_COUNT,
+            empty_winner_policy: EmptyWinnerPolicy::default(),
+            strict_account_order: false,
+            min_time_before_start_seconds: 0,
+            total_distributed: 0,
+            kill_tie_breaker: KillTieBreaker::default(),
+            spawn_cost_scale_bps: 0,
+            last_heartbeat: current_time,
+            liveness_window_seconds: DEFAULT_LIVENESS_WINDOW_SECONDS,
+            is_draw: false,
+            winning_team: None,
+            distribution_in_progress: false,
+            round_to_whole_tokens: false,
+            rounding_remainder: 0,
+            leave_penalty_bps: 0,
+            leave_penalties: 0,
+            match_seed: Self::derive_match_seed(&authority, current_time),
+            refunded_bitmap: 0,
+            metadata: [0; 32],
+            spectator_pools: [0; 2],
+            spectator_vault_bump: 0,
+            max_vault_balance: u64::MAX,
+            bonus_pool: 0,
+            series: Pubkey::default(),
+            team_a_bet: session_bet,
+            team_b_bet: session_bet,
+            earnings_divisor: DEFAULT_EARNINGS_DIVISOR,
+            frozen: false,
+            allow_friendly_fire: false,
+            auto_start_at: None,
+            hard_deadline,
+            designated_opponent: None,
+            require_victim_ack: false,
+            pending_kills: Vec::new(),
+            min_kills_to_earn: 0,
+            lobby_lock_seconds: DEFAULT_LOBBY_LOCK_SECONDS,
+            state_hash: [0; 32],
+            max_failure_bps: DEFAULT_MAX_FAILURE_BPS,
+            enforce_balance: false,
+            initial_spawns: game_mode.default_spawn_count(),
+            is_sol: false,
+            require_ata: false,
+            total_collected: 0,
+            max_concurrent_spawns: MAX_SPAWNS_PER_PLAYER,
+            max_lifetime_spawns: u16::MAX,
             bump,
             vault_bump,
-            vault_token_bump,
+            vault_token_bump: 0,
         })
     }
 
-    /// SECURITY FIX: Check if session has expired
-    pub fn is_expired(&self, current_time: i64) -> bool {
-        current_time >= self.expires_at
-    }
-
-    /// Gets an empty slot for a player in the specified team
-    /// SECURITY FIX: Added expiration check and duplicate player validation
-    pub fn get_player_empty_slot(&self, team: u8, current_time: i64) -> Result<usize> {
-        // Check if session has expired
-        require!(!self.is_expired(current_time), WagerError::GameSessionExpired);
-
-        // Validate game is in correct state
+    /// Recycles a settled session's account for a new game: both teams, the
+    /// ledger and every setting go back to a fresh session with the new bet
+    /// and mode, keeping only its identity (id, authority, mint, PDA bumps).
+    /// The vault must be empty and no spectator stakes may be riding on the
+    /// old game, since their claims would otherwise read the new outcome.
+    pub fn reset(
+        &mut self,
+        new_bet: u64,
+        new_mode: GameMode,
+        current_time: i64,
+        vault_balance: u64,
+    ) -> Result<()> {
         require!(
-            self.status == GameStatus::WaitingForPlayers,
+            matches!(self.status, GameStatus::Distributed | GameStatus::Cancelled),
+            WagerError::InvalidGameState
+        );
+        require!(vault_balance == 0, WagerError::VaultNotEmpty);
+        require!(
+            !self.is_series_round() && self.spectator_pools == [0; 2],
             WagerError::InvalidGameState
         );
 
-        let player_count = self.game_mode.players_per_team();
-        match team {
-            0 => self.team_a.get_empty_slot(player_count),
-            1 => self.team_b.get_empty_slot(player_count),
-            _ => Err(error!(WagerError::InvalidTeam)),
-        }
+        let mut fresh = GameSession::new(
+            self.session_id.clone(),
+            self.authority,
+            new_bet,
+            new_mode,
+            current_time,
+            self.bump,
+            self.vault_bump,
+        )?;
+        fresh.token_mint = self.token_mint;
+        fresh.vault_token_bump = self.vault_token_bump;
+        fresh.token_decimals = self.token_decimals;
+        fresh.is_sol = self.is_sol;
+        fresh.spectator_vault_bump = self.spectator_vault_bump;
+
+        *self = fresh;
+        Ok(())
     }
 
-    /// SECURITY FIX: Improved team validation logic
-    pub fn check_all_filled(&self) -> Result<bool> {
-        let player_count = self.game_mode.players_per_team();
-        
-        // Direct boolean check instead of error string matching
-        let team_a_full = self.team_a.is_full(player_count);
-        let team_b_full = self.team_b.is_full(player_count);
-        
-        Ok(team_a_full && team_b_full)
+    /// Total tokens wagered into the session. Fixed-stake modes pay each
+    /// team's per-player bet for every slot; pay-to-spawn and free-for-all
+    /// pots are whatever the teams have actually paid in.
+    pub fn total_pot(&self) -> Result<u64> {
+        let stakes = if self.is_pay_to_spawn() || self.game_mode.is_free_for_all() {
+            self.team_a.total_bet.checked_add(self.team_b.total_bet)
+        } else {
+            self.team_a_bet
+                .checked_add(self.team_b_bet)
+                .and_then(|x| x.checked_mul(self.game_mode.players_per_team() as u64))
+        };
+
+        stakes
+            .and_then(|x| x.checked_add(self.bonus_pool))
+            .ok_or(error!(WagerError::TotalPotCalculationError))
     }
 
-    /// SECURITY FIX: Added duplicate player validation
-    pub fn validate_player_not_joined(&self, player: &Pubkey) -> Result<()> {
-        let player_count = self.game_mode.players_per_team();
-        
+    /// Adds house bonus tokens to the pot. Pay-to-spawn pays per-player
+    /// earnings rather than splitting a pot, so it cannot take a bonus.
+    pub fn add_bonus(&mut self, amount: u64) -> Result<()> {
         require!(
-            !self.team_a.contains_player(player, player_count) &&
-            !self.team_b.contains_player(player, player_count),
-            WagerError::PlayerAlreadyJoined
+            matches!(self.status, GameStatus::WaitingForPlayers | GameStatus::InProgress),
+            WagerError::InvalidGameState
         );
-        
+        require!(!self.is_pay_to_spawn(), WagerError::InvalidGameMode);
+        require!(amount > 0, WagerError::InvalidBetAmount);
+
+        self.bonus_pool = self
+            .bonus_pool
+            .checked_add(amount)
+            .ok_or(WagerError::ArithmeticError)?;
         Ok(())
     }
 
-    /// Checks if the game mode supports pay-to-spawn
-    pub fn is_pay_to_spawn(&self) -> bool {
-        self.game_mode.is_pay_to_spawn()
+    /// Winner-takes-all payout per winner before rounding: an equal share of
+    /// every bet actually collected plus the bonus pool, so handicapped teams
+    /// with unequal bets split what the vault really holds
+    pub fn winner_payout(&self, winner_count: usize) -> Result<u64> {
+        self.winner_pot()?
+            .checked_div(winner_count as u64)
+            .ok_or(error!(WagerError::ArithmeticError))
     }
 
-    /// Gets all active players from both teams
-    pub fn get_all_players(&self) -> Vec<Pubkey> {
-        let player_count = self.game_mode.players_per_team();
-        let mut players = Vec::new();
-        
-        // Add team A players
-        for i in 0..player_count {
-            if self.team_a.players[i] != Pubkey::default() {
-                players.push(self.team_a.players[i]);
-            }
-        }
-        
-        // Add team B players
-        for i in 0..player_count {
-            if self.team_b.players[i] != Pubkey::default() {
-                players.push(self.team_b.players[i]);
-            }
-        }
-        
-        players
+    /// Every bet actually collected plus the bonus pool, split among the winners
+    pub fn winner_pot(&self) -> Result<u64> {
+        self.team_a
+            .total_bet
+            .checked_add(self.team_b.total_bet)
+            .and_then(|x| x.checked_add(self.bonus_pool))
+            .ok_or(error!(WagerError::ArithmeticError))
     }
 
-    /// Gets the team and index of a player
-    pub fn get_player_team_and_index(&self, player: Pubkey) -> Result<(u8, usize)> {
-        let player_count = self.game_mode.players_per_team();
-        
-        // Check team A first
-        if let Some(index) = self.team_a.get_player_index(&player, player_count) {
-            return Ok((0, index));
-        }
-        
-        // Check team B
-        if let Some(index) = self.team_b.get_player_index(&player, player_count) {
-            return Ok((1, index));
-        }
-        
-        Err(error!(WagerError::PlayerNotFound))
+    /// Each active winner's share of the winner pot, in slot order, before
+    /// rounding. The indivisible remainder is handed out one token at a time
+    /// to the earliest slots, so the shares always add up to the whole pot.
+    pub fn winner_shares(&self, winning_team: u8) -> Result<Vec<(Pubkey, u64)>> {
+        let winners: Vec<Pubkey> = self
+            .get_team_roster(winning_team)?
+            .into_iter()
+            .map(|(player, _, _)| player)
+            .collect();
+        require!(!winners.is_empty(), WagerError::InvalidWinner);
+
+        let pot = self.winner_pot()?;
+        let base = pot / winners.len() as u64;
+        let extra = (pot % winners.len() as u64) as usize;
+
+        let shares: Vec<(Pubkey, u64)> = winners
+            .into_iter()
+            .enumerate()
+            .map(|(i, player)| (player, if i < extra { base + 1 } else { base }))
+            .collect();
+
+        let total = shares
+            .iter()
+            .try_fold(0u64, |total, (_, share)| total.checked_add(*share))
+            .ok_or(WagerError::ArithmeticError)?;
+        require!(total == pot, WagerError::IncompleteDistribution);
+
+        Ok(shares)
     }
 
-    /// Gets the player index in a specific team
-    pub fn get_player_index(&self, team: u8, player: Pubkey) -> Result<usize> {
-        let player_count = self.game_mode.players_per_team();
-        
+    /// Per-player bet charged to `team`
+    pub fn team_bet(&self, team: u8) -> Result<u64> {
         match team {
-            0 => self.team_a
-                .get_player_index(&player, player_count)
-                .ok_or(error!(WagerError::PlayerNotFound)),
-            1 => self.team_b
-                .get_player_index(&player, player_count)
-                .ok_or(error!(WagerError::PlayerNotFound)),
+            0 => Ok(self.team_a_bet),
+            1 => Ok(self.team_b_bet),
             _ => Err(error!(WagerError::InvalidTeam)),
         }
     }
 
-    /// SECURITY FIX: Improved arithmetic with overflow protection
-    pub fn get_kills_and_spawns(&self, player_pubkey: Pubkey) -> Result<u16> {
-        let (team, index) = self.get_player_team_and_index(player_pubkey)?;
-        
-        let (kills, spawns) = match team {
-            0 => (
-                self.team_a.player_kills[index],
-                self.team_a.player_spawns[index],
-            ),
-            1 => (
-                self.team_b.player_kills[index],
-                self.team_b.player_spawns[index],
-            ),
-            _ => return Err(error!(WagerError::InvalidTeam)),
-        };
-        
-        // SECURITY FIX: Use checked arithmetic to prevent overflow
-        kills
-            .checked_add(spawns)
-            .ok_or(error!(WagerError::ArithmeticError))
-    }
-
-    /// SECURITY FIX: Secure kill recording with bounds checking
-    pub fn add_kill(
-        &mut self,
-        killer_team: u8,
-        killer: Pubkey,
-        victim_team: u8,
-        victim: Pubkey,
-    ) -> Result<()> {
-        // Validate game state
+    /// Handicap a ranked match with a different per-player bet for each team,
+    /// only before anyone has joined (only by authority)
+    pub fn set_team_bets(&mut self, team_a_bet: u64, team_b_bet: u64) -> Result<()> {
         require!(
-            self.status == GameStatus::InProgress,
-            WagerError::GameNotInProgress
+            !self.is_pay_to_spawn() && !self.game_mode.is_free_for_all(),
+            WagerError::InvalidGameMode
+        );
+        require!(team_a_bet > 0 && team_b_bet > 0, WagerError::InvalidBetAmount);
+        require!(
+            self.status == GameStatus::WaitingForPlayers,
+            WagerError::InvalidGameState
+        );
+        require!(
+            self.team_a.total_bet == 0 && self.team_b.total_bet == 0,
+            WagerError::PlayerAlreadyJoined
         );
 
-        // Prevent self-kills
-        require!(killer != victim, WagerError::SelfKillNotAllowed);
+        self.team_a_bet = team_a_bet;
+        self.team_b_bet = team_b_bet;
+        Ok(())
+    }
 
-        // Get player indices with validation
-        let killer_index = self.get_player_index(killer_team, killer)?;
-        let victim_index = self.get_player_index(victim_team, victim)?;
+    /// Default match seed for sessions created without one
+    pub fn derive_match_seed(authority: &Pubkey, created_at: i64) -> [u8; 32] {
+        anchor_lang::solana_program::hash::hashv(&[
+            authority.as_ref(),
+            &created_at.to_le_bytes(),
+        ])
+        .to_bytes()
+    }
 
-        // SECURITY FIX: Validate indices are within bounds
+    /// Monotonicity guard for clock reads. Clock::unix_timestamp comes from
+    /// validator votes and can step slightly backward between slots, so a
+    /// reading earlier than the session's own creation is known to be skewed
+    /// and is treated as the creation time instead.
+    pub fn monotonic_time(&self, current_time: i64) -> i64 {
+        current_time.max(self.created_at)
+    }
+
+    /// SECURITY FIX: Check if session has expired. A skewed reading before
+    /// created_at can never count as expired, so a session near the boundary
+    /// cannot flip back and forth on a backward clock step into the past.
+    pub fn is_expired(&self, current_time: i64) -> bool {
+        current_time >= self.expires_at && current_time >= self.created_at
+    }
+
+    /// Whether this session is a round of a GameSeries, settled by the series
+    pub fn is_series_round(&self) -> bool {
+        self.series != Pubkey::default()
+    }
+
+    /// Each team's players in slot-independent (sorted) order, empty slots
+    /// included, so rosters can be compared across series rounds
+    pub fn sorted_rosters(&self) -> [[Pubkey; MAX_PLAYERS_PER_TEAM]; 2] {
+        let mut team_a = self.team_a.players;
+        let mut team_b = self.team_b.players;
+        team_a.sort();
+        team_b.sort();
+        [team_a, team_b]
+    }
+
+    /// Checks `next` can be linked as the following round of a series this
+    /// session belongs to: same authority, mint, mode and bet, not yet started.
+    /// Rounds are linked before players join, so rosters are checked against
+    /// round one when each round is advanced (GameSeries::check_round_roster).
+    pub fn validate_next_series_round(&self, next: &GameSession) -> Result<()> {
+        require!(!next.is_series_round(), WagerError::SeriesRoundMismatch);
         require!(
-            killer_index < MAX_PLAYERS_PER_TEAM,
-            WagerError::InvalidPlayerIndex
+            next.status == GameStatus::WaitingForPlayers,
+            WagerError::SeriesRoundMismatch
         );
         require!(
-            victim_index < MAX_PLAYERS_PER_TEAM,
-            WagerError::InvalidPlayerIndex
+            next.authority == self.authority
+                && next.token_mint == self.token_mint
+                && next.game_mode == self.game_mode
+                && next.session_bet == self.session_bet
+                && next.team_a_bet == self.team_a_bet
+                && next.team_b_bet == self.team_b_bet,
+            WagerError::SeriesRoundMismatch
         );
+        Ok(())
+    }
 
-        // SECURITY FIX: Record kill with overflow protection
-        match killer_team {
-            0 => {
-                let current_kills = self.team_a.player_kills[killer_index];
-                require!(
-                    current_kills < u16::MAX,
-                    WagerError::KillCountOverflow
-                );
-                self.team_a.player_kills[killer_index] = current_kills
-                    .checked_add(1)
-                    .ok_or(WagerError::ArithmeticError)?;
-            }
-            1 => {
-                let current_kills = self.team_b.player_kills[killer_index];
-                require!(
-                    current_kills < u16::MAX,
-                    WagerError::KillCountOverflow
-                );
-                self.team_b.player_kills[killer_index] = current_kills
-                    .checked_add(1)
-                    .ok_or(WagerError::ArithmeticError)?;
-            }
-            _ => return Err(error!(WagerError::InvalidTeam)),
-        }
-
-        // SECURITY FIX: Decrement spawns with underflow protection
-        match victim_team {
-            0 => {
-                require!(
-                    self.team_a.player_spawns[victim_index] > 0,
-                    WagerError::PlayerHasNoSpawns
-                );
-                self.team_a.player_spawns[victim_index] = self.team_a.player_spawns
-                    [victim_index]
-                    .checked_sub(1)
-                    .ok_or(WagerError::ArithmeticError)?;
-            }
-            1 => {
-                require!(
-                    self.team_b.player_spawns[victim_index] > 0,
-                    WagerError::PlayerHasNoSpawns
-                );
-                self.team_b.player_spawns[victim_index] = self.team_b.player_spawns
-                    [victim_index]
-                    .checked_sub(1)
-                    .ok_or(WagerError::ArithmeticError)?;
-            }
-            _ => return Err(error!(WagerError::InvalidTeam)),
-        }
+    /// Lets kills on teammates count, only before the game starts
+    pub fn set_allow_friendly_fire(&mut self, allow: bool) -> Result<()> {
+        require!(
+            self.status == GameStatus::WaitingForPlayers,
+            WagerError::GameAlreadyStarted
+        );
+        self.allow_friendly_fire = allow;
+        Ok(())
+    }
 
-        msg!(
-            "Kill recorded: {} (team {}) killed {} (team {})",
-            killer,
-            killer_team,
-            victim,
-            victim_team
+    /// Moves a waiting session nobody has paid into yet onto `new_mint`
+    pub fn migrate_mint(&mut self, new_mint: Pubkey, new_decimals: u8, vault_balance: u64) -> Result<()> {
+        require!(
+            self.status == GameStatus::WaitingForPlayers,
+            WagerError::GameAlreadyStarted
+        );
+        require!(new_mint != self.token_mint, WagerError::InvalidMint);
+        require!(
+            vault_balance == 0
+                && self.team_a.total_bet == 0
+                && self.team_b.total_bet == 0
+                && self.bonus_pool == 0,
+            WagerError::VaultNotEmpty
         );
 
+        self.token_mint = new_mint;
+        self.token_decimals = new_decimals;
         Ok(())
     }
 
-    /// SECURITY FIX: Configurable spawn addition with overflow protection
-    pub fn add_spawns(&mut self, team: u8, player_index: usize) -> Result<()> {
-        // Validate game state
+    /// Turns victim-acknowledged kill reporting on or off, only before the game starts
+    pub fn set_require_victim_ack(&mut self, require_ack: bool) -> Result<()> {
         require!(
-            self.status == GameStatus::InProgress || self.status == GameStatus::WaitingForPlayers,
-            WagerError::InvalidGameState
+            self.status == GameStatus::WaitingForPlayers,
+            WagerError::GameAlreadyStarted
         );
+        self.require_victim_ack = require_ack;
+        Ok(())
+    }
 
-        // Validate player index
+    /// Sets the kills a pay-to-spawn player needs before earning anything,
+    /// only before the game starts
+    pub fn set_min_kills_to_earn(&mut self, min_kills: u16) -> Result<()> {
         require!(
-            player_index < MAX_PLAYERS_PER_TEAM,
-            WagerError::InvalidPlayerIndex
+            self.status == GameStatus::WaitingForPlayers,
+            WagerError::GameAlreadyStarted
         );
+        self.min_kills_to_earn = min_kills;
+        Ok(())
+    }
 
-        // SECURITY FIX: Use configurable spawn increment with overflow protection
-        let spawn_increment = self.spawns_per_purchase;
+    /// Sets the pay-to-spawn earnings divisor, within 1..=MAX_EARNINGS_DIVISOR
+    pub fn set_earnings_divisor(&mut self, earnings_divisor: u16) -> Result<()> {
+        require!(
+            earnings_divisor > 0 && earnings_divisor <= MAX_EARNINGS_DIVISOR,
+            WagerError::InvalidEarningsDivisor
+        );
+        self.earnings_divisor = earnings_divisor;
+        Ok(())
+    }
 
-        match team {
-            0 => {
-                let current_spawns = self.team_a.player_spawns[player_index];
-                self.team_a.player_spawns[player_index] = current_spawns
-                    .checked_add(spawn_increment)
+    /// Pay-to-spawn earnings owed if every player cashed out their current
+    /// score and spawns now, using the same per-player rounding as distribution
+    pub fn projected_pay_spawn_earnings(&self) -> Result<u64> {
+        self.all_score_and_spawns()?
+            .into_iter()
+            .try_fold(0u64, |total, (_, score_and_spawns, _)| {
+                let earnings = score_and_spawns
+                    .checked_mul(self.session_bet)
+                    .and_then(|x| x.checked_div(self.earnings_divisor as u64))
                     .ok_or(WagerError::ArithmeticError)?;
-            }
-            1 => {
-                let current_spawns = self.team_b.player_spawns[player_index];
-                self.team_b.player_spawns[player_index] = current_spawns
-                    .checked_add(spawn_increment)
-                    .ok_or(WagerError::ArithmeticError)?;
-            }
-            _ => return Err(error!(WagerError::InvalidTeam)),
-        }
+                total
+                    .checked_add(earnings)
+                    .ok_or(error!(WagerError::ArithmeticError))
+            })
+    }
 
-        msg!(
-            "Added {} spawns to player {} in team {}",
-            spawn_increment,
-            player_index,
-            team
+    /// Rejects a state whose projected pay-to-spawn earnings exceed what the
+    /// vault holds, so purchases cannot create obligations it can never pay
+    pub fn check_projected_solvency(&self, vault_balance: u64) -> Result<()> {
+        require!(
+            self.projected_pay_spawn_earnings()? <= vault_balance,
+            WagerError::ProjectedInsolvency
         );
-
         Ok(())
     }
 
-    /// Initialize default spawns for a player when they join
-    pub fn initialize_player_spawns(&mut self, team: u8, player_index: usize) -> Result<()> {
-        // Validate player index
+    /// Tokens the vault is short of the projected pay-to-spawn earnings, 0 while solvent
+    pub fn required_topup(&self, vault_balance: u64) -> Result<u64> {
+        Ok(self.projected_pay_spawn_earnings()?.saturating_sub(vault_balance))
+    }
+
+    /// Records an authority top-up of an in-progress pay-to-spawn vault. Only
+    /// the current shortfall may be covered, so the vault is not overfunded.
+    pub fn add_topup(&mut self, amount: u64, vault_balance: u64) -> Result<()> {
+        require!(self.is_pay_to_spawn(), WagerError::InvalidGameMode);
         require!(
-            player_index < MAX_PLAYERS_PER_TEAM,
-            WagerError::InvalidPlayerIndex
+            self.status == GameStatus::InProgress,
+            WagerError::GameNotInProgress
+        );
+        require!(amount > 0, WagerError::InvalidBetAmount);
+        require!(
+            amount <= self.required_topup(vault_balance)?,
+            WagerError::TopUpExceedsShortfall
         );
 
-        let default_spawns = self.game_mode.default_spawn_count();
-
-        match team {
-            0 => {
-                self.team_a.player_spawns[player_index] = default_spawns;
-            }
-            1 => {
-                self.team_b.player_spawns[player_index] = default_spawns;
-            }
-            _ => return Err(error!(WagerError::InvalidTeam)),
-        }
+        self.bonus_pool = self
+            .bonus_pool
+            .checked_add(amount)
+            .ok_or(WagerError::ArithmeticError)?;
+        Ok(())
+    }
 
+    /// Rejects a deposit that would leave the vault holding more than the cap
+    pub fn check_vault_cap(&self, vault_balance_after: u64) -> Result<()> {
+        require!(
+            vault_balance_after <= self.max_vault_balance,
+            WagerError::VaultCapExceeded
+        );
         Ok(())
     }
 
-    /// Check if a team has won (opponent team eliminated)
-    pub fn check_winner(&self) -> Option<u8> {
-        let player_count = self.game_mode.players_per_team();
-        
-        if self.team_a.is_eliminated(player_count) && !self.team_b.is_eliminated(player_count) {
-            Some(1) // Team B wins
-        } else if self.team_b.is_eliminated(player_count) && !self.team_a.is_eliminated(player_count) {
-            Some(0) // Team A wins
-        } else {
-            None // No winner yet or tie
-        }
+    /// Seconds left before the session expires, 0 once it has
+    pub fn seconds_until_expiry(&self, current_time: i64) -> i64 {
+        self.expires_at
+            .saturating_sub(self.monotonic_time(current_time))
+            .max(0)
     }
 
-    /// Get team statistics
-    pub fn get_team_stats(&self, team: u8) -> Result<(u32, u16)> {
+    /// Gets an empty slot for a player in the specified team
+    /// SECURITY FIX: Added expiration check and duplicate player validation
+    pub fn get_player_empty_slot(&self, team: u8, current_time: i64) -> Result<usize> {
+        require!(self.is_joinable(current_time), WagerError::GameNotJoinable);
+
         let player_count = self.game_mode.players_per_team();
-        
         match team {
-            0 => Ok((
-                self.team_a.get_total_kills(player_count),
-                self.team_a.player_spawns.iter().take(player_count).sum(),
-            )),
-            1 => Ok((
-                self.team_b.get_total_kills(player_count),
-                self.team_b.player_spawns.iter().take(player_count).sum(),
-            )),
+            0 => self.team_a.get_empty_slot(player_count),
+            1 => self.team_b.get_empty_slot(player_count),
             _ => Err(error!(WagerError::InvalidTeam)),
         }
     }
 
-    /// Validate that the game session can transition to in-progress state
-    pub fn can_start(&self) -> Result<bool> {
-        require!(
-            self.status == GameStatus::WaitingForPlayers,
-            WagerError::InvalidGameState
-        );
+    /// Single source of truth for whether a new player can join: waiting for
+    /// players, not expired, and at least one team still has a free slot
+    pub fn is_joinable(&self, current_time: i64) -> bool {
+        let player_count = self.game_mode.players_per_team();
+
+        self.status == GameStatus::WaitingForPlayers
+            && !self.is_expired(current_time)
+            && !(self.team_a.is_full(player_count) && self.team_b.is_full(player_count))
+    }
+
+    /// SECURITY FIX: Improved team validation logic
+    pub fn check_all_filled(&self) -> Result<bool> {
+        let player_count = self.game_mode.players_per_team();
         
-        self.check_all_filled()
+        // Direct boolean check instead of error string matching
+        let team_a_full = self.team_a.is_full(player_count);
+        let team_b_full = self.team_b.is_full(player_count);
+        
+        Ok(team_a_full && team_b_full)
     }
 
-    /// Update spawn purchase configuration (only by authority)
-    pub fn update_spawns_per_purchase(&mut self, new_spawns_per_purchase: u16) -> Result<()> {
+    /// SECURITY FIX: Added duplicate player validation
+    pub fn validate_player_not_joined(&self, player: &Pubkey) -> Result<()> {
         require!(
-            new_spawns_per_purchase > 0 && new_spawns_per_purchase <= 50,
-            WagerError::InvalidSpawnCount
+            self.find_player(player).is_none(),
+            WagerError::PlayerAlreadyJoined
         );
         
-        self.spawns_per_purchase = new_spawns_per_purchase;
         Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Finds the (team, index) seating a player. The single lookup every
+    /// membership check goes through, so they can never disagree.
+    pub fn find_player(&self, player: &Pubkey) -> Option<(u8, usize)> {
+        let player_count = self.game_mode.players_per_team();
 
-    #[test]
-    fn test_game_mode_players_per_team() {
-        assert_eq!(GameMode::WinnerTakesAllOneVsOne.players_per_team(), 1);
-        assert_eq!(GameMode::WinnerTakesAllThreeVsThree.players_per_team(), 3);
-        assert_eq!(GameMode::WinnerTakesAllFiveVsFive.players_per_team(), 5);
+        self.team_a
+            .get_player_index(player, player_count)
+            .map(|index| (0, index))
+            .or_else(|| {
+                self.team_b
+                    .get_player_index(player, player_count)
+                    .map(|index| (1, index))
+            })
     }
 
-    #[test]
-    fn test_team_validation() {
-        let mut team = Team::default();
-        assert!(team.get_empty_slot(1).is_ok());
-        
-        // Fill first slot
-        team.players[0] = Pubkey::new_unique();
+    /// Seats a validated player in a free slot and credits their stake to the team
+    pub fn seat_player(
+        &mut self,
+        team: u8,
+        slot: usize,
+        player: Pubkey,
+        joined_at: i64,
+    ) -> Result<()> {
+        // SECURITY FIX: Bounds checking before array access
+        require!(slot < MAX_PLAYERS_PER_TEAM, WagerError::InvalidPlayerIndex);
+
+        let session_bet = self.team_bet(team)?;
+        let selected_team = match team {
+            0 => &mut self.team_a,
+            1 => &mut self.team_b,
+            _ => return Err(error!(WagerError::InvalidTeam)),
+        };
+
+        // SECURITY FIX: Double-check that the slot is still empty (race condition protection)
+        require!(
+            selected_team.players[slot] == Pubkey::default(),
+            WagerError::TeamSlotNoLongerAvailable
+        );
+
+        selected_team.players[slot] = player;
+        selected_team.player_kills[slot] = 0;
+        selected_team.player_score[slot] = 0;
+        selected_team.player_contributions[slot] = session_bet;
+        selected_team.player_payouts[slot] = 0;
+        selected_team.player_joined_at[slot] = joined_at;
+        selected_team.player_spawn_purchases[slot] = 0;
+        selected_team.player_spawns_bought[slot] = 0;
+        selected_team.claimed[slot] = false;
+        selected_team.paid[slot] = false;
+
+        // SECURITY FIX: Update total bet for the team with overflow protection
+        selected_team.total_bet = selected_team
+            .total_bet
+            .checked_add(session_bet)
+            .ok_or(WagerError::ArithmeticError)?;
+        self.add_total_collected(session_bet)?;
+
+        self.record_replay_event(
+            ReplayEvent::Join,
+            &[&[team], &[slot as u8], player.as_ref()],
+        );
+
+        // SECURITY FIX: Initialize spawns using configurable value from game mode
+        self.initialize_player_spawns(team, slot)
+    }
+
+    /// Clears a leaving player's slot and takes their stake back off the team.
+    /// Every per-slot field is reset so a later seat_player, including the same
+    /// player re-joining, starts from a clean slot.
+    pub fn unseat_player(&mut self, team: u8, slot: usize) -> Result<()> {
+        require!(slot < MAX_PLAYERS_PER_TEAM, WagerError::InvalidPlayerIndex);
+
+        let selected_team = match team {
+            0 => &mut self.team_a,
+            1 => &mut self.team_b,
+            _ => return Err(error!(WagerError::InvalidTeam)),
+        };
+
+        require!(
+            selected_team.players[slot] != Pubkey::default(),
+            WagerError::PlayerNotFound
+        );
+
+        // Take off everything the player paid in, spawn purchases included
+        let contribution = selected_team.player_contributions[slot];
+
+        selected_team.players[slot] = Pubkey::default();
+        selected_team.player_spawns[slot] = 0;
+        selected_team.player_kills[slot] = 0;
+        selected_team.player_score[slot] = 0;
+        selected_team.player_contributions[slot] = 0;
+        selected_team.player_payouts[slot] = 0;
+        selected_team.player_joined_at[slot] = 0;
+        selected_team.player_spawn_purchases[slot] = 0;
+        selected_team.player_spawns_bought[slot] = 0;
+        selected_team.claimed[slot] = false;
+        selected_team.paid[slot] = false;
+
+        selected_team.total_bet = selected_team
+            .total_bet
+            .checked_sub(contribution)
+            .ok_or(WagerError::ArithmeticError)?;
+
+        // The leaver's stake goes back out, so it no longer counts as collected
+        self.total_collected = self
+            .total_collected
+            .checked_sub(contribution)
+            .ok_or(WagerError::ArithmeticError)?;
+
+        Ok(())
+    }
+
+    /// Tokens the player in `slot` of `team` has paid in: their bet plus any
+    /// spawns bought since
+    pub fn player_contribution(&self, team: u8, slot: usize) -> Result<u64> {
+        require!(slot < MAX_PLAYERS_PER_TEAM, WagerError::InvalidPlayerIndex);
+
+        match team {
+            0 => Ok(self.team_a.player_contributions[slot]),
+            1 => Ok(self.team_b.player_contributions[slot]),
+            _ => Err(error!(WagerError::InvalidTeam)),
+        }
+    }
+
+    /// Records tokens a player paid into the vault after joining
+    pub fn record_contribution(&mut self, team: u8, player_index: usize, amount: u64) -> Result<()> {
+        require!(
+            player_index < MAX_PLAYERS_PER_TEAM,
+            WagerError::InvalidPlayerIndex
+        );
+
+        let selected_team = match team {
+            0 => &mut self.team_a,
+            1 => &mut self.team_b,
+            _ => return Err(error!(WagerError::InvalidTeam)),
+        };
+
+        selected_team.player_contributions[player_index] = selected_team.player_contributions
+            [player_index]
+            .checked_add(amount)
+            .ok_or(WagerError::ArithmeticError)?;
+
+        self.add_total_collected(amount)
+    }
+
+    /// Records player tokens paid into the vault
+    fn add_total_collected(&mut self, amount: u64) -> Result<()> {
+        self.total_collected = self
+            .total_collected
+            .checked_add(amount)
+            .ok_or(WagerError::ArithmeticError)?;
+
+        Ok(())
+    }
+
+    /// Sets the share of failed transfers, in bps, a distribution run tolerates.
+    /// Only before the game starts, so a run can't be made to skip payouts.
+    pub fn set_max_failure_bps(&mut self, max_failure_bps: u16) -> Result<()> {
+        require!(
+            self.status == GameStatus::WaitingForPlayers,
+            WagerError::ConfigLockedError
+        );
+        require!(
+            max_failure_bps as u64 <= BPS_DENOMINATOR,
+            WagerError::InvalidFailureThreshold
+        );
+        self.max_failure_bps = max_failure_bps;
+        Ok(())
+    }
+
+    /// Whether `failures` out of `total` transfers is more than the run tolerates
+    pub fn exceeds_failure_threshold(&self, failures: usize, total: usize) -> bool {
+        if total == 0 {
+            return false;
+        }
+        (failures as u64 * BPS_DENOMINATOR / total as u64) > self.max_failure_bps as u64
+    }
+
+    /// Marks a distribution as running, rejecting re-entry while one is in flight
+    pub fn begin_distribution(&mut self) -> Result<()> {
+        require!(!self.frozen, WagerError::SessionFrozen);
+        require!(
+            !self.distribution_in_progress,
+            WagerError::ConcurrentModificationDetected
+        );
+
+        self.distribution_in_progress = true;
+        Ok(())
+    }
+
+    /// Clears the in-flight distribution flag
+    pub fn end_distribution(&mut self) {
+        self.distribution_in_progress = false;
+    }
+
+    /// Records a vault payout that is not attributed to a player
+    pub fn add_total_distributed(&mut self, amount: u64) -> Result<()> {
+        self.total_distributed = self
+            .total_distributed
+            .checked_add(amount)
+            .ok_or(WagerError::ArithmeticError)?;
+
+        Ok(())
+    }
+
+    /// Everything left in the vault once the session owes nobody anything,
+    /// which the authority may sweep out so the vault can be closed.
+    /// Distributed sessions are settled; cancelled ones once every
+    /// contribution has been refunded.
+    pub fn sweepable_dust(&self, vault_balance: u64) -> Result<u64> {
+        let settled = match self.status {
+            GameStatus::Distributed => true,
+            GameStatus::Cancelled => self
+                .get_outstanding_contributions()?
+                .iter()
+                .all(|(_, outstanding)| *outstanding == 0),
+            _ => false,
+        };
+        require!(settled, WagerError::InvalidGameState);
+        require!(vault_balance > 0, WagerError::NothingToClaim);
+        Ok(vault_balance)
+    }
+
+    /// Tokens the vault must still hold: recorded team bets not yet paid out
+    pub fn required_vault_balance(&self) -> Result<u64> {
+        Ok(self
+            .team_a
+            .total_bet
+            .checked_add(self.team_b.total_bet)
+            .and_then(|x| x.checked_add(self.bonus_pool))
+            .ok_or(WagerError::ArithmeticError)?
+            .saturating_sub(self.total_distributed))
+    }
+
+    /// Solvency invariant: the vault covers every contribution not yet paid out
+    pub fn assert_vault_covers_contributions(&self, vault_balance: u64) -> Result<()> {
+        require!(
+            vault_balance >= self.required_vault_balance()?,
+            WagerError::InsufficientVaultBalance
+        );
+        Ok(())
+    }
+
+    /// Tokens the ledger says the vault holds: outstanding contributions plus
+    /// leave penalties kept back from refunds
+    pub fn expected_vault_balance(&self) -> Result<u64> {
+        self.required_vault_balance()?
+            .checked_add(self.leave_penalties)
+            .ok_or(error!(WagerError::ArithmeticError))
+    }
+
+    /// Pairs the vault's actual balance with the ledger's expectation
+    pub fn vault_balance(&self, balance: u64) -> Result<VaultBalance> {
+        Ok(VaultBalance {
+            balance,
+            expected: self.expected_vault_balance()?,
+            status: self.status.clone() as u8,
+        })
+    }
+
+    /// Composes the solvency, reconciliation and settlement checks for monitoring
+    pub fn health(&self, vault_balance: u64, current_time: i64) -> Result<SessionHealth> {
+        let required = self.required_vault_balance()?;
+
+        Ok(SessionHealth {
+            solvent: vault_balance >= required,
+            vault_drift: vault_balance as i128 - self.expected_vault_balance()? as i128,
+            is_expired: self.is_expired(current_time),
+            status: self.status.clone() as u8,
+            fully_settled: self.status == GameStatus::Distributed && required == 0,
+            headroom: vault_balance as i128 - required as i128,
+        })
+    }
+
+    /// Toggles flooring winner payouts to whole tokens. Only before the game
+    /// starts, so players know the payout terms they joined under.
+    pub fn set_round_to_whole_tokens(&mut self, enabled: bool) -> Result<()> {
+        require!(
+            self.status == GameStatus::WaitingForPlayers,
+            WagerError::ConfigLockedError
+        );
+        self.round_to_whole_tokens = enabled;
+        Ok(())
+    }
+
+    /// Splits a payout into the amount to transfer and the sub-token remainder
+    /// kept in the vault, flooring to whole tokens when rounding is enabled
+    pub fn round_payout(&self, amount: u64) -> Result<(u64, u64)> {
+        if !self.round_to_whole_tokens {
+            return Ok((amount, 0));
+        }
+
+        let whole_token = 10u64
+            .checked_pow(self.token_decimals as u32)
+            .ok_or(WagerError::ArithmeticError)?;
+        let remainder = amount % whole_token;
+
+        Ok((amount - remainder, remainder))
+    }
+
+    /// Records sub-token remainder left behind in the vault by rounded payouts
+    pub fn add_rounding_remainder(&mut self, remainder: u64) -> Result<()> {
+        self.rounding_remainder = self
+            .rounding_remainder
+            .checked_add(remainder)
+            .ok_or(WagerError::ArithmeticError)?;
+
+        Ok(())
+    }
+
+    /// Gets the winning team's active players that have not been paid yet
+    pub fn get_unclaimed_winners(&self, winning_team: u8) -> Result<Vec<Pubkey>> {
+        let player_count = self.game_mode.players_per_team();
+        let team = match winning_team {
+            0 => &self.team_a,
+            1 => &self.team_b,
+            _ => return Err(error!(WagerError::InvalidTeam)),
+        };
+
+        Ok(team
+            .players
+            .iter()
+            .take(player_count)
+            .enumerate()
+            .filter(|(i, player)| **player != Pubkey::default() && !team.claimed[*i])
+            .map(|(_, player)| *player)
+            .collect())
+    }
+
+    /// Marks a winner as paid so re-running distribution skips them
+    pub fn mark_claimed(&mut self, player: Pubkey) -> Result<()> {
+        let (team, index) = self.get_player_team_and_index(player)?;
+        let selected_team = match team {
+            0 => &mut self.team_a,
+            _ => &mut self.team_b,
+        };
+
+        require!(!selected_team.claimed[index], WagerError::AlreadyDistributed);
+        selected_team.claimed[index] = true;
+        Ok(())
+    }
+
+    /// Scoreboard stats for a player. Deaths are the spawns lost from the
+    /// session's starting count plus any bought; with no deaths the ratio is
+    /// kills over one.
+    pub fn player_stats(&self, player: Pubkey) -> Result<PlayerStats> {
+        let (team, index) = self.find_player(&player).ok_or(WagerError::PlayerNotFound)?;
+        let selected_team = match team {
+            0 => &self.team_a,
+            _ => &self.team_b,
+        };
+
+        let spawns = selected_team.player_spawns[index];
+        let kills = selected_team.player_kills[index];
+        let deaths = (self.initial_spawns as u64)
+            .checked_add(selected_team.player_spawns_bought[index] as u64)
+            .and_then(|total| total.checked_sub(spawns as u64))
+            .ok_or(WagerError::ArithmeticError)?;
+        let kd_ratio_bps = (kills as u64)
+            .checked_mul(BPS_DENOMINATOR)
+            .ok_or(WagerError::ArithmeticError)?
+            / deaths.max(1);
+
+        Ok(PlayerStats {
+            team,
+            index: index as u8,
+            spawns,
+            kills,
+            kd_ratio_bps,
+        })
+    }
+
+    /// Whether a player's pay-to-spawn earnings have already been paid
+    pub fn is_earnings_paid(&self, player: Pubkey) -> Result<bool> {
+        let (team, index) = self.get_player_team_and_index(player)?;
+        Ok(match team {
+            0 => self.team_a.paid[index],
+            _ => self.team_b.paid[index],
+        })
+    }
+
+    /// Whether an earlier pay-to-spawn distribution paid at least one player
+    pub fn has_paid_earnings(&self) -> bool {
+        let player_count = self.game_mode.players_per_team();
+        self.team_a.paid.iter().take(player_count).any(|paid| *paid)
+            || self.team_b.paid.iter().take(player_count).any(|paid| *paid)
+    }
+
+    /// Marks a player's pay-to-spawn earnings as paid so a resumed
+    /// distribution skips them
+    pub fn mark_earnings_paid(&mut self, player: Pubkey) -> Result<()> {
+        let (team, index) = self.get_player_team_and_index(player)?;
+        let selected_team = match team {
+            0 => &mut self.team_a,
+            _ => &mut self.team_b,
+        };
+
+        require!(!selected_team.paid[index], WagerError::AlreadyDistributed);
+        selected_team.paid[index] = true;
+        Ok(())
+    }
+
+    /// Records a vault payout to a player
+    pub fn record_payout(&mut self, player: Pubkey, amount: u64) -> Result<()> {
+        let (team, index) = self.get_player_team_and_index(player)?;
+        let selected_team = match team {
+            0 => &mut self.team_a,
+            _ => &mut self.team_b,
+        };
+
+        selected_team.player_payouts[index] = selected_team.player_payouts[index]
+            .checked_add(amount)
+            .ok_or(WagerError::ArithmeticError)?;
+
+        self.add_total_distributed(amount)
+    }
+
+    /// Builds the contributed vs paid-out ledger for every player, capped to `max_entries`
+    pub fn get_settlement_report(&self, max_entries: usize) -> Result<Vec<PlayerSettlement>> {
+        let player_count = self.game_mode.players_per_team();
+        let mut report = Vec::new();
+
+        for team in [&self.team_a, &self.team_b] {
+            for i in 0..player_count {
+                if team.players[i] == Pubkey::default() || report.len() >= max_entries {
+                    continue;
+                }
+
+                let contributed = team.player_contributions[i];
+                let paid_out = team.player_payouts[i];
+                let net = i64::try_from(paid_out as i128 - contributed as i128)
+                    .map_err(|_| error!(WagerError::ArithmeticError))?;
+
+                report.push(PlayerSettlement {
+                    player: team.players[i],
+                    contributed,
+                    paid_out,
+                    net,
+                });
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Checks if the game mode supports pay-to-spawn
+    pub fn is_pay_to_spawn(&self) -> bool {
+        self.game_mode.is_pay_to_spawn()
+    }
+
+    /// Gets all active players from both teams
+    pub fn get_all_players(&self) -> Vec<Pubkey> {
+        let player_count = self.game_mode.players_per_team();
+        let mut players = Vec::new();
+        
+        // Add team A players
+        for i in 0..player_count {
+            if self.team_a.players[i] != Pubkey::default() {
+                players.push(self.team_a.players[i]);
+            }
+        }
+        
+        // Add team B players
+        for i in 0..player_count {
+            if self.team_b.players[i] != Pubkey::default() {
+                players.push(self.team_b.players[i]);
+            }
+        }
+        
+        players
+    }
+
+    /// Gets the active players of a single team
+    pub fn get_active_team_players(&self, team: u8) -> Result<Vec<Pubkey>> {
+        let player_count = self.game_mode.players_per_team();
+        let selected_team = match team {
+            0 => &self.team_a,
+            1 => &self.team_b,
+            _ => return Err(error!(WagerError::InvalidTeam)),
+        };
+
+        Ok(selected_team
+            .players
+            .iter()
+            .take(player_count)
+            .filter(|&player| *player != Pubkey::default())
+            .copied()
+            .collect())
+    }
+
+    /// Gets (player, spawns, kills) for every active slot of a single team
+    pub fn get_team_roster(&self, team: u8) -> Result<Vec<(Pubkey, u16, u16)>> {
+        let player_count = self.game_mode.players_per_team();
+        let selected_team = match team {
+            0 => &self.team_a,
+            1 => &self.team_b,
+            _ => return Err(error!(WagerError::InvalidTeam)),
+        };
+
+        Ok((0..player_count)
+            .filter(|&i| selected_team.players[i] != Pubkey::default())
+            .map(|i| {
+                (
+                    selected_team.players[i],
+                    selected_team.player_spawns[i],
+                    selected_team.player_kills[i],
+                )
+            })
+            .collect())
+    }
+
+    /// Gets the bet owed back to each seated player when a session is
+    /// cancelled before it starts, in `get_all_players` order
+    pub fn get_cancellation_refunds(&self) -> Result<Vec<(Pubkey, u64)>> {
+        require!(
+            self.status == GameStatus::WaitingForPlayers,
+            WagerError::GameAlreadyStarted
+        );
+        self.get_outstanding_contributions()
+    }
+
+    /// Marks a lobby that outlived its expiry as Expired so it can no longer
+    /// be joined and is left only to settle_unfilled or claim_walkover
+    pub fn expire(&mut self, current_time: i64) -> Result<()> {
+        require!(
+            self.status == GameStatus::WaitingForPlayers,
+            WagerError::InvalidGameState
+        );
+        require!(
+            self.is_expired(current_time),
+            WagerError::SessionNotExpired
+        );
+
+        self.status = GameStatus::Expired;
+        Ok(())
+    }
+
+    /// Gets each seated player's contributions owed back when a session
+    /// expired before it filled, in `get_all_players` order
+    pub fn get_unfilled_refunds(&self, current_time: i64) -> Result<Vec<(Pubkey, u64)>> {
+        require!(
+            matches!(self.status, GameStatus::WaitingForPlayers | GameStatus::Expired),
+            WagerError::InvalidGameState
+        );
+        require!(
+            self.is_expired(current_time),
+            WagerError::SessionNotExpired
+        );
+        self.get_outstanding_contributions()
+    }
+
+    /// Gets the full team's contributions owed back when a session expired with
+    /// one team full and the other never showing up, in `get_all_players` order
+    pub fn get_walkover_refunds(&self, current_time: i64) -> Result<Vec<(Pubkey, u64)>> {
+        require!(!self.game_mode.is_free_for_all(), WagerError::InvalidGameMode);
+
+        let player_count = self.game_mode.players_per_team();
+        let team_a_count = self.team_a.get_active_player_count(player_count);
+        let team_b_count = self.team_b.get_active_player_count(player_count);
+        require!(
+            (team_a_count == player_count && team_b_count == 0)
+                || (team_b_count == player_count && team_a_count == 0),
+            WagerError::NotAWalkover
+        );
+
+        self.get_unfilled_refunds(current_time)
+    }
+
+    /// Gets each remaining player's contributions not yet paid back out, in `get_all_players` order
+    pub fn get_outstanding_contributions(&self) -> Result<Vec<(Pubkey, u64)>> {
+        self.get_all_players()
+            .into_iter()
+            .map(|player| {
+                let (team, index) = self.get_player_team_and_index(player)?;
+                let selected_team = match team {
+                    0 => &self.team_a,
+                    _ => &self.team_b,
+                };
+                let outstanding = selected_team.player_contributions[index]
+                    .saturating_sub(selected_team.player_payouts[index]);
+                Ok((player, outstanding))
+            })
+            .collect()
+    }
+
+    /// Gets up to `limit` players still owed a draw refund, with their
+    /// outstanding contributions, in `get_all_players` order
+    pub fn get_pending_draw_refunds(&self, limit: usize) -> Result<Vec<(Pubkey, u64)>> {
+        let mut pending = Vec::new();
+        for (player, outstanding) in self.get_outstanding_contributions()? {
+            if pending.len() == limit {
+                break;
+            }
+            if self.refunded_bitmap & self.refund_bit(player)? == 0 {
+                pending.push((player, outstanding));
+            }
+        }
+        Ok(pending)
+    }
+
+    /// Returns every player still owed their stake back from a team game
+    /// resolved as a draw. A recorded winner is never overturned here.
+    pub fn settle_as_draw(&self) -> Result<Vec<(Pubkey, u64)>> {
+        require!(
+            self.status == GameStatus::AwaitingDistribution,
+            WagerError::InvalidGameState
+        );
+        require!(self.is_draw, WagerError::InvalidWinningTeam);
+
+        self.get_pending_draw_refunds(MAX_PLAYERS_PER_TEAM * 2)
+    }
+
+    /// Checks a payout's `winning_team` argument against the recorded result:
+    /// DRAW_TEAM only for a draw, otherwise the team declared the winner
+    pub fn check_recorded_winner(&self, winning_team: u8) -> Result<()> {
+        if winning_team == DRAW_TEAM {
+            require!(self.is_draw, WagerError::InvalidWinningTeam);
+        } else {
+            require!(
+                self.winning_team == Some(winning_team),
+                WagerError::InvalidWinningTeam
+            );
+        }
+        Ok(())
+    }
+
+    /// Records that a player's draw refund has been paid
+    pub fn mark_refunded(&mut self, player: Pubkey) -> Result<()> {
+        let bit = self.refund_bit(player)?;
+        require!(self.refunded_bitmap & bit == 0, WagerError::AlreadyDistributed);
+
+        self.refunded_bitmap |= bit;
+        Ok(())
+    }
+
+    fn refund_bit(&self, player: Pubkey) -> Result<u16> {
+        let (team, index) = self.get_player_team_and_index(player)?;
+        Ok(1 << (team as usize * MAX_PLAYERS_PER_TEAM + index))
+    }
+
+    /// Works out the payouts for an admin force-settlement, in the order their
+    /// [player, player_token_account] pairs are expected in remaining_accounts
+    pub fn force_settle_payouts(&self, resolution: ForceSettleResolution) -> Result<Vec<(Pubkey, u64)>> {
+        require!(
+            self.status != GameStatus::Distributed,
+            WagerError::SessionAlreadyFinalized
+        );
+
+        let team = match resolution {
+            ForceSettleResolution::RefundAll => return self.get_outstanding_contributions(),
+            ForceSettleResolution::AwardTeamA => 0,
+            ForceSettleResolution::AwardTeamB => 1,
+        };
+
+        let winners = self.get_active_team_players(team)?;
+        require!(!winners.is_empty(), WagerError::InvalidWinningTeam);
+
+        // Split the whole balance owed, remainder tokens going to the earliest
+        // slots as in winner_shares, so nothing is stranded once Distributed
+        let pot = self.required_vault_balance()?;
+        let base = pot / winners.len() as u64;
+        let extra = (pot % winners.len() as u64) as usize;
+
+        Ok(winners
+            .into_iter()
+            .enumerate()
+            .map(|(i, winner)| (winner, if i < extra { base + 1 } else { base }))
+            .collect())
+    }
+
+    /// Records an admin force-settlement and finalizes the session
+    pub fn mark_force_settled(&mut self, resolution: ForceSettleResolution) -> Result<()> {
+        require!(
+            self.status != GameStatus::Distributed,
+            WagerError::SessionAlreadyFinalized
+        );
+
+        self.winning_team = match resolution {
+            ForceSettleResolution::RefundAll => None,
+            ForceSettleResolution::AwardTeamA => Some(0),
+            ForceSettleResolution::AwardTeamB => Some(1),
+        };
+        self.is_draw = self.winning_team.is_none();
+        self.status = GameStatus::Distributed;
+        Ok(())
+    }
+
+    /// Update the policy used when the winning team has no active players.
+    /// Only before anyone has staked, so the authority cannot switch to
+    /// SendToAuthority once a team has emptied and take the pot.
+    pub fn update_empty_winner_policy(&mut self, policy: EmptyWinnerPolicy) -> Result<()> {
+        require!(
+            self.status == GameStatus::WaitingForPlayers,
+            WagerError::ConfigLockedError
+        );
+        require!(self.get_all_players().is_empty(), WagerError::ConfigLockedError);
+
+        self.empty_winner_policy = policy;
+        Ok(())
+    }
+
+    /// Gets the team and index of a player.
+    /// In FreeForAll the "team" is only a slot bank (team A holds seats 0-4,
+    /// team B seats 5-9); it carries no alliance and kills across or within
+    /// banks count the same.
+    pub fn get_player_team_and_index(&self, player: Pubkey) -> Result<(u8, usize)> {
+        self.find_player(&player)
+            .ok_or(error!(WagerError::PlayerNotFound))
+    }
+
+    /// Gets the player index in a specific team
+    pub fn get_player_index(&self, team: u8, player: Pubkey) -> Result<usize> {
+        let player_count = self.game_mode.players_per_team();
+        
+        match team {
+            0 => self.team_a
+                .get_player_index(&player, player_count)
+                .ok_or(error!(WagerError::PlayerNotFound)),
+            1 => self.team_b
+                .get_player_index(&player, player_count)
+                .ok_or(error!(WagerError::PlayerNotFound)),
+            _ => Err(error!(WagerError::InvalidTeam)),
+        }
+    }
+
+    /// SECURITY FIX: Improved arithmetic with overflow protection
+    pub fn get_kills_and_spawns(&self, player_pubkey: Pubkey) -> Result<u16> {
+        let (team, index) = self.get_player_team_and_index(player_pubkey)?;
+        
+        let (kills, spawns) = match team {
+            0 => (
+                self.team_a.player_kills[index],
+                self.team_a.player_spawns[index],
+            ),
+            1 => (
+                self.team_b.player_kills[index],
+                self.team_b.player_spawns[index],
+            ),
+            _ => return Err(error!(WagerError::InvalidTeam)),
+        };
+        
+        // SECURITY FIX: Use checked arithmetic to prevent overflow
+        kills
+            .checked_add(spawns)
+            .ok_or(error!(WagerError::ArithmeticError))
+    }
+
+    /// Gets weighted kill score plus remaining spawns, the basis of
+    /// pay-to-spawn earnings. Players short of min_kills_to_earn count as 0.
+    pub fn get_score_and_spawns(&self, player_pubkey: Pubkey) -> Result<u64> {
+        let (team, index) = self.get_player_team_and_index(player_pubkey)?;
+        let selected_team = match team {
+            0 => &self.team_a,
+            _ => &self.team_b,
+        };
+
+        self.earning_score(selected_team, index)
+    }
+
+    fn earning_score(&self, team: &Team, index: usize) -> Result<u64> {
+        if team.player_kills[index] < self.min_kills_to_earn {
+            return Ok(0);
+        }
+
+        (team.player_score[index] as u64)
+            .checked_add(team.player_spawns[index] as u64)
+            .ok_or(error!(WagerError::ArithmeticError))
+    }
+
+    /// Every active player's team and slot in `get_all_players` order, found
+    /// by walking the teams once instead of searching for each player
+    fn active_slots(&self) -> impl Iterator<Item = (&Team, usize)> + '_ {
+        let player_count = self.game_mode.players_per_team();
+        [&self.team_a, &self.team_b].into_iter().flat_map(move |team| {
+            (0..player_count)
+                .filter(move |&i| team.players[i] != Pubkey::default())
+                .map(move |i| (team, i))
+        })
+    }
+
+    /// Kills plus spawns for every active player in `get_all_players` order,
+    /// the same pairs as calling get_kills_and_spawns per player but in O(n)
+    pub fn all_kills_and_spawns(&self) -> Result<Vec<(Pubkey, u16)>> {
+        self.active_slots()
+            .map(|(team, i)| {
+                let kills_and_spawns = team.player_kills[i]
+                    .checked_add(team.player_spawns[i])
+                    .ok_or(error!(WagerError::ArithmeticError))?;
+                Ok((team.players[i], kills_and_spawns))
+            })
+            .collect()
+    }
+
+    /// (player, score + spawns, earnings already paid) for every active
+    /// player in `get_all_players` order, in O(n)
+    pub fn all_score_and_spawns(&self) -> Result<Vec<(Pubkey, u64, bool)>> {
+        self.active_slots()
+            .map(|(team, i)| Ok((team.players[i], self.earning_score(team, i)?, team.paid[i])))
+            .collect()
+    }
+
+    /// SECURITY FIX: Secure kill recording with bounds checking
+    pub fn add_kill(
+        &mut self,
+        killer_team: u8,
+        killer: Pubkey,
+        victim_team: u8,
+        victim: Pubkey,
+    ) -> Result<()> {
+        self.add_weighted_kill(killer_team, killer, victim_team, victim, DEFAULT_KILL_WEIGHT)
+    }
+
+    /// Records a kill that counts once toward kills but `kill_weight` toward
+    /// the killer's score, so a weapon like a headshot can pay more
+    pub fn add_weighted_kill(
+        &mut self,
+        killer_team: u8,
+        killer: Pubkey,
+        victim_team: u8,
+        victim: Pubkey,
+        kill_weight: u16,
+    ) -> Result<()> {
+        let (killer_index, victim_index) =
+            self.validate_kill(killer_team, killer, victim_team, victim, kill_weight)?;
+
+        // SECURITY FIX: Record kill with overflow protection
+        match killer_team {
+            0 => {
+                let current_kills = self.team_a.player_kills[killer_index];
+                require!(
+                    current_kills < u16::MAX,
+                    WagerError::KillCountOverflow
+                );
+                self.team_a.player_kills[killer_index] = current_kills
+                    .checked_add(1)
+                    .ok_or(WagerError::ArithmeticError)?;
+                self.team_a.player_score[killer_index] = self.team_a.player_score[killer_index]
+                    .checked_add(kill_weight as u32)
+                    .ok_or(WagerError::ArithmeticError)?;
+            }
+            1 => {
+                let current_kills = self.team_b.player_kills[killer_index];
+                require!(
+                    current_kills < u16::MAX,
+                    WagerError::KillCountOverflow
+                );
+                self.team_b.player_kills[killer_index] = current_kills
+                    .checked_add(1)
+                    .ok_or(WagerError::ArithmeticError)?;
+                self.team_b.player_score[killer_index] = self.team_b.player_score[killer_index]
+                    .checked_add(kill_weight as u32)
+                    .ok_or(WagerError::ArithmeticError)?;
+            }
+            _ => return Err(error!(WagerError::InvalidTeam)),
+        }
+
+        // SECURITY FIX: Decrement spawns with underflow protection
+        match victim_team {
+            0 => {
+                require!(
+                    self.team_a.player_spawns[victim_index] > 0,
+                    WagerError::PlayerHasNoSpawns
+                );
+                self.team_a.player_spawns[victim_index] = self.team_a.player_spawns
+                    [victim_index]
+                    .checked_sub(1)
+                    .ok_or(WagerError::ArithmeticError)?;
+            }
+            1 => {
+                require!(
+                    self.team_b.player_spawns[victim_index] > 0,
+                    WagerError::PlayerHasNoSpawns
+                );
+                self.team_b.player_spawns[victim_index] = self.team_b.player_spawns
+                    [victim_index]
+                    .checked_sub(1)
+                    .ok_or(WagerError::ArithmeticError)?;
+            }
+            _ => return Err(error!(WagerError::InvalidTeam)),
+        }
+
+        self.record_replay_event(
+            ReplayEvent::Kill,
+            &[
+                &[killer_team],
+                killer.as_ref(),
+                &[victim_team],
+                victim.as_ref(),
+                &kill_weight.to_le_bytes(),
+            ],
+        );
+
+        msg!(
+            "Kill recorded: {} (team {}) killed {} (team {})",
+            killer,
+            killer_team,
+            victim,
+            victim_team
+        );
+
+        Ok(())
+    }
+
+    /// Reports a kill: applied immediately, or held for the victim's ack when
+    /// the session requires one. Returns whether the kill was applied.
+    pub fn report_kill(
+        &mut self,
+        killer_team: u8,
+        killer: Pubkey,
+        victim_team: u8,
+        victim: Pubkey,
+        kill_weight: u16,
+        current_time: i64,
+    ) -> Result<bool> {
+        if !self.require_victim_ack {
+            self.add_weighted_kill(killer_team, killer, victim_team, victim, kill_weight)?;
+            return Ok(true);
+        }
+
+        let (_, victim_index) =
+            self.validate_kill(killer_team, killer, victim_team, victim, kill_weight)?;
+        require!(
+            !self
+                .pending_kills
+                .iter()
+                .any(|kill| kill.killer == killer && kill.victim == victim),
+            WagerError::PendingKillExists
+        );
+        require!(
+            self.pending_kills.len() < MAX_PENDING_KILLS,
+            WagerError::TooManyPendingKills
+        );
+
+        // Every pending kill on the victim must still find a spawn to take
+        let pending_on_victim = self
+            .pending_kills
+            .iter()
+            .filter(|kill| kill.victim == victim)
+            .count();
+        let victim_spawns = match victim_team {
+            0 => self.team_a.player_spawns[victim_index],
+            _ => self.team_b.player_spawns[victim_index],
+        };
+        require!(
+            victim_spawns as usize > pending_on_victim,
+            WagerError::PlayerHasNoSpawns
+        );
+
+        self.pending_kills.push(PendingKill {
+            killer_team,
+            killer,
+            victim_team,
+            victim,
+            kill_weight,
+            reported_at: current_time,
+        });
+        Ok(false)
+    }
+
+    /// Reports a batch of kills in order, failing the whole batch on the first
+    /// invalid entry. A team game whose batch eliminates one side is resolved
+    /// in its favour; the winning team is returned.
+    pub fn record_kills_batch(
+        &mut self,
+        kills: &[KillRecord],
+        current_time: i64,
+    ) -> Result<Option<u8>> {
+        require!(
+            !kills.is_empty() && kills.len() <= MAX_KILLS_PER_BATCH,
+            WagerError::InvalidKillBatch
+        );
+
+        for kill in kills {
+            self.report_kill(
+                kill.killer_team,
+                kill.killer,
+                kill.victim_team,
+                kill.victim,
+                kill.weight,
+                current_time,
+            )?;
+        }
+
+        if self.game_mode.is_free_for_all() {
+            return Ok(None);
+        }
+        let winning_team = self.check_winner();
+        if winning_team.is_some() {
+            self.mark_resolved(winning_team)?;
+        }
+        Ok(winning_team)
+    }
+
+    /// Applies the pending kill of `victim` by `killer` once the victim acknowledges it
+    pub fn ack_kill(&mut self, killer: Pubkey, victim: Pubkey) -> Result<()> {
+        let index = self
+            .pending_kills
+            .iter()
+            .position(|kill| kill.killer == killer && kill.victim == victim)
+            .ok_or(WagerError::PendingKillNotFound)?;
+
+        let kill = self.pending_kills.remove(index);
+        self.add_weighted_kill(
+            kill.killer_team,
+            kill.killer,
+            kill.victim_team,
+            kill.victim,
+            kill.kill_weight,
+        )
+    }
+
+    /// Applies, in report order, every pending kill whose victim let the ack
+    /// window lapse, dropping any that can no longer apply. Returns how many
+    /// were confirmed.
+    pub fn confirm_timed_out_kills(&mut self, current_time: i64) -> Result<usize> {
+        let (timed_out, pending): (Vec<PendingKill>, Vec<PendingKill>) = self
+            .pending_kills
+            .drain(..)
+            .partition(|kill| {
+                current_time >= kill.reported_at.saturating_add(KILL_ACK_TIMEOUT_SECONDS)
+            });
+        self.pending_kills = pending;
+
+        let mut confirmed = 0;
+        for kill in &timed_out {
+            // A kill that no longer applies (the victim is out of spawns, a
+            // player left, the game ended) is dropped rather than blocking
+            // every instruction that flushes the queue
+            let applicable = self
+                .validate_kill(
+                    kill.killer_team,
+                    kill.killer,
+                    kill.victim_team,
+                    kill.victim,
+                    kill.kill_weight,
+                )
+                .is_ok();
+            if !applicable {
+                continue;
+            }
+
+            self.add_weighted_kill(
+                kill.killer_team,
+                kill.killer,
+                kill.victim_team,
+                kill.victim,
+                kill.kill_weight,
+            )?;
+            confirmed += 1;
+        }
+        Ok(confirmed)
+    }
+
+    /// Checks a kill can be recorded and returns the (killer, victim) slot indices
+    fn validate_kill(
+        &self,
+        killer_team: u8,
+        killer: Pubkey,
+        victim_team: u8,
+        victim: Pubkey,
+        kill_weight: u16,
+    ) -> Result<(usize, usize)> {
+        require!(
+            kill_weight > 0 && kill_weight <= MAX_KILL_WEIGHT,
+            WagerError::InvalidKillWeight
+        );
+
+        // Validate game state
+        require!(
+            self.status == GameStatus::InProgress,
+            WagerError::GameNotInProgress
+        );
+
+        // Prevent self-kills
+        require!(killer != victim, WagerError::SelfKillNotAllowed);
+
+        // SECURITY FIX: Teammates farming kills on each other would inflate
+        // pay-to-spawn earnings. Free-for-all banks are slots, not sides.
+        require!(
+            killer_team != victim_team
+                || self.allow_friendly_fire
+                || self.game_mode.is_free_for_all(),
+            WagerError::FriendlyFireNotAllowed
+        );
+
+        // Get player indices with validation
+        let killer_index = self.get_player_index(killer_team, killer)?;
+        let victim_index = self.get_player_index(victim_team, victim)?;
+
+        // SECURITY FIX: Validate indices are within bounds
+        require!(
+            killer_index < MAX_PLAYERS_PER_TEAM,
+            WagerError::InvalidPlayerIndex
+        );
+        require!(
+            victim_index < MAX_PLAYERS_PER_TEAM,
+            WagerError::InvalidPlayerIndex
+        );
+
+        let victim_spawns = match victim_team {
+            0 => self.team_a.player_spawns[victim_index],
+            _ => self.team_b.player_spawns[victim_index],
+        };
+        require!(victim_spawns > 0, WagerError::PlayerHasNoSpawns);
+
+        Ok((killer_index, victim_index))
+    }
+
+    /// SECURITY FIX: Configurable spawn addition with overflow protection
+    pub fn add_spawns(&mut self, team: u8, player_index: usize) -> Result<()> {
+        // Validate game state
+        require!(
+            self.status == GameStatus::InProgress || self.status == GameStatus::WaitingForPlayers,
+            WagerError::InvalidGameState
+        );
+
+        // Validate player index
+        require!(
+            player_index < MAX_PLAYERS_PER_TEAM,
+            WagerError::InvalidPlayerIndex
+        );
+
+        // SECURITY FIX: Use configurable spawn increment with overflow protection
+        let spawn_increment = self.spawns_per_purchase;
+
+        let team_ref = match team {
+            0 => &mut self.team_a,
+            1 => &mut self.team_b,
+            _ => return Err(error!(WagerError::InvalidTeam)),
+        };
+
+        team_ref.player_spawns[player_index] = team_ref.player_spawns[player_index]
+            .checked_add(spawn_increment)
+            .ok_or(WagerError::ArithmeticError)?;
+        team_ref.player_spawn_purchases[player_index] = team_ref.player_spawn_purchases
+            [player_index]
+            .checked_add(1)
+            .ok_or(WagerError::ArithmeticError)?;
+        team_ref.player_spawns_bought[player_index] = team_ref.player_spawns_bought[player_index]
+            .checked_add(spawn_increment)
+            .ok_or(WagerError::ArithmeticError)?;
+
+        self.record_replay_event(
+            ReplayEvent::SpawnPurchase,
+            &[&[team], &[player_index as u8], &spawn_increment.to_le_bytes()],
+        );
+
+        msg!(
+            "Added {} spawns to player {} in team {}",
+            spawn_increment,
+            player_index,
+            team
+        );
+
+        Ok(())
+    }
+
+    /// Folds an operation into state_hash as hash(prev_hash || event || fields),
+    /// so the final hash commits to every event and the order they happened in
+    pub fn record_replay_event(&mut self, event: ReplayEvent, fields: &[&[u8]]) {
+        let prev_hash = self.state_hash;
+        let event_tag = [event as u8];
+        let mut parts: Vec<&[u8]> = vec![&prev_hash, &event_tag];
+        parts.extend_from_slice(fields);
+
+        self.state_hash = anchor_lang::solana_program::hash::hashv(&parts).to_bytes();
+    }
+
+    /// Cost of a player's next spawn purchase, scaled by their prior purchases
+    pub fn spawn_purchase_cost(&self, team: u8, player_index: usize) -> Result<u64> {
+        self.spawn_bundle_cost(team, player_index, 1)
+    }
+
+    /// Combined cost of a player's next `count` spawn purchases, each scaled by
+    /// the purchases made before it
+    pub fn spawn_bundle_cost(&self, team: u8, player_index: usize, count: u8) -> Result<u64> {
+        require!(
+            player_index < MAX_PLAYERS_PER_TEAM,
+            WagerError::InvalidPlayerIndex
+        );
+
+        let purchases = match team {
+            0 => self.team_a.player_spawn_purchases[player_index],
+            1 => self.team_b.player_spawn_purchases[player_index],
+            _ => return Err(error!(WagerError::InvalidTeam)),
+        };
+
+        let mut total_cost = 0u64;
+        for prior_purchases in (purchases as u64)..(purchases as u64 + count as u64) {
+            let multiplier_bps = prior_purchases
+                .checked_mul(self.spawn_cost_scale_bps as u64)
+                .and_then(|extra| extra.checked_add(BPS_DENOMINATOR))
+                .ok_or(WagerError::ArithmeticError)?;
+
+            let cost = (self.session_bet as u128)
+                .checked_mul(multiplier_bps as u128)
+                .ok_or(WagerError::ArithmeticError)?
+                / BPS_DENOMINATOR as u128;
+
+            total_cost = u64::try_from(cost)
+                .ok()
+                .and_then(|cost| total_cost.checked_add(cost))
+                .ok_or(WagerError::ArithmeticError)?;
+        }
+
+        Ok(total_cost)
+    }
+
+    /// Initialize a joining player's spawns to the session's starting lives
+    pub fn initialize_player_spawns(&mut self, team: u8, player_index: usize) -> Result<()> {
+        // Validate player index
+        require!(
+            player_index < MAX_PLAYERS_PER_TEAM,
+            WagerError::InvalidPlayerIndex
+        );
+
+        let initial_spawns = self.initial_spawns;
+
+        match team {
+            0 => {
+                self.team_a.player_spawns[player_index] = initial_spawns;
+            }
+            1 => {
+                self.team_b.player_spawns[player_index] = initial_spawns;
+            }
+            _ => return Err(error!(WagerError::InvalidTeam)),
+        }
+
+        Ok(())
+    }
+
+    /// Check if a team has won (opponent team eliminated)
+    pub fn check_winner(&self) -> Option<u8> {
+        let player_count = self.game_mode.players_per_team();
+        
+        if self.team_a.is_eliminated(player_count) && !self.team_b.is_eliminated(player_count) {
+            Some(1) // Team B wins
+        } else if self.team_b.is_eliminated(player_count) && !self.team_a.is_eliminated(player_count) {
+            Some(0) // Team A wins
+        } else {
+            None // No winner yet or tie
+        }
+    }
+
+    /// Total decision for a finished game: a sole survivor wins, otherwise
+    /// (mutual elimination or an expired in-progress game) more total kills
+    /// wins, an exact kill tie goes to the session's kill_tie_breaker, and
+    /// anything still level is a Draw. Errors while the game can still be
+    /// played out.
+    pub fn determine_final_winner(&self, current_time: i64) -> Result<WinnerOutcome> {
+        require!(!self.game_mode.is_free_for_all(), WagerError::InvalidGameMode);
+
+        match self.check_winner() {
+            Some(0) => return Ok(WinnerOutcome::TeamA),
+            Some(_) => return Ok(WinnerOutcome::TeamB),
+            None => {}
+        }
+
+        let player_count = self.game_mode.players_per_team();
+        let both_eliminated = self.team_a.is_eliminated(player_count)
+            && self.team_b.is_eliminated(player_count);
+        let expired_in_progress =
+            self.status == GameStatus::InProgress && self.is_expired(current_time);
+        require!(
+            both_eliminated || expired_in_progress,
+            WagerError::GameNotResolvable
+        );
+
+        Ok(match self.winner_by_kills() {
+            Some(0) => WinnerOutcome::TeamA,
+            Some(_) => WinnerOutcome::TeamB,
+            None => WinnerOutcome::Draw,
+        })
+    }
+
+    /// Client-facing copy of the session's status, teams, bets and timestamps
+    pub fn snapshot(&self) -> SessionSnapshot {
+        let team_snapshot = |team: &Team| TeamSnapshot {
+            players: team.players,
+            player_spawns: team.player_spawns,
+            player_kills: team.player_kills,
+            total_bet: team.total_bet,
+        };
+
+        SessionSnapshot {
+            session_id: self.session_id.clone(),
+            authority: self.authority,
+            token_mint: self.token_mint,
+            game_mode: self.game_mode,
+            status: self.status.clone(),
+            session_bet: self.session_bet,
+            team_a_bet: self.team_a_bet,
+            team_b_bet: self.team_b_bet,
+            team_a: team_snapshot(&self.team_a),
+            team_b: team_snapshot(&self.team_b),
+            created_at: self.created_at,
+            expires_at: self.expires_at,
+            winning_team: self.winning_team,
+            is_draw: self.is_draw,
+            total_distributed: self.total_distributed,
+        }
+    }
+
+    /// Rejects distributing a session past expiry unless it was resolved first.
+    /// A game that finished in time stays payable however late the payout is.
+    pub fn check_distribution_window(&self, current_time: i64) -> Result<()> {
+        require!(
+            self.status == GameStatus::AwaitingDistribution || !self.is_expired(current_time),
+            WagerError::GameSessionExpired
+        );
+        Ok(())
+    }
+
+    /// Resolves an in-progress team game that ran past expiry on kills and the
+    /// kill tie breaker, leaving it ready to pay the winners or refund a draw.
+    /// A game resolved before expiry but never paid keeps its recorded result.
+    pub fn settle_expired(&mut self, current_time: i64) -> Result<WinnerOutcome> {
+        require!(
+            matches!(self.status, GameStatus::InProgress | GameStatus::AwaitingDistribution),
+            WagerError::GameNotInProgress
+        );
+        require!(self.is_expired(current_time), WagerError::SessionNotExpired);
+
+        if self.status == GameStatus::AwaitingDistribution {
+            return Ok(match self.winning_team {
+                Some(0) => WinnerOutcome::TeamA,
+                Some(_) => WinnerOutcome::TeamB,
+                None => WinnerOutcome::Draw,
+            });
+        }
+
+        let outcome = self.determine_final_winner(current_time)?;
+        self.mark_resolved(outcome.winning_team())?;
+        Ok(outcome)
+    }
+
+    /// Read-only snapshot of determine_final_winner plus both teams' kill and spawn totals
+    pub fn winner_view(&self, current_time: i64) -> Result<WinnerView> {
+        let outcome = self.determine_final_winner(current_time)?;
+        let player_count = self.game_mode.players_per_team();
+
+        Ok(WinnerView {
+            outcome,
+            team_a_kills: self.team_a.get_total_kills(player_count),
+            team_b_kills: self.team_b.get_total_kills(player_count),
+            team_a_spawns: self.team_a.get_total_spawns(player_count),
+            team_b_spawns: self.team_b.get_total_spawns(player_count),
+        })
+    }
+
+    /// Breaks a mutual elimination by total kills, None on an unbroken kill tie
+    pub fn resolve_by_kills(&self) -> Option<u8> {
+        let player_count = self.game_mode.players_per_team();
+
+        if !(self.team_a.is_eliminated(player_count) && self.team_b.is_eliminated(player_count)) {
+            return None;
+        }
+
+        self.winner_by_kills()
+    }
+
+    /// Resolves an expired in-progress game by total kills, None on an unbroken kill tie
+    pub fn resolve_expired_by_kills(&self, current_time: i64) -> Option<u8> {
+        if self.status != GameStatus::InProgress || !self.is_expired(current_time) {
+            return None;
+        }
+
+        self.winner_by_kills()
+    }
+
+    /// Picks the team with more total kills, applying the tie breaker on equal kills
+    fn winner_by_kills(&self) -> Option<u8> {
+        let player_count = self.game_mode.players_per_team();
+        let team_a_kills = self.team_a.get_total_kills(player_count);
+        let team_b_kills = self.team_b.get_total_kills(player_count);
+
+        match team_a_kills.cmp(&team_b_kills) {
+            std::cmp::Ordering::Greater => Some(0),
+            std::cmp::Ordering::Less => Some(1),
+            std::cmp::Ordering::Equal => self.break_kill_tie(),
+        }
+    }
+
+    /// Applies the configured tie breaker to an exact kill tie
+    fn break_kill_tie(&self) -> Option<u8> {
+        let player_count = self.game_mode.players_per_team();
+
+        match self.kill_tie_breaker {
+            KillTieBreaker::Draw => None,
+            KillTieBreaker::EarliestJoin => {
+                match (
+                    self.team_a.get_earliest_join(player_count),
+                    self.team_b.get_earliest_join(player_count),
+                ) {
+                    (Some(a), Some(b)) if a < b => Some(0),
+                    (Some(a), Some(b)) if b < a => Some(1),
+                    _ => None,
+                }
+            }
+            KillTieBreaker::HigherSpawnsRemaining => {
+                let team_a_spawns = self.team_a.get_total_spawns(player_count);
+                let team_b_spawns = self.team_b.get_total_spawns(player_count);
+
+                match team_a_spawns.cmp(&team_b_spawns) {
+                    std::cmp::Ordering::Greater => Some(0),
+                    std::cmp::Ordering::Less => Some(1),
+                    std::cmp::Ordering::Equal => None,
+                }
+            }
+        }
+    }
+
+    /// Update the kill tie breaker (only before the game starts, so the rule
+    /// can't be picked once the kill counts are known)
+    pub fn update_kill_tie_breaker(&mut self, tie_breaker: KillTieBreaker) -> Result<()> {
+        self.check_spawn_config_unlocked()?;
+
+        self.kill_tie_breaker = tie_breaker;
+        Ok(())
+    }
+
+    /// Get team statistics
+    pub fn get_team_stats(&self, team: u8) -> Result<(u32, u16)> {
+        let player_count = self.game_mode.players_per_team();
+        
+        match team {
+            0 => Ok((
+                self.team_a.get_total_kills(player_count),
+                self.team_a.player_spawns.iter().take(player_count).sum(),
+            )),
+            1 => Ok((
+                self.team_b.get_total_kills(player_count),
+                self.team_b.player_spawns.iter().take(player_count).sum(),
+            )),
+            _ => Err(error!(WagerError::InvalidTeam)),
+        }
+    }
+
+    /// Lives left across `team`'s seated players, for elimination progress bars.
+    /// Empty slots are skipped so stale spawns there can never inflate the count.
+    pub fn team_remaining_spawns(&self, team: u8) -> Result<u16> {
+        let player_count = self.game_mode.players_per_team();
+        let selected_team = match team {
+            0 => &self.team_a,
+            1 => &self.team_b,
+            _ => return Err(error!(WagerError::InvalidTeam)),
+        };
+
+        (0..player_count)
+            .filter(|&i| selected_team.players[i] != Pubkey::default())
+            .try_fold(0u16, |total, i| total.checked_add(selected_team.player_spawns[i]))
+            .ok_or(error!(WagerError::ArithmeticError))
+    }
+
+    /// Number of FreeForAll players that still have spawns left
+    pub fn get_free_for_all_survivors(&self) -> usize {
+        self.get_all_players()
+            .into_iter()
+            .filter(|player| {
+                self.get_player_team_and_index(*player)
+                    .map(|(team, index)| match team {
+                        0 => self.team_a.player_spawns[index] > 0,
+                        _ => self.team_b.player_spawns[index] > 0,
+                    })
+                    .unwrap_or(false)
+            })
+            .count()
+    }
+
+    /// The FreeForAll player with the strictly highest kills plus spawns, if any
+    pub fn free_for_all_winner(&self) -> Option<Pubkey> {
+        let mut best: Option<(Pubkey, u16)> = None;
+        let mut tied = false;
+
+        for (player, score) in self.all_kills_and_spawns().ok()? {
+            match best {
+                Some((_, top)) if score < top => {}
+                Some((_, top)) if score == top => tied = true,
+                _ => {
+                    best = Some((player, score));
+                    tied = false;
+                }
+            }
+        }
+
+        if tied {
+            return None;
+        }
+        best.map(|(player, _)| player)
+    }
+
+    /// Validate that the game session can transition to in-progress state
+    pub fn can_start(&self) -> Result<bool> {
+        require!(
+            self.status == GameStatus::WaitingForPlayers,
+            WagerError::InvalidGameState
+        );
+        
+        self.check_all_filled()
+    }
+
+    /// Completes an in-progress game, recording a draw when there is no winning team
+    pub fn mark_resolved(&mut self, winning_team: Option<u8>) -> Result<()> {
+        self.complete(winning_team.is_none())?;
+        self.winning_team = winning_team;
+        Ok(())
+    }
+
+    /// Completes an in-progress FreeForAll game, recording a draw on a tied top score
+    pub fn mark_free_for_all_resolved(&mut self) -> Result<()> {
+        self.complete(self.free_for_all_winner().is_none())
+    }
+
+    /// Explicitly ends an in-progress pay-to-spawn match so its earnings can be settled
+    pub fn end_match(&mut self) -> Result<()> {
+        require!(self.is_pay_to_spawn(), WagerError::InvalidGameMode);
+
+        self.complete(false)
+    }
+
+    fn complete(&mut self, is_draw: bool) -> Result<()> {
+        require!(
+            self.status == GameStatus::InProgress,
+            WagerError::GameNotInProgress
+        );
+
+        self.is_draw = is_draw;
+        self.status = GameStatus::AwaitingDistribution;
+        Ok(())
+    }
+
+    /// Checks whether the minimum gap between creation and start has elapsed
+    pub fn min_start_gap_elapsed(&self, current_time: i64) -> bool {
+        current_time.saturating_sub(self.created_at) >= self.min_time_before_start_seconds
+    }
+
+    /// The phase a client should route on. An in-progress game past expiry is
+    /// AwaitingSettlement, since settle_expired_game can close it out.
+    pub fn game_phase(&self, current_time: i64) -> GamePhase {
+        match self.status {
+            GameStatus::WaitingForPlayers if self.is_expired(current_time) => GamePhase::Expired,
+            GameStatus::WaitingForPlayers => {
+                let seated = if self.game_mode.is_free_for_all() {
+                    self.get_all_players().len() >= FREE_FOR_ALL_MIN_PLAYERS
+                } else {
+                    self.check_all_filled().unwrap_or(false)
+                };
+                if seated && self.min_start_gap_elapsed(current_time) {
+                    GamePhase::ReadyToStart
+                } else {
+                    GamePhase::Lobby
+                }
+            }
+            GameStatus::InProgress if self.is_expired(current_time) => GamePhase::AwaitingSettlement,
+            GameStatus::InProgress => GamePhase::Live,
+            GameStatus::AwaitingDistribution => GamePhase::AwaitingSettlement,
+            GameStatus::Completed | GameStatus::Distributed => GamePhase::Settled,
+            GameStatus::Expired => GamePhase::Expired,
+            GameStatus::Cancelled => GamePhase::Cancelled,
+        }
+    }
+
+    /// Transitions a filled session to in-progress once the minimum start gap has elapsed.
+    /// FreeForAll sessions may start with as few as FREE_FOR_ALL_MIN_PLAYERS.
+    pub fn start(&mut self, current_time: i64) -> Result<()> {
+        let ready = if self.game_mode.is_free_for_all() {
+            require!(
+                self.status == GameStatus::WaitingForPlayers,
+                WagerError::InvalidGameState
+            );
+            self.get_all_players().len() >= FREE_FOR_ALL_MIN_PLAYERS
+        } else {
+            self.can_start()?
+        };
+        require!(ready, WagerError::NotAllPlayersJoined);
+        require!(
+            self.min_start_gap_elapsed(current_time),
+            WagerError::TooEarlyToStart
+        );
+
+        self.status = GameStatus::InProgress;
+        Ok(())
+    }
+
+    /// Starts a casual queue that has not filled once its auto-start time has
+    /// passed, as long as both teams have someone to play against
+    pub fn auto_start(&mut self, current_time: i64) -> Result<()> {
+        require!(
+            self.status == GameStatus::WaitingForPlayers,
+            WagerError::InvalidGameState
+        );
+        let auto_start_at = self.auto_start_at.ok_or(WagerError::AutoStartNotConfigured)?;
+        require!(
+            current_time >= auto_start_at && self.min_start_gap_elapsed(current_time),
+            WagerError::TooEarlyToStart
+        );
+
+        let ready = if self.game_mode.is_free_for_all() {
+            self.get_all_players().len() >= FREE_FOR_ALL_MIN_PLAYERS
+        } else {
+            let player_count = self.game_mode.players_per_team();
+            self.team_a.get_active_player_count(player_count) > 0
+                && self.team_b.get_active_player_count(player_count) > 0
+        };
+        require!(ready, WagerError::NotAllPlayersJoined);
+
+        self.status = GameStatus::InProgress;
+        Ok(())
+    }
+
+    /// Sets or clears the auto-start time, which must fall inside the
+    /// session's lifetime (only before the game starts)
+    pub fn update_auto_start_at(&mut self, auto_start_at: Option<i64>) -> Result<()> {
+        require!(
+            self.status == GameStatus::WaitingForPlayers,
+            WagerError::GameAlreadyStarted
+        );
+        if let Some(at) = auto_start_at {
+            require!(
+                at >= self.created_at && at < self.expires_at,
+                WagerError::InvalidSessionDuration
+            );
+        }
+
+        self.auto_start_at = auto_start_at;
+        Ok(())
+    }
+
+    /// Pushes expires_at back by up to a day at a time, never past hard_deadline,
+    /// so an authority cannot keep player funds locked indefinitely
+    pub fn extend_expiry(&mut self, additional_seconds: i64) -> Result<()> {
+        require!(
+            self.status == GameStatus::WaitingForPlayers
+                || self.status == GameStatus::InProgress,
+            WagerError::InvalidGameState
+        );
+        require!(
+            additional_seconds > 0 && additional_seconds <= MAX_SESSION_EXTENSION_SECONDS,
+            WagerError::InvalidExtensionTime
+        );
+
+        let expires_at = self
+            .expires_at
+            .checked_add(additional_seconds)
+            .ok_or(WagerError::ArithmeticError)?;
+        require!(expires_at <= self.hard_deadline, WagerError::InvalidExtensionTime);
+
+        self.expires_at = expires_at;
+        Ok(())
+    }
+
+    /// Reserves team B of a 1v1 match for a single invited opponent
+    pub fn designate_opponent(&mut self, opponent: Pubkey) -> Result<()> {
+        require!(
+            self.game_mode.players_per_team() == 1,
+            WagerError::InvalidGameMode
+        );
+        require!(opponent != Pubkey::default(), WagerError::InvalidPlayer);
+        require!(
+            self.status == GameStatus::WaitingForPlayers,
+            WagerError::InvalidGameState
+        );
+
+        self.designated_opponent = Some(opponent);
+        Ok(())
+    }
+
+    /// Rejects anyone but the designated opponent from team B of a challenge
+    pub fn check_invited(&self, team: u8, player: &Pubkey) -> Result<()> {
+        if let (1, Some(opponent)) = (team, self.designated_opponent) {
+            require!(*player == opponent, WagerError::NotInvited);
+        }
+        Ok(())
+    }
+
+    /// With balance enforced, rejects a join that would leave `team` more than
+    /// one player ahead of the other side
+    pub fn check_team_balance(&self, team: u8) -> Result<()> {
+        if !self.enforce_balance {
+            return Ok(());
+        }
+
+        let player_count = self.game_mode.players_per_team();
+        let team_a = self.team_a.get_active_player_count(player_count);
+        let team_b = self.team_b.get_active_player_count(player_count);
+        let (joining, other) = match team {
+            0 => (team_a, team_b),
+            1 => (team_b, team_a),
+            _ => return Err(error!(WagerError::InvalidTeam)),
+        };
+        require!(joining <= other, WagerError::TeamImbalance);
+        Ok(())
+    }
+
+    /// With require_ata set, rejects a token account that is not the associated
+    /// token account of `owner` for the session mint
+    pub fn check_canonical_token_account(&self, owner: &Pubkey, token_account: &Pubkey) -> Result<()> {
+        if self.require_ata {
+            require!(
+                *token_account == get_associated_token_address(owner, &self.token_mint),
+                WagerError::NonCanonicalTokenAccount
+            );
+        }
+        Ok(())
+    }
+
+    /// Turns the canonical ATA requirement on or off, only before the game starts
+    pub fn set_require_ata(&mut self, require_ata: bool) -> Result<()> {
+        require!(
+            self.status == GameStatus::WaitingForPlayers,
+            WagerError::GameAlreadyStarted
+        );
+        self.require_ata = require_ata;
+        Ok(())
+    }
+
+    /// Turns join-time team balancing on or off, only before the game starts
+    pub fn set_enforce_balance(&mut self, enforce: bool) -> Result<()> {
+        require!(
+            self.status == GameStatus::WaitingForPlayers,
+            WagerError::GameAlreadyStarted
+        );
+        self.enforce_balance = enforce;
+        Ok(())
+    }
+
+    /// Update the minimum gap between creation and start (only before the game starts)
+    pub fn update_min_time_before_start(&mut self, seconds: i64) -> Result<()> {
+        require!(
+            self.status == GameStatus::WaitingForPlayers,
+            WagerError::GameAlreadyStarted
+        );
+        require!(
+            (0..=SESSION_TIMEOUT_SECONDS).contains(&seconds),
+            WagerError::InvalidSessionDuration
+        );
+
+        self.min_time_before_start_seconds = seconds;
+        Ok(())
+    }
+
+    /// Time after which the authority is considered gone if no heartbeat arrives
+    pub fn authority_liveness_deadline(&self) -> i64 {
+        self.last_heartbeat.saturating_add(self.liveness_window_seconds)
+    }
+
+    /// Checks whether the authority has been silent past its liveness window
+    pub fn is_authority_silent(&self, current_time: i64) -> bool {
+        current_time > self.authority_liveness_deadline()
+    }
+
+    /// Record that the authority is still active
+    pub fn record_heartbeat(&mut self, current_time: i64) -> Result<()> {
+        require!(
+            self.status != GameStatus::Distributed,
+            WagerError::SessionAlreadyFinalized
+        );
+
+        self.last_heartbeat = current_time;
+        Ok(())
+    }
+
+    /// Update how long the authority may stay silent before emergency refunds open
+    pub fn update_liveness_window(&mut self, seconds: i64) -> Result<()> {
+        require!(
+            self.status != GameStatus::Distributed,
+            WagerError::SessionAlreadyFinalized
+        );
+        require!(
+            (1..=MAX_LIVENESS_WINDOW_SECONDS).contains(&seconds),
+            WagerError::InvalidLivenessWindow
+        );
+
+        self.liveness_window_seconds = seconds;
+        Ok(())
+    }
+
+    /// Update how much each successive spawn purchase costs (only by authority,
+    /// only before the game starts)
+    pub fn update_spawn_cost_scale(&mut self, scale_bps: u16) -> Result<()> {
+        self.check_spawn_config_unlocked()?;
+        require!(
+            scale_bps <= MAX_SPAWN_COST_SCALE_BPS,
+            WagerError::InvalidSpawnCostScale
+        );
+
+        self.spawn_cost_scale_bps = scale_bps;
+        Ok(())
+    }
+
+    /// Adds a spectator stake to a team's pool. Betting closes when the game
+    /// starts, before any kill can hint at the outcome.
+    pub fn record_spectator_bet(&mut self, team: u8, amount: u64) -> Result<()> {
+        require!(!self.game_mode.is_free_for_all(), WagerError::InvalidGameMode);
+        require!(team == 0 || team == 1, WagerError::InvalidTeam);
+        require!(amount > 0, WagerError::InvalidBetAmount);
+        require!(
+            self.status == GameStatus::WaitingForPlayers,
+            WagerError::InvalidGameState
+        );
+
+        let pool = &mut self.spectator_pools[team as usize];
+        *pool = pool.checked_add(amount).ok_or(WagerError::ArithmeticError)?;
+        Ok(())
+    }
+
+    /// What a spectator stake on `team` pays out once the session is settled.
+    /// Winners get their stake back plus a share of the losing pool
+    /// proportional to their stake; draws, undecided, cancelled and expired
+    /// sessions refund the stake; losers get nothing.
+    pub fn spectator_payout(&self, team: u8, amount: u64) -> Result<u64> {
+        if matches!(self.status, GameStatus::Cancelled | GameStatus::Expired) {
+            return Ok(amount);
+        }
+
+        require!(
+            matches!(
+                self.status,
+                GameStatus::AwaitingDistribution | GameStatus::Completed | GameStatus::Distributed
+            ),
+            WagerError::InvalidGameState
+        );
+
+        let winning_team = match self.winning_team {
+            Some(winning_team) => winning_team,
+            None => return Ok(amount),
+        };
+        if team != winning_team {
+            return Ok(0);
+        }
+
+        let winning_pool = self.spectator_pools[winning_team as usize];
+        let losing_pool = self.spectator_pools[1 - winning_team as usize];
+        let share = (amount as u128)
+            .checked_mul(losing_pool as u128)
+            .and_then(|x| x.checked_div(winning_pool as u128))
+            .ok_or(WagerError::ArithmeticError)? as u64;
+
+        amount.checked_add(share).ok_or(error!(WagerError::ArithmeticError))
+    }
+
+    /// Change the bet while nobody has joined, so every stake stays equal (only by authority)
+    pub fn update_session_bet(&mut self, new_bet: u64) -> Result<()> {
+        require!(new_bet > 0, WagerError::InvalidBetAmount);
+        require!(
+            self.status == GameStatus::WaitingForPlayers,
+            WagerError::InvalidGameState
+        );
+        require!(
+            self.team_a.total_bet == 0 && self.team_b.total_bet == 0,
+            WagerError::PlayerAlreadyJoined
+        );
+
+        self.session_bet = new_bet;
+        self.team_a_bet = new_bet;
+        self.team_b_bet = new_bet;
+        Ok(())
+    }
+
+    /// Update the share of the bet kept when a player leaves (only by authority).
+    /// Only while the lobby is empty, so nobody stakes under one penalty and
+    /// leaves under another.
+    pub fn update_leave_penalty(&mut self, penalty_bps: u16) -> Result<()> {
+        require!(
+            self.status == GameStatus::WaitingForPlayers,
+            WagerError::ConfigLockedError
+        );
+        require!(self.get_all_players().is_empty(), WagerError::ConfigLockedError);
+        require!(
+            penalty_bps <= MAX_LEAVE_PENALTY_BPS,
+            WagerError::InvalidLeavePenalty
+        );
+
+        self.leave_penalty_bps = penalty_bps;
+        Ok(())
+    }
+
+    /// Sets how long a full lobby stays locked against leaves, up to MAX_LOBBY_LOCK_SECONDS.
+    /// Only while the lobby is still forming; the lock means nothing after the start.
+    pub fn update_lobby_lock(&mut self, lock_seconds: i64) -> Result<()> {
+        require!(
+            self.status == GameStatus::WaitingForPlayers,
+            WagerError::ConfigLockedError
+        );
+        require!(
+            (0..=MAX_LOBBY_LOCK_SECONDS).contains(&lock_seconds),
+            WagerError::InvalidLobbyLock
+        );
+
+        self.lobby_lock_seconds = lock_seconds;
+        Ok(())
+    }
+
+    /// Rejects a leave while the lobby is full and still inside its lock window,
+    /// which starts when the last seat was taken
+    pub fn check_lobby_unlocked(&self, current_time: i64) -> Result<()> {
+        if !self.check_all_filled()? {
+            return Ok(());
+        }
+
+        let player_count = self.game_mode.players_per_team();
+        let filled_at = [&self.team_a, &self.team_b]
+            .iter()
+            .flat_map(|team| team.player_joined_at.iter().take(player_count))
+            .copied()
+            .max()
+            .unwrap_or(0);
+        require!(
+            current_time >= filled_at.saturating_add(self.lobby_lock_seconds),
+            WagerError::LobbyLockedError
+        );
+        Ok(())
+    }
+
+    /// Checks `captain` holds slot 0 of `team` and `target` is someone else on it
+    pub fn check_captain(&self, team: u8, captain: Pubkey, target: Pubkey) -> Result<()> {
+        let players = match team {
+            0 => &self.team_a.players,
+            1 => &self.team_b.players,
+            _ => return Err(error!(WagerError::InvalidTeam)),
+        };
+        require!(
+            captain != Pubkey::default() && players[0] == captain,
+            WagerError::NotTeamCaptain
+        );
+        require!(target != captain, WagerError::NotTeamCaptain);
+        Ok(())
+    }
+
+    /// Splits what the player in `slot` of `team` paid in into (refund,
+    /// penalty kept in the vault)
+    pub fn leave_refund_split(&self, team: u8, slot: usize) -> Result<(u64, u64)> {
+        let bet = self.player_contribution(team, slot)?;
+        let refund = (bet as u128)
+            .checked_mul((BPS_DENOMINATOR - self.leave_penalty_bps as u64) as u128)
+            .ok_or(WagerError::ArithmeticError)?
+            .checked_div(BPS_DENOMINATOR as u128)
+            .ok_or(WagerError::ArithmeticError)? as u64;
+        let penalty = bet
+            .checked_sub(refund)
+            .ok_or(WagerError::ArithmeticError)?;
+
+        Ok((refund, penalty))
+    }
+
+    /// Update spawn purchase configuration (only by authority, only before the game starts)
+    pub fn update_spawns_per_purchase(&mut self, new_spawns_per_purchase: u16) -> Result<()> {
+        self.check_spawn_config_unlocked()?;
+        require!(
+            new_spawns_per_purchase > 0 && new_spawns_per_purchase <= 50,
+            WagerError::InvalidSpawnCount
+        );
+        
+        self.spawns_per_purchase = new_spawns_per_purchase;
+        Ok(())
+    }
+
+    /// Sets the lives players are seated with. Only while the lobby is still
+    /// empty, so everyone in a session starts with the same count
+    pub fn update_initial_spawns(&mut self, initial_spawns: u16) -> Result<()> {
+        self.check_spawn_config_unlocked()?;
+        require!(self.get_all_players().is_empty(), WagerError::InvalidGameState);
+        require!(
+            (1..=MAX_SPAWNS_PER_PLAYER).contains(&initial_spawns),
+            WagerError::InvalidSpawnCount
+        );
+
+        self.initial_spawns = initial_spawns;
+        Ok(())
+    }
+
+    /// Sets the caps on lives held at once and spawns bought over the session.
+    /// The concurrent cap never exceeds MAX_SPAWNS_PER_PLAYER.
+    pub fn update_spawn_caps(&mut self, max_concurrent: u16, max_lifetime: u16) -> Result<()> {
+        self.check_spawn_config_unlocked()?;
+        require!(
+            (1..=MAX_SPAWNS_PER_PLAYER).contains(&max_concurrent),
+            WagerError::InvalidSpawnCount
+        );
+        require!(max_lifetime > 0, WagerError::InvalidSpawnCount);
+
+        self.max_concurrent_spawns = max_concurrent;
+        self.max_lifetime_spawns = max_lifetime;
+        Ok(())
+    }
+
+    /// Rejects a purchase of `spawns_to_add` that would leave the player holding
+    /// more than max_concurrent_spawns lives, or having bought more than
+    /// max_lifetime_spawns over the session
+    pub fn check_spawn_caps(&self, team: u8, player_index: usize, spawns_to_add: u16) -> Result<()> {
+        require!(
+            player_index < MAX_PLAYERS_PER_TEAM,
+            WagerError::InvalidPlayerIndex
+        );
+
+        let selected_team = match team {
+            0 => &self.team_a,
+            1 => &self.team_b,
+            _ => return Err(error!(WagerError::InvalidTeam)),
+        };
+
+        let held = selected_team.player_spawns[player_index]
+            .checked_add(spawns_to_add)
+            .ok_or(WagerError::ArithmeticError)?;
+        require!(
+            held <= self.max_concurrent_spawns,
+            WagerError::MaxSpawnsExceeded
+        );
+
+        let bought = selected_team.player_spawns_bought[player_index]
+            .checked_add(spawns_to_add)
+            .ok_or(WagerError::LifetimeSpawnsExceeded)?;
+        require!(
+            bought <= self.max_lifetime_spawns,
+            WagerError::LifetimeSpawnsExceeded
+        );
+
+        Ok(())
+    }
+
+    /// Spawn pricing feeds pay-to-spawn earnings, so changing it once kills
+    /// and purchases are being recorded would rewrite the terms mid-game
+    pub fn check_spawn_config_unlocked(&self) -> Result<()> {
+        require!(
+            self.status == GameStatus::WaitingForPlayers,
+            WagerError::ConfigLockedError
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_mode_players_per_team() {
+        assert_eq!(GameMode::WinnerTakesAllOneVsOne.players_per_team(), 1);
+        assert_eq!(GameMode::WinnerTakesAllThreeVsThree.players_per_team(), 3);
+        assert_eq!(GameMode::WinnerTakesAllFiveVsFive.players_per_team(), 5);
+    }
+
+    #[test]
+    fn test_team_validation() {
+        let mut team = Team::default();
+        assert!(team.get_empty_slot(1).is_ok());
+        
+        // Fill first slot
+        team.players[0] = Pubkey::new_unique();
         assert!(team.get_empty_slot(1).is_err()); // Should be full for 1v1
         assert!(team.get_empty_slot(2).is_ok());  // Should have slot for 2v2+
     }
 
     #[test]
-    fn test_session_expiration() {
-        let current_time = 1000;
+    fn test_session_expiration() {
+        let current_time = 1000;
+        let session = GameSession::new(
+            "test".to_string(),
+            Pubkey::new_unique(),
+            100,
+            GameMode::WinnerTakesAllOneVsOne,
+            current_time,
+            1,
+            2,
+        ).unwrap();
+
+        assert!(!session.is_expired(current_time));
+        assert!(session.is_expired(current_time + SESSION_TIMEOUT_SECONDS + 1));
+    }
+
+    #[test]
+    fn test_cancellation_refunds_cover_seated_players() {
+        let mut session = empty_session(GameMode::WinnerTakesAllThreeVsThree);
+        let first = Pubkey::new_unique();
+        let second = Pubkey::new_unique();
+        session.seat_player(0, 0, first, 1000).unwrap();
+        session.seat_player(1, 2, second, 1000).unwrap();
+
+        assert_eq!(
+            session.get_cancellation_refunds().unwrap(),
+            vec![(first, 100), (second, 100)]
+        );
+
+        session.status = GameStatus::InProgress;
+        assert!(session.get_cancellation_refunds().is_err());
+    }
+
+    #[test]
+    fn test_challenge_only_admits_the_invited_opponent() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        let challenger = Pubkey::new_unique();
+        let opponent = Pubkey::new_unique();
+        session.seat_player(0, 0, challenger, 1000).unwrap();
+        session.designate_opponent(opponent).unwrap();
+
+        assert!(session.check_invited(1, &Pubkey::new_unique()).is_err());
+        assert!(session.check_invited(1, &opponent).is_ok());
+        // Open matches admit anyone
+        assert!(empty_session(GameMode::WinnerTakesAllOneVsOne)
+            .check_invited(1, &Pubkey::new_unique())
+            .is_ok());
+
+        assert!(empty_session(GameMode::WinnerTakesAllThreeVsThree)
+            .designate_opponent(opponent)
+            .is_err());
+    }
+
+    #[test]
+    fn test_extension_stops_at_hard_deadline() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        session.status = GameStatus::InProgress;
+        assert_eq!(session.hard_deadline, 1000 + MAX_SESSION_LIFETIME_SECONDS);
+
+        // Six full-day extensions fit inside the seven-day lifetime
+        for _ in 0..6 {
+            session.extend_expiry(MAX_SESSION_EXTENSION_SECONDS).unwrap();
+        }
+        assert!(session.extend_expiry(MAX_SESSION_EXTENSION_SECONDS).is_err());
+
+        // The remaining headroom can still be used exactly up to the deadline
+        let headroom = session.hard_deadline - session.expires_at;
+        session.extend_expiry(headroom).unwrap();
+        assert_eq!(session.expires_at, session.hard_deadline);
+        assert!(session.extend_expiry(1).is_err());
+    }
+
+    #[test]
+    fn test_leave_then_rejoin_starts_from_a_clean_slot() {
+        let mut session = empty_session(GameMode::PayToSpawnThreeVsThree);
+        let player = Pubkey::new_unique();
+        session.seat_player(0, 1, player, 1000).unwrap();
+        session.team_a.player_spawns[1] = 4;
+        session.team_a.player_kills[1] = 2;
+        session.team_a.player_spawn_purchases[1] = 1;
+
+        session.unseat_player(0, 1).unwrap();
+        assert_eq!(session.find_player(&player), None);
+        assert_eq!(session.team_a.total_bet, 0);
+        assert!(session.validate_player_not_joined(&player).is_ok());
+
+        // Re-join on the other team: fresh spawns, no stats carried over, one stake
+        session.seat_player(1, 0, player, 1100).unwrap();
+        assert_eq!(session.find_player(&player), Some((1, 0)));
+        assert_eq!(session.team_b.player_spawns[0], DEFAULT_SPAWN_COUNT);
+        assert_eq!(session.team_b.player_kills[0], 0);
+        assert_eq!(session.team_b.player_spawn_purchases[0], 0);
+        assert_eq!(session.team_b.player_joined_at[0], 1100);
+        assert_eq!(session.team_a.total_bet + session.team_b.total_bet, 100);
+        assert!(session.validate_player_not_joined(&player).is_err());
+
+        // Leaving the vacated slot again is rejected
+        assert!(session.unseat_player(0, 1).is_err());
+    }
+
+    #[test]
+    fn test_unfilled_refunds_with_one_team_seated() {
+        let mut session = empty_session(GameMode::WinnerTakesAllThreeVsThree);
+        let first = Pubkey::new_unique();
+        let second = Pubkey::new_unique();
+        session.seat_player(0, 0, first, 1000).unwrap();
+        session.seat_player(0, 1, second, 1000).unwrap();
+        let expired = session.expires_at;
+
+        // Not refundable until the session has expired
+        assert!(session.get_unfilled_refunds(expired - 1).is_err());
+        assert_eq!(
+            session.get_unfilled_refunds(expired).unwrap(),
+            vec![(first, 100), (second, 100)]
+        );
+
+        session.status = GameStatus::InProgress;
+        assert!(session.get_unfilled_refunds(expired).is_err());
+    }
+
+    #[test]
+    fn test_expire_stale_lobby() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        let player = Pubkey::new_unique();
+        session.seat_player(0, 0, player, 1000).unwrap();
+        let expired = session.expires_at;
+
+        assert!(session.expire(expired - 1).is_err());
+        assert!(session.status == GameStatus::WaitingForPlayers);
+
+        session.expire(expired).unwrap();
+        assert!(session.status == GameStatus::Expired);
+        assert_eq!(session.game_phase(expired), GamePhase::Expired);
+        assert!(session.expire(expired).is_err());
+
+        // Players still get their stake back through the unfilled refund flow
+        assert_eq!(session.get_unfilled_refunds(expired).unwrap(), vec![(player, 100)]);
+    }
+
+    #[test]
+    fn test_walkover_refunds_full_team_against_empty_team() {
+        let mut session = empty_session(GameMode::WinnerTakesAllThreeVsThree);
+        let team_a: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        for (slot, player) in team_a.iter().enumerate() {
+            session.seat_player(0, slot, *player, 1000).unwrap();
+        }
+        let expired = session.expires_at;
+
+        assert!(session.get_walkover_refunds(expired - 1).is_err());
+        assert_eq!(
+            session.get_walkover_refunds(expired).unwrap(),
+            team_a.iter().map(|player| (*player, 100)).collect::<Vec<_>>()
+        );
+
+        // A partial fill on both sides is not a walkover
+        session.seat_player(1, 0, Pubkey::new_unique(), 1000).unwrap();
+        assert!(session.get_walkover_refunds(expired).is_err());
+
+        // Nor is a team that never filled facing an empty one
+        let mut partial = empty_session(GameMode::WinnerTakesAllThreeVsThree);
+        partial.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        assert!(partial.get_walkover_refunds(expired).is_err());
+        assert!(partial.get_unfilled_refunds(expired).is_ok());
+    }
+
+    #[test]
+    fn test_all_kills_and_spawns_matches_per_player_lookups() {
+        let mut session = empty_session(GameMode::PayToSpawnThreeVsThree);
+        let seats = [(0u8, 0usize, 3u16), (0, 2, 1), (1, 1, 4)];
+        for (team, slot, kills) in seats {
+            session.seat_player(team, slot, Pubkey::new_unique(), 1000).unwrap();
+            let bank = if team == 0 { &mut session.team_a } else { &mut session.team_b };
+            bank.player_kills[slot] = kills;
+            bank.player_score[slot] = kills as u32 * 2;
+            bank.player_spawns[slot] -= kills;
+        }
+
+        let per_player: Vec<(Pubkey, u16)> = session
+            .get_all_players()
+            .into_iter()
+            .map(|player| (player, session.get_kills_and_spawns(player).unwrap()))
+            .collect();
+        assert_eq!(session.all_kills_and_spawns().unwrap(), per_player);
+
+        let per_player_scores: Vec<(Pubkey, u64, bool)> = session
+            .get_all_players()
+            .into_iter()
+            .map(|player| (player, session.get_score_and_spawns(player).unwrap(), false))
+            .collect();
+        assert_eq!(session.all_score_and_spawns().unwrap(), per_player_scores);
+
+        let total: u32 = per_player.iter().map(|(_, value)| *value as u32).sum();
+        assert_eq!(total, 3 * DEFAULT_SPAWN_COUNT as u32);
+    }
+
+    #[test]
+    fn test_player_stats_kd_ratio() {
+        let mut session = empty_session(GameMode::PayToSpawnThreeVsThree);
+        let player = Pubkey::new_unique();
+        session.seat_player(1, 2, player, 1000).unwrap();
+        session.team_b.player_kills[2] = 4;
+        session.team_b.player_spawns[2] = 6;
+
+        // 4 kills over 10 - 6 = 4 spawns lost
+        let stats = session.player_stats(player).unwrap();
+        assert_eq!(
+            stats,
+            PlayerStats { team: 1, index: 2, spawns: 6, kills: 4, kd_ratio_bps: 10_000 }
+        );
+    }
+
+    #[test]
+    fn test_weighted_kill_advances_score_by_weight() {
+        let mut session = empty_session(GameMode::PayToSpawnOneVsOne);
+        let killer = Pubkey::new_unique();
+        let victim = Pubkey::new_unique();
+        session.seat_player(0, 0, killer, 1000).unwrap();
+        session.seat_player(1, 0, victim, 1000).unwrap();
+        session.status = GameStatus::InProgress;
+
+        session.add_weighted_kill(0, killer, 1, victim, 3).unwrap();
+        assert_eq!(session.team_a.player_kills[0], 1);
+        assert_eq!(session.team_a.player_score[0], 3);
+
+        // A plain kill keeps the default weight
+        session.add_kill(0, killer, 1, victim).unwrap();
+        assert_eq!(session.team_a.player_kills[0], 2);
+        assert_eq!(session.team_a.player_score[0], 3 + DEFAULT_KILL_WEIGHT as u32);
+        assert_eq!(
+            session.get_score_and_spawns(killer).unwrap(),
+            4 + session.team_a.player_spawns[0] as u64
+        );
+    }
+
+    #[test]
+    fn test_friendly_fire_rejected_by_default() {
+        let mut session = empty_session(GameMode::PayToSpawnThreeVsThree);
+        let killer = Pubkey::new_unique();
+        let teammate = Pubkey::new_unique();
+        session.seat_player(0, 0, killer, 1000).unwrap();
+        session.seat_player(0, 1, teammate, 1000).unwrap();
+        session.status = GameStatus::InProgress;
+
+        assert!(session.add_kill(0, killer, 0, teammate).is_err());
+        assert_eq!(session.team_a.player_kills[0], 0);
+        assert_eq!(session.team_a.player_spawns[1], DEFAULT_SPAWN_COUNT);
+    }
+
+    #[test]
+    fn test_friendly_fire_allowed_when_enabled() {
+        let mut session = empty_session(GameMode::PayToSpawnThreeVsThree);
+        let killer = Pubkey::new_unique();
+        let teammate = Pubkey::new_unique();
+        session.seat_player(0, 0, killer, 1000).unwrap();
+        session.seat_player(0, 1, teammate, 1000).unwrap();
+        session.set_allow_friendly_fire(true).unwrap();
+        session.status = GameStatus::InProgress;
+
+        session.add_kill(0, killer, 0, teammate).unwrap();
+        assert_eq!(session.team_a.player_kills[0], 1);
+        assert_eq!(session.team_a.player_spawns[1], DEFAULT_SPAWN_COUNT - 1);
+
+        // The setting is locked once play has started
+        assert!(session.set_allow_friendly_fire(false).is_err());
+    }
+
+    #[test]
+    fn test_weighted_kill_rejects_out_of_range_weight() {
+        let mut session = empty_session(GameMode::PayToSpawnOneVsOne);
+        let killer = Pubkey::new_unique();
+        let victim = Pubkey::new_unique();
+        session.seat_player(0, 0, killer, 1000).unwrap();
+        session.seat_player(1, 0, victim, 1000).unwrap();
+        session.status = GameStatus::InProgress;
+
+        assert!(session.add_weighted_kill(0, killer, 1, victim, 0).is_err());
+        assert!(session
+            .add_weighted_kill(0, killer, 1, victim, MAX_KILL_WEIGHT + 1)
+            .is_err());
+        assert_eq!(session.team_a.player_kills[0], 0);
+        assert_eq!(session.team_a.player_score[0], 0);
+    }
+
+    #[test]
+    fn test_player_stats_without_deaths() {
+        let mut session = empty_session(GameMode::PayToSpawnThreeVsThree);
+        let player = Pubkey::new_unique();
+        session.seat_player(0, 0, player, 1000).unwrap();
+        session.team_a.player_kills[0] = 3;
+        session.team_a.player_spawns[0] = DEFAULT_SPAWN_COUNT;
+
+        assert_eq!(session.player_stats(player).unwrap().kd_ratio_bps, 30_000);
+        assert!(session.player_stats(Pubkey::new_unique()).is_err());
+    }
+
+    #[test]
+    fn test_player_stats_counts_bought_spawns_lost() {
+        let mut session = empty_session(GameMode::PayToSpawnOneVsOne);
+        let player = Pubkey::new_unique();
+        session.seat_player(0, 0, player, 1000).unwrap();
+        session.add_spawns(0, 0).unwrap();
+        let bought = session.team_a.player_spawns_bought[0];
+        assert!(bought > 0);
+
+        // Down to 5 spawns after buying more: every spawn lost is a death
+        session.team_a.player_spawns[0] = 5;
+        let deaths = (DEFAULT_SPAWN_COUNT + bought - 5) as u64;
+        session.team_a.player_kills[0] = (deaths * 2) as u16;
+
+        assert_eq!(session.player_stats(player).unwrap().kd_ratio_bps, 20_000);
+    }
+
+    #[test]
+    fn test_projected_solvency_boundary() {
+        let mut session = empty_session(GameMode::PayToSpawnOneVsOne);
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.seat_player(1, 0, Pubkey::new_unique(), 1000).unwrap();
+
+        // Each player is owed 10 spawns * 100 / 10 on entry
+        assert_eq!(session.projected_pay_spawn_earnings().unwrap(), 200);
+        assert!(session.check_projected_solvency(200).is_ok());
+        assert!(session.check_projected_solvency(199).is_err());
+
+        // A weighted kill moves one spawn from the victim into three score
+        session.status = GameStatus::InProgress;
+        let (killer, victim) = (session.team_a.players[0], session.team_b.players[0]);
+        session.add_weighted_kill(0, killer, 1, victim, 3).unwrap();
+        assert_eq!(session.projected_pay_spawn_earnings().unwrap(), 220);
+        assert!(session.check_projected_solvency(219).is_err());
+
+        // A more generous divisor doubles the obligation
+        session.set_earnings_divisor(5).unwrap();
+        assert_eq!(session.projected_pay_spawn_earnings().unwrap(), 440);
+        assert!(session.check_projected_solvency(440).is_ok());
+        assert!(session.check_projected_solvency(439).is_err());
+    }
+
+    #[test]
+    fn test_required_topup_for_oversubscribed_session() {
+        let mut session = empty_session(GameMode::PayToSpawnOneVsOne);
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.seat_player(1, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.status = GameStatus::InProgress;
+
+        // 200 projected against a 200 vault: solvent
+        assert_eq!(session.required_topup(200).unwrap(), 0);
+        assert!(session.add_topup(1, 200).is_err());
+
+        // A generous divisor doubles the obligation to 400
+        session.set_earnings_divisor(5).unwrap();
+        assert_eq!(session.required_topup(200).unwrap(), 200);
+        assert_eq!(session.required_topup(450).unwrap(), 0);
+
+        assert!(session.add_topup(201, 200).is_err());
+        session.add_topup(150, 200).unwrap();
+        assert_eq!(session.required_topup(350).unwrap(), 50);
+        session.add_topup(50, 350).unwrap();
+        assert_eq!(session.required_topup(400).unwrap(), 0);
+        assert_eq!(session.bonus_pool, 200);
+        assert_eq!(session.expected_vault_balance().unwrap(), 400);
+
+        session.status = GameStatus::AwaitingDistribution;
+        assert!(session.add_topup(1, 0).is_err());
+    }
+
+    #[test]
+    fn test_frozen_session_blocks_distribution() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        session.frozen = true;
+        assert!(session.begin_distribution().is_err());
+        assert!(!session.distribution_in_progress);
+
+        session.frozen = false;
+        assert!(session.begin_distribution().is_ok());
+    }
+
+    #[test]
+    fn test_auto_start_with_uneven_teams_at_deadline() {
+        let mut session = empty_session(GameMode::WinnerTakesAllThreeVsThree);
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.seat_player(0, 1, Pubkey::new_unique(), 1000).unwrap();
+        session.seat_player(1, 0, Pubkey::new_unique(), 1000).unwrap();
+
+        // Without a deadline a 2v1 cannot start
+        assert!(session.auto_start(5000).is_err());
+        assert!(session.start(5000).is_err());
+
+        session.update_auto_start_at(Some(1300)).unwrap();
+        assert!(session.auto_start(1299).is_err());
+        assert!(session.status == GameStatus::WaitingForPlayers);
+
+        session.auto_start(1300).unwrap();
+        assert!(session.status == GameStatus::InProgress);
+    }
+
+    #[test]
+    fn test_auto_start_needs_both_teams() {
+        let mut session = empty_session(GameMode::WinnerTakesAllThreeVsThree);
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.seat_player(0, 1, Pubkey::new_unique(), 1000).unwrap();
+        session.update_auto_start_at(Some(1300)).unwrap();
+
+        assert!(session.auto_start(1300).is_err());
+        assert!(session.update_auto_start_at(Some(999)).is_err());
+        assert!(session.update_auto_start_at(Some(session.expires_at)).is_err());
+    }
+
+    #[test]
+    fn test_sweepable_dust_only_once_settled() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        let player = Pubkey::new_unique();
+        session.seat_player(0, 0, player, 1000).unwrap();
+
+        session.status = GameStatus::AwaitingDistribution;
+        assert!(session.sweepable_dust(7).is_err());
+
+        session.status = GameStatus::Distributed;
+        assert_eq!(session.sweepable_dust(7).unwrap(), 7);
+        assert!(session.sweepable_dust(0).is_err());
+
+        // A cancelled session still owing a refund keeps its balance
+        session.status = GameStatus::Cancelled;
+        assert!(session.sweepable_dust(107).is_err());
+        session.record_payout(player, 100).unwrap();
+        assert_eq!(session.sweepable_dust(7).unwrap(), 7);
+    }
+
+    #[test]
+    fn test_vault_cap_defaults_to_unbounded() {
+        let session = empty_session(GameMode::PayToSpawnOneVsOne);
+
+        assert_eq!(session.max_vault_balance, u64::MAX);
+        assert!(session.check_vault_cap(u64::MAX).is_ok());
+    }
+
+    #[test]
+    fn test_vault_cap_rejects_deposit_pushing_over() {
+        let mut session = empty_session(GameMode::PayToSpawnOneVsOne);
+        session.max_vault_balance = 250;
+
+        // Two bets fill the vault to 200; a 50 purchase lands exactly on the cap
+        assert!(session.check_vault_cap(250).is_ok());
+        assert!(session.check_vault_cap(251).is_err());
+    }
+
+    #[test]
+    fn test_finished_game_awaits_distribution() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.seat_player(1, 0, Pubkey::new_unique(), 1000).unwrap();
+        assert!(session.status == GameStatus::WaitingForPlayers);
+
+        session.start(1000).unwrap();
+        assert!(session.status == GameStatus::InProgress);
+
+        session.mark_resolved(Some(0)).unwrap();
+        assert!(session.status == GameStatus::AwaitingDistribution);
+        assert_eq!(session.winning_team, Some(0));
+        assert!(session.mark_resolved(Some(1)).is_err());
+        // Nothing may be swept while the winners are still owed their payout
+        assert!(session.sweepable_dust(200).is_err());
+
+        // Appended last so existing accounts keep their status encoding
+        assert_eq!(GameStatus::Completed as u8, 2);
+        assert_eq!(GameStatus::AwaitingDistribution as u8, 6);
+    }
+
+    #[test]
+    fn test_final_winner_by_elimination() {
+        let mut session = filled_pay_to_spawn_session(0, 5);
+        session.team_a.player_spawns[0] = 1;
+        assert_eq!(session.determine_final_winner(1001).unwrap(), WinnerOutcome::TeamA);
+
+        let mut session = filled_pay_to_spawn_session(5, 0);
+        session.team_b.player_spawns[0] = 1;
+        assert_eq!(session.determine_final_winner(1001).unwrap(), WinnerOutcome::TeamB);
+    }
+
+    #[test]
+    fn test_winner_view_does_not_change_status() {
+        let mut session = filled_pay_to_spawn_session(0, 5);
+        session.team_a.player_spawns[0] = 2;
+        session.status = GameStatus::InProgress;
+
+        let view = session.winner_view(1001).unwrap();
+        assert_eq!(view.outcome, WinnerOutcome::TeamA);
+        assert_eq!(view.team_a_kills, 0);
+        assert_eq!(view.team_b_kills, 5);
+        assert_eq!(view.team_a_spawns, 2);
+        assert_eq!(view.team_b_spawns, 0);
+        assert!(session.status == GameStatus::InProgress);
+        assert_eq!(session.winning_team, None);
+    }
+
+    #[test]
+    fn test_final_winner_mutual_elimination_by_kills() {
+        let session = filled_pay_to_spawn_session(3, 5);
+        assert_eq!(session.determine_final_winner(1001).unwrap(), WinnerOutcome::TeamB);
+    }
+
+    #[test]
+    fn test_final_winner_kill_tie_uses_tie_breaker() {
+        let mut session = filled_pay_to_spawn_session(4, 4);
+        session.team_a.player_joined_at[0] = 1000;
+        session.team_b.player_joined_at[0] = 900;
+
+        // Spawn purchases play no part in breaking a kill tie
+        session.team_a.player_spawn_purchases[0] = 3;
+        assert_eq!(session.determine_final_winner(1001).unwrap(), WinnerOutcome::Draw);
+
+        session.kill_tie_breaker = KillTieBreaker::EarliestJoin;
+        assert_eq!(session.determine_final_winner(1001).unwrap(), WinnerOutcome::TeamB);
+        assert_eq!(
+            session.determine_final_winner(1001).unwrap().winning_team(),
+            session.resolve_by_kills()
+        );
+    }
+
+    #[test]
+    fn test_final_winner_full_tie_is_draw() {
+        let mut session = filled_pay_to_spawn_session(4, 4);
+        session.team_a.player_spawn_purchases[0] = 2;
+        session.team_b.player_spawn_purchases[0] = 2;
+        let outcome = session.determine_final_winner(1001).unwrap();
+        assert_eq!(outcome, WinnerOutcome::Draw);
+        assert_eq!(outcome.winning_team(), None);
+    }
+
+    #[test]
+    fn test_final_winner_expired_in_progress() {
+        let mut session = filled_pay_to_spawn_session(2, 1);
+        session.team_a.player_spawns[0] = 5;
+        session.team_b.player_spawns[0] = 5;
+        session.status = GameStatus::InProgress;
+
+        // Both teams still alive before expiry: the game is not over
+        assert!(session.determine_final_winner(1001).is_err());
+
+        let outcome = session.determine_final_winner(session.expires_at).unwrap();
+        assert_eq!(outcome, WinnerOutcome::TeamA);
+        assert_eq!(outcome.winning_team(), Some(0));
+    }
+
+    #[test]
+    fn test_finished_game_distributes_after_expiry() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        let (winner, loser) = (Pubkey::new_unique(), Pubkey::new_unique());
+        session.seat_player(0, 0, winner, 1000).unwrap();
+        session.seat_player(1, 0, loser, 1000).unwrap();
+        session.start(1000).unwrap();
+        let expired = session.expires_at;
+
+        // Still live: expiry closes the regular distribution path
+        assert!(session.check_distribution_window(expired).is_err());
+
+        // Finished in time, then expired before the payout went out
+        session.mark_resolved(Some(0)).unwrap();
+        session.check_distribution_window(expired + 3600).unwrap();
+
+        session.begin_distribution().unwrap();
+        for (player, amount) in session.winner_shares(0).unwrap() {
+            session.record_payout(player, amount).unwrap();
+            session.mark_claimed(player).unwrap();
+        }
+        session.end_distribution();
+        assert_eq!(session.total_distributed, 200);
+        assert_eq!(session.required_vault_balance().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_settle_expired_three_vs_three_team_a_leads_on_kills() {
+        let mut session = empty_session(GameMode::WinnerTakesAllThreeVsThree);
+        for slot in 0..3 {
+            session.seat_player(0, slot, Pubkey::new_unique(), 1000).unwrap();
+            session.seat_player(1, slot, Pubkey::new_unique(), 1000).unwrap();
+        }
+        session.start(1000).unwrap();
+        session.team_a.player_kills[0] = 2;
+        session.team_b.player_kills[1] = 1;
+
+        // Still live before expiry
+        assert!(session.settle_expired(session.expires_at - 1).is_err());
+
+        let outcome = session.settle_expired(session.expires_at).unwrap();
+        assert_eq!(outcome, WinnerOutcome::TeamA);
+        assert!(session.status == GameStatus::AwaitingDistribution);
+        assert_eq!(session.winning_team, Some(0));
+
+        let shares = session.winner_shares(0).unwrap();
+        assert_eq!(shares.len(), 3);
+        assert_eq!(shares.iter().map(|(_, amount)| amount).sum::<u64>(), 6 * 100);
+
+        // Settling again before payout keeps the recorded result
+        session.team_b.player_kills[2] = 5;
+        assert_eq!(session.settle_expired(session.expires_at).unwrap(), WinnerOutcome::TeamA);
+        assert!(session.status == GameStatus::AwaitingDistribution);
+
+        // Already paid out
+        session.status = GameStatus::Distributed;
+        assert!(session.settle_expired(session.expires_at).is_err());
+    }
+
+    #[test]
+    fn test_settle_expired_kill_tie_refunds_as_draw() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.seat_player(1, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.start(1000).unwrap();
+
+        assert_eq!(session.settle_expired(session.expires_at).unwrap(), WinnerOutcome::Draw);
+        assert!(session.is_draw);
+        assert_eq!(session.settle_as_draw().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_settle_expired_kill_tie_applies_tie_breaker() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.seat_player(1, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.update_kill_tie_breaker(KillTieBreaker::HigherSpawnsRemaining).unwrap();
+        session.start(1000).unwrap();
+        session.team_a.player_spawns[0] = 3;
+        session.team_b.player_spawns[0] = 5;
+
+        assert_eq!(session.settle_expired(session.expires_at).unwrap(), WinnerOutcome::TeamB);
+        assert_eq!(session.winning_team, Some(1));
+        assert!(!session.is_draw);
+    }
+
+    #[test]
+    fn test_kill_tie_breaker_locked_at_start() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.seat_player(1, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.update_kill_tie_breaker(KillTieBreaker::EarliestJoin).unwrap();
+
+        session.start(1000).unwrap();
+        assert_eq!(
+            session.update_kill_tie_breaker(KillTieBreaker::Draw).unwrap_err(),
+            error!(WagerError::ConfigLockedError)
+        );
+        assert!(session.kill_tie_breaker == KillTieBreaker::EarliestJoin);
+    }
+
+    #[test]
+    fn test_spectators_on_opposite_sides_after_team_a_wins() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        session.record_spectator_bet(0, 300).unwrap();
+        session.record_spectator_bet(1, 200).unwrap();
+        session.record_spectator_bet(0, 100).unwrap();
+        assert_eq!(session.spectator_pools, [400, 200]);
+
+        // Nothing is claimable while the outcome is open
+        assert!(session.spectator_payout(0, 300).is_err());
+
+        session.status = GameStatus::InProgress;
+        session.mark_resolved(Some(0)).unwrap();
+        assert!(session.record_spectator_bet(1, 50).is_err());
+
+        // 300 of the 400 backing team A takes three quarters of the losing 200
+        assert_eq!(session.spectator_payout(0, 300).unwrap(), 450);
+        assert_eq!(session.spectator_payout(0, 100).unwrap(), 150);
+        assert_eq!(session.spectator_payout(1, 200).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_spectator_stakes_refunded_on_draw() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        session.record_spectator_bet(0, 300).unwrap();
+        session.record_spectator_bet(1, 200).unwrap();
+        session.status = GameStatus::InProgress;
+        session.mark_resolved(None).unwrap();
+
+        assert_eq!(session.spectator_payout(0, 300).unwrap(), 300);
+        assert_eq!(session.spectator_payout(1, 200).unwrap(), 200);
+    }
+
+    #[test]
+    fn test_spectator_betting_closes_at_start_and_refunds_on_expiry() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        session.record_spectator_bet(0, 300).unwrap();
+
+        session.status = GameStatus::InProgress;
+        assert_eq!(
+            session.record_spectator_bet(1, 200).unwrap_err(),
+            error!(WagerError::InvalidGameState)
+        );
+        assert_eq!(session.spectator_pools, [300, 0]);
+
+        session.status = GameStatus::Expired;
+        assert_eq!(session.spectator_payout(0, 300).unwrap(), 300);
+    }
+
+    #[test]
+    fn test_update_session_bet_before_any_join() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+
+        session.update_session_bet(5_000).unwrap();
+        assert_eq!(session.session_bet, 5_000);
+        assert!(session.update_session_bet(0).is_err());
+    }
+
+    #[test]
+    fn test_update_session_bet_rejected_after_join() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+
+        assert!(session.update_session_bet(5_000).is_err());
+        assert_eq!(session.session_bet, 100);
+    }
+
+    #[test]
+    fn test_seconds_until_expiry_before_expiry() {
+        let session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+
+        assert_eq!(session.seconds_until_expiry(1000), SESSION_TIMEOUT_SECONDS);
+        assert_eq!(session.seconds_until_expiry(session.expires_at - 1), 1);
+    }
+
+    #[test]
+    fn test_seconds_until_expiry_exactly_expired() {
+        let session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+
+        assert_eq!(session.seconds_until_expiry(session.expires_at), 0);
+        assert!(session.is_expired(session.expires_at));
+    }
+
+    #[test]
+    fn test_seconds_until_expiry_past_expiry() {
+        let session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+
+        assert_eq!(session.seconds_until_expiry(session.expires_at + 500), 0);
+        assert_eq!(session.seconds_until_expiry(i64::MAX), 0);
+        assert_eq!(session.seconds_until_expiry(i64::MIN), SESSION_TIMEOUT_SECONDS);
+    }
+
+    #[test]
+    fn test_clock_reading_before_creation_is_ignored() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        assert_eq!(session.monotonic_time(999), 1000);
+        assert_eq!(session.monotonic_time(1001), 1001);
+
+        // A skewed reading earlier than creation never expires the session,
+        // even if expires_at was pulled back to the creation time
+        session.expires_at = session.created_at;
+        assert!(!session.is_expired(999));
+        assert!(session.is_expired(1000));
+        assert_eq!(session.seconds_until_expiry(999), 0);
+    }
+
+    fn empty_session(game_mode: GameMode) -> GameSession {
+        GameSession::new(
+            "test".to_string(),
+            Pubkey::new_unique(),
+            100,
+            game_mode,
+            1000,
+            1,
+            2,
+        ).unwrap()
+    }
+
+    fn filled_pay_to_spawn_session(team_a_kills: u16, team_b_kills: u16) -> GameSession {
+        let mut session = GameSession::new(
+            "test".to_string(),
+            Pubkey::new_unique(),
+            100,
+            GameMode::PayToSpawnOneVsOne,
+            1000,
+            1,
+            2,
+        ).unwrap();
+        session.team_a.players[0] = Pubkey::new_unique();
+        session.team_b.players[0] = Pubkey::new_unique();
+        session.team_a.player_kills[0] = team_a_kills;
+        session.team_b.player_kills[0] = team_b_kills;
+        session
+    }
+
+    #[test]
+    fn test_resolve_by_kills_both_eliminated_team_b_ahead() {
+        let session = filled_pay_to_spawn_session(3, 5);
+
+        assert_eq!(session.check_winner(), None);
+        assert_eq!(session.resolve_by_kills(), Some(1));
+    }
+
+    #[test]
+    fn test_resolve_by_kills_exact_tie() {
+        let session = filled_pay_to_spawn_session(4, 4);
+
+        assert_eq!(session.check_winner(), None);
+        assert_eq!(session.resolve_by_kills(), None);
+    }
+
+    fn expired_kill_tie_session(tie_breaker: KillTieBreaker) -> GameSession {
+        let mut session = filled_pay_to_spawn_session(4, 4);
+        session.status = GameStatus::InProgress;
+        session.kill_tie_breaker = tie_breaker;
+
+        // Team B joined first, team A has more spawns left
+        session.team_a.player_joined_at[0] = 1010;
+        session.team_b.player_joined_at[0] = 1005;
+        session.team_a.player_spawns[0] = 7;
+        session.team_b.player_spawns[0] = 2;
+        session
+    }
+
+    #[test]
+    fn test_kill_tie_breakers_resolve_differently() {
+        let expired = 1000 + SESSION_TIMEOUT_SECONDS;
+
+        let draw = expired_kill_tie_session(KillTieBreaker::Draw);
+        assert_eq!(draw.resolve_expired_by_kills(expired), None);
+
+        let earliest_join = expired_kill_tie_session(KillTieBreaker::EarliestJoin);
+        assert_eq!(earliest_join.resolve_expired_by_kills(expired), Some(1));
+
+        let spawns = expired_kill_tie_session(KillTieBreaker::HigherSpawnsRemaining);
+        assert_eq!(spawns.resolve_expired_by_kills(expired), Some(0));
+
+        // Not resolvable before expiry
+        assert_eq!(spawns.resolve_expired_by_kills(expired - 1), None);
+    }
+
+    #[test]
+    fn test_start_rejected_before_min_gap() {
+        let mut session = filled_pay_to_spawn_session(0, 0);
+        session.update_min_time_before_start(60).unwrap();
+
+        // created_at is 1000, so a start at 1059 is one second too early
+        assert!(session.start(1059).is_err());
+        assert!(session.status == GameStatus::WaitingForPlayers);
+    }
+
+    #[test]
+    fn test_start_allowed_after_min_gap() {
+        let mut session = filled_pay_to_spawn_session(0, 0);
+        session.update_min_time_before_start(60).unwrap();
+
+        session.start(1060).unwrap();
+        assert!(session.status == GameStatus::InProgress);
+    }
+
+    #[test]
+    fn test_settlement_report_winner_takes_all_one_vs_one() {
+        let mut session = GameSession::new(
+            "test".to_string(),
+            Pubkey::new_unique(),
+            100,
+            GameMode::WinnerTakesAllOneVsOne,
+            1000,
+            1,
+            2,
+        ).unwrap();
+        let winner = Pubkey::new_unique();
+        let loser = Pubkey::new_unique();
+        session.seat_player(0, 0, winner, 1000).unwrap();
+        session.seat_player(1, 0, loser, 1000).unwrap();
+
+        session.record_payout(winner, 200).unwrap();
+
+        let report = session.get_settlement_report(10).unwrap();
+        assert_eq!(report.len(), 2);
+        assert!(report[0].player == winner && report[0].contributed == 100);
+        assert_eq!(report[0].net, 100);
+        assert!(report[1].player == loser && report[1].paid_out == 0);
+        assert_eq!(report[1].net, -100);
+        assert_eq!(session.total_distributed, 200);
+
+        // Entries beyond the cap are dropped
+        assert_eq!(session.get_settlement_report(1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_spawn_cost_scales_with_each_purchase() {
+        let mut session = filled_pay_to_spawn_session(0, 0);
+        session.update_spawn_cost_scale(2_500).unwrap();
+        session.status = GameStatus::InProgress;
+
+        let first = session.spawn_purchase_cost(0, 0).unwrap();
+        session.add_spawns(0, 0).unwrap();
+        let second = session.spawn_purchase_cost(0, 0).unwrap();
+        session.add_spawns(0, 0).unwrap();
+        let third = session.spawn_purchase_cost(0, 0).unwrap();
+
+        assert_eq!(first, 100);
+        assert_eq!(second, 125);
+        assert_eq!(third, 150);
+        assert!(first < second && second < third);
+
+        // A bundle of purchases costs the sum of each successive purchase
+        assert_eq!(session.spawn_bundle_cost(0, 0, 2).unwrap(), 150 + 175);
+
+        // The other player's cost is unaffected
+        assert_eq!(session.spawn_purchase_cost(1, 0).unwrap(), 100);
+        assert_eq!(session.spawn_bundle_cost(1, 0, 5).unwrap(), 100 + 125 + 150 + 175 + 200);
+        assert!(session.update_spawn_cost_scale(MAX_SPAWN_COST_SCALE_BPS + 1).is_err());
+    }
+
+    #[test]
+    fn test_team_remaining_spawns_skips_empty_slots() {
+        let mut session = empty_session(GameMode::PayToSpawnThreeVsThree);
+        assert_eq!(session.team_remaining_spawns(0).unwrap(), 0);
+
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.seat_player(0, 2, Pubkey::new_unique(), 1000).unwrap();
+        session.team_a.player_spawns[2] = 3;
+        // Leftover spawns in an empty slot are not counted
+        session.team_a.player_spawns[1] = 7;
+
+        assert_eq!(session.team_remaining_spawns(0).unwrap(), DEFAULT_SPAWN_COUNT + 3);
+        assert_eq!(session.team_remaining_spawns(1).unwrap(), 0);
+        assert!(session.team_remaining_spawns(2).is_err());
+
+        session.team_a.player_spawns[0] = u16::MAX;
+        assert!(session.team_remaining_spawns(0).is_err());
+    }
+
+    fn ack_session() -> (GameSession, Pubkey, Pubkey) {
+        let mut session = filled_pay_to_spawn_session(0, 0);
+        session.set_require_victim_ack(true).unwrap();
+        session.status = GameStatus::InProgress;
+        session.team_a.player_spawns[0] = 2;
+        session.team_b.player_spawns[0] = 2;
+        let killer = session.team_a.players[0];
+        let victim = session.team_b.players[0];
+        (session, killer, victim)
+    }
+
+    #[test]
+    fn test_victim_ack_applies_pending_kill() {
+        let (mut session, killer, victim) = ack_session();
+
+        assert!(!session.report_kill(0, killer, 1, victim, DEFAULT_KILL_WEIGHT, 2000).unwrap());
+        assert_eq!(session.team_a.player_kills[0], 0);
+        assert_eq!(session.team_b.player_spawns[0], 2);
+        assert!(session.report_kill(0, killer, 1, victim, DEFAULT_KILL_WEIGHT, 2001).is_err());
+
+        // Only the reported (killer, victim) pair can be acknowledged
+        assert!(session.ack_kill(victim, killer).is_err());
+        session.ack_kill(killer, victim).unwrap();
+        assert_eq!(session.team_a.player_kills[0], 1);
+        assert_eq!(session.team_b.player_spawns[0], 1);
+        assert!(session.pending_kills.is_empty());
+        assert!(session.ack_kill(killer, victim).is_err());
+
+        // Without ack mode kills apply at once, and the mode locks at start
+        assert!(session.set_require_victim_ack(false).is_err());
+        session.require_victim_ack = false;
+        assert!(session.report_kill(0, killer, 1, victim, DEFAULT_KILL_WEIGHT, 2002).unwrap());
+        assert_eq!(session.team_a.player_kills[0], 2);
+    }
+
+    #[test]
+    fn test_unacknowledged_kill_confirms_after_timeout() {
+        let (mut session, killer, victim) = ack_session();
+        session.report_kill(0, killer, 1, victim, DEFAULT_KILL_WEIGHT, 2000).unwrap();
+        session.report_kill(1, victim, 0, killer, DEFAULT_KILL_WEIGHT, 2100).unwrap();
+
+        assert_eq!(session.confirm_timed_out_kills(2000 + KILL_ACK_TIMEOUT_SECONDS - 1).unwrap(), 0);
+        assert_eq!(session.pending_kills.len(), 2);
+
+        assert_eq!(session.confirm_timed_out_kills(2000 + KILL_ACK_TIMEOUT_SECONDS).unwrap(), 1);
+        assert_eq!(session.team_a.player_kills[0], 1);
+        assert_eq!(session.team_b.player_spawns[0], 1);
+        assert_eq!(session.team_b.player_kills[0], 0);
+        assert_eq!(session.pending_kills.len(), 1);
+
+        assert_eq!(session.confirm_timed_out_kills(2100 + KILL_ACK_TIMEOUT_SECONDS).unwrap(), 1);
+        assert_eq!(session.team_b.player_kills[0], 1);
+        assert!(session.pending_kills.is_empty());
+    }
+
+    #[test]
+    fn test_timed_out_kill_that_cannot_apply_is_dropped() {
+        let (mut session, killer, victim) = ack_session();
+        session.report_kill(0, killer, 1, victim, DEFAULT_KILL_WEIGHT, 2000).unwrap();
+        session.report_kill(1, victim, 0, killer, DEFAULT_KILL_WEIGHT, 2001).unwrap();
+
+        // The first victim ran out of spawns before their kill timed out
+        session.team_b.player_spawns[0] = 0;
+        let now = 2001 + KILL_ACK_TIMEOUT_SECONDS;
+        assert_eq!(session.confirm_timed_out_kills(now).unwrap(), 1);
+        assert!(session.pending_kills.is_empty());
+        assert_eq!(session.team_a.player_kills[0], 0);
+        assert_eq!(session.team_b.player_kills[0], 1);
+        assert_eq!(session.team_a.player_spawns[0], 1);
+    }
+
+    fn batch_session() -> (GameSession, Pubkey, Pubkey) {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        session.seat_player(0, 0, a, 1000).unwrap();
+        session.seat_player(1, 0, b, 1000).unwrap();
+        session.status = GameStatus::InProgress;
+        session.team_a.player_spawns[0] = 3;
+        session.team_b.player_spawns[0] = 3;
+        (session, a, b)
+    }
+
+    fn kill(killer_team: u8, killer: Pubkey, victim: Pubkey) -> KillRecord {
+        KillRecord {
+            killer_team,
+            killer,
+            victim_team: 1 - killer_team,
+            victim,
+            weight: DEFAULT_KILL_WEIGHT,
+        }
+    }
+
+    #[test]
+    fn test_kill_batch_eliminating_team_resolves_game() {
+        let (mut session, a, b) = batch_session();
+        let kills = vec![kill(0, a, b), kill(1, b, a), kill(0, a, b), kill(0, a, b)];
+
+        assert_eq!(session.record_kills_batch(&kills, 2000).unwrap(), Some(0));
+        assert_eq!(session.team_a.player_kills[0], 3);
+        assert_eq!(session.team_b.player_kills[0], 1);
+        assert_eq!(session.team_b.player_spawns[0], 0);
+        assert!(session.status == GameStatus::AwaitingDistribution);
+        assert_eq!(session.winning_team, Some(0));
+    }
+
+    #[test]
+    fn test_kill_batch_rejects_invalid_victim() {
+        let (mut session, a, b) = batch_session();
+        let kills = vec![kill(0, a, b), kill(0, a, Pubkey::new_unique())];
+
+        assert!(session.record_kills_batch(&kills, 2000).is_err());
+        assert!(session.record_kills_batch(&[], 2000).is_err());
+        let oversized = vec![kill(0, a, b); MAX_KILLS_PER_BATCH + 1];
+        assert!(session.record_kills_batch(&oversized, 2000).is_err());
+    }
+
+    #[test]
+    fn test_mint_migration_requires_empty_vault() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        let new_mint = Pubkey::new_unique();
+
+        assert!(session.migrate_mint(new_mint, 9, 1).is_err());
+        assert!(session.migrate_mint(session.token_mint, 9, 0).is_err());
+
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        assert!(session.migrate_mint(new_mint, 9, 0).is_err());
+        assert_ne!(session.token_mint, new_mint);
+
+        let mut fresh = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        fresh.migrate_mint(new_mint, 9, 0).unwrap();
+        assert_eq!(fresh.token_mint, new_mint);
+        assert_eq!(fresh.token_decimals, 9);
+
+        fresh.status = GameStatus::InProgress;
+        assert!(fresh.migrate_mint(Pubkey::new_unique(), 6, 0).is_err());
+    }
+
+    #[test]
+    fn test_vault_balance_matches_after_two_joins() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.seat_player(1, 0, Pubkey::new_unique(), 1001).unwrap();
+
+        let view = session.vault_balance(200).unwrap();
+        assert_eq!(view.balance, 200);
+        assert_eq!(view.expected, 200);
+        assert_eq!(view.status, GameStatus::WaitingForPlayers as u8);
+
+        // A short vault shows up as a gap between the two figures
+        assert_eq!(session.vault_balance(150).unwrap().expected, 200);
+    }
+
+    #[test]
+    fn test_full_lobby_locked_against_leaves() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        // A lobby that is not full can always be left
+        session.check_lobby_unlocked(1000).unwrap();
+
+        session.seat_player(1, 0, Pubkey::new_unique(), 1010).unwrap();
+        assert!(session.check_lobby_unlocked(1010).is_err());
+        assert!(session.check_lobby_unlocked(1010 + DEFAULT_LOBBY_LOCK_SECONDS - 1).is_err());
+        session.check_lobby_unlocked(1010 + DEFAULT_LOBBY_LOCK_SECONDS).unwrap();
+
+        session.update_lobby_lock(0).unwrap();
+        session.check_lobby_unlocked(1010).unwrap();
+        assert!(session.update_lobby_lock(MAX_LOBBY_LOCK_SECONDS + 1).is_err());
+        assert!(session.update_lobby_lock(-1).is_err());
+
+        session.start(1010).unwrap();
+        assert_eq!(
+            session.update_lobby_lock(60).unwrap_err(),
+            error!(WagerError::ConfigLockedError)
+        );
+        assert_eq!(session.lobby_lock_seconds, 0);
+    }
+
+    #[test]
+    fn test_state_hash_commits_to_event_order() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let seated = || {
+            let mut session = empty_session(GameMode::PayToSpawnOneVsOne);
+            session.seat_player(0, 0, a, 1000).unwrap();
+            session.seat_player(1, 0, b, 1000).unwrap();
+            session.status = GameStatus::InProgress;
+            session
+        };
+
+        let mut first = seated();
+        first.add_spawns(0, 0).unwrap();
+        first.add_kill(0, a, 1, b).unwrap();
+
+        let mut replay = seated();
+        replay.add_spawns(0, 0).unwrap();
+        replay.add_kill(0, a, 1, b).unwrap();
+        assert_eq!(first.state_hash, replay.state_hash);
+
+        let mut reordered = seated();
+        reordered.add_kill(0, a, 1, b).unwrap();
+        reordered.add_spawns(0, 0).unwrap();
+        assert_eq!(reordered.team_a.player_spawns, first.team_a.player_spawns);
+        assert_ne!(reordered.state_hash, first.state_hash);
+        assert_ne!(first.state_hash, [0; 32]);
+    }
+
+    #[test]
+    fn test_only_captain_can_kick() {
+        let mut session = empty_session(GameMode::WinnerTakesAllThreeVsThree);
+        let (captain, second, third) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        session.seat_player(0, 0, captain, 1000).unwrap();
+        session.seat_player(0, 1, second, 1000).unwrap();
+        session.seat_player(0, 2, third, 1000).unwrap();
+
+        session.check_captain(0, captain, second).unwrap();
+        assert!(session.check_captain(0, second, third).is_err());
+        assert!(session.check_captain(0, captain, captain).is_err());
+        // Slot 0 of the other, empty team is nobody's captain seat
+        assert!(session.check_captain(1, captain, second).is_err());
+
+        let index = session.get_player_index(0, second).unwrap();
+        session.unseat_player(0, index).unwrap();
+        assert_eq!(session.team_a.players[1], Pubkey::default());
+        assert_eq!(session.team_a.total_bet, 200);
+    }
+
+    #[test]
+    fn test_leave_after_spawn_purchase_refunds_full_contribution() {
+        let mut session = empty_session(GameMode::PayToSpawnThreeVsThree);
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.seat_player(0, 1, Pubkey::new_unique(), 1000).unwrap();
+
+        // Slot 1 buys spawns before the game starts
+        let cost = session.spawn_purchase_cost(0, 1).unwrap();
+        session.add_spawns(0, 1).unwrap();
+        session.record_contribution(0, 1, cost).unwrap();
+        session.team_a.total_bet += cost;
+        assert_eq!(session.team_a.total_bet, 200 + cost);
+
+        assert_eq!(session.leave_refund_split(0, 1).unwrap(), (100 + cost, 0));
+        session.unseat_player(0, 1).unwrap();
+        assert_eq!(session.team_a.total_bet, 100);
+        assert_eq!(session.expected_vault_balance().unwrap(), 100);
+    }
+
+    #[test]
+    fn test_failure_threshold_boundary() {
+        let mut session = empty_session(GameMode::PayToSpawnFiveVsFive);
+        // The default keeps the old more-than-half rule
+        assert!(!session.exceeds_failure_threshold(5, 10));
+        assert!(session.exceeds_failure_threshold(6, 10));
+
+        session.set_max_failure_bps(3_000).unwrap();
+        assert!(!session.exceeds_failure_threshold(3, 10));
+        assert!(session.exceeds_failure_threshold(4, 10));
+        assert!(!session.exceeds_failure_threshold(0, 0));
+
+        assert!(session.set_max_failure_bps(10_001).is_err());
+    }
+
+    #[test]
+    fn test_failure_threshold_locked_at_start() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        session.set_max_failure_bps(2_000).unwrap();
+
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.seat_player(1, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.start(1000).unwrap();
+        assert_eq!(
+            session.set_max_failure_bps(10_000).unwrap_err(),
+            error!(WagerError::ConfigLockedError)
+        );
+        assert_eq!(session.max_failure_bps, 2_000);
+    }
+
+    #[test]
+    fn test_enforced_balance_sends_second_player_to_team_b() {
+        let mut session = empty_session(GameMode::WinnerTakesAllThreeVsThree);
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        // Without enforcement team A can keep filling
+        session.check_team_balance(0).unwrap();
+
+        session.set_enforce_balance(true).unwrap();
+        assert!(session.check_team_balance(0).is_err());
+        session.check_team_balance(1).unwrap();
+
+        session.seat_player(1, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.check_team_balance(0).unwrap();
+        session.check_team_balance(1).unwrap();
+
+        session.status = GameStatus::InProgress;
+        assert!(session.set_enforce_balance(false).is_err());
+    }
+
+    #[test]
+    fn test_configured_initial_spawns_seat_players() {
+        let mut session = empty_session(GameMode::PayToSpawnOneVsOne);
+        assert_eq!(session.initial_spawns, DEFAULT_SPAWN_COUNT);
+        assert!(session.update_initial_spawns(0).is_err());
+        assert!(session.update_initial_spawns(MAX_SPAWNS_PER_PLAYER + 1).is_err());
+
+        session.update_initial_spawns(3).unwrap();
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.seat_player(1, 0, Pubkey::new_unique(), 1000).unwrap();
+        assert_eq!(session.team_a.player_spawns[0], 3);
+        assert_eq!(session.team_b.player_spawns[0], 3);
+
+        // Seated players keep what they joined with
+        assert!(session.update_initial_spawns(5).is_err());
+    }
+
+    #[test]
+    fn test_game_phase_for_each_state() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        let live = 1001;
+        let expired = session.expires_at;
+
+        assert_eq!(session.game_phase(live), GamePhase::Lobby);
+        assert_eq!(session.game_phase(expired), GamePhase::Expired);
+
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        assert_eq!(session.game_phase(live), GamePhase::Lobby);
+        session.seat_player(1, 0, Pubkey::new_unique(), 1000).unwrap();
+        assert_eq!(session.game_phase(live), GamePhase::ReadyToStart);
+
+        // Filled but still inside the minimum start gap
+        session.min_time_before_start_seconds = 60;
+        assert_eq!(session.game_phase(live), GamePhase::Lobby);
+        assert_eq!(session.game_phase(1060), GamePhase::ReadyToStart);
+
+        session.status = GameStatus::InProgress;
+        assert_eq!(session.game_phase(live), GamePhase::Live);
+        assert_eq!(session.game_phase(expired), GamePhase::AwaitingSettlement);
+
+        session.status = GameStatus::AwaitingDistribution;
+        assert_eq!(session.game_phase(live), GamePhase::AwaitingSettlement);
+
+        session.status = GameStatus::Distributed;
+        assert_eq!(session.game_phase(live), GamePhase::Settled);
+        session.status = GameStatus::Completed;
+        assert_eq!(session.game_phase(expired), GamePhase::Settled);
+
+        session.status = GameStatus::Expired;
+        assert_eq!(session.game_phase(live), GamePhase::Expired);
+        session.status = GameStatus::Cancelled;
+        assert_eq!(session.game_phase(live), GamePhase::Cancelled);
+    }
+
+    #[test]
+    fn test_require_ata_rejects_non_canonical_account() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        let owner = Pubkey::new_unique();
+        let ata = get_associated_token_address(&owner, &session.token_mint);
+        let other_account = Pubkey::new_unique();
+
+        // Any owned account passes while the flag is off
+        session.check_canonical_token_account(&owner, &other_account).unwrap();
+
+        session.set_require_ata(true).unwrap();
+        session.check_canonical_token_account(&owner, &ata).unwrap();
+        assert!(session.check_canonical_token_account(&owner, &other_account).is_err());
+        // Another wallet's ATA is not this owner's
+        assert!(session
+            .check_canonical_token_account(&Pubkey::new_unique(), &ata)
+            .is_err());
+
+        session.status = GameStatus::InProgress;
+        assert!(session.set_require_ata(false).is_err());
+    }
+
+    #[test]
+    fn test_reset_session_accepts_clean_joins() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        let (winner, loser) = (Pubkey::new_unique(), Pubkey::new_unique());
+        session.seat_player(0, 0, winner, 1000).unwrap();
+        session.seat_player(1, 0, loser, 1000).unwrap();
+        session.start(1000).unwrap();
+        session.team_a.player_kills[0] = 1;
+        session.mark_resolved(Some(0)).unwrap();
+
+        // Not settled yet
+        assert!(session.reset(500, GameMode::WinnerTakesAllThreeVsThree, 5000, 0).is_err());
+
+        session.record_payout(winner, 200).unwrap();
+        session.mark_claimed(winner).unwrap();
+        session.status = GameStatus::Distributed;
+        assert!(session.reset(500, GameMode::WinnerTakesAllThreeVsThree, 5000, 1).is_err());
+
+        session.reset(500, GameMode::WinnerTakesAllThreeVsThree, 5000, 0).unwrap();
+        assert!(session.status == GameStatus::WaitingForPlayers);
+        assert_eq!(session.session_bet, 500);
+        assert!(session.game_mode == GameMode::WinnerTakesAllThreeVsThree);
+        assert_eq!(session.created_at, 5000);
+        assert_eq!(session.expires_at, 5000 + SESSION_TIMEOUT_SECONDS);
+        assert_eq!(session.total_distributed, 0);
+        assert_eq!(session.winning_team, None);
+        assert!(session.get_all_players().is_empty());
+
+        // The old winner rejoins into a clean slot with no carried-over stats
+        session.seat_player(1, 0, winner, 5000).unwrap();
+        assert_eq!(session.team_b.player_kills[0], 0);
+        assert!(!session.team_b.claimed[0]);
+        assert_eq!(session.team_b.player_contributions[0], 500);
+        assert_eq!(session.team_a.total_bet, 0);
+        assert_eq!(session.team_b.total_bet, 500);
+        assert_eq!(session.expected_vault_balance().unwrap(), 500);
+    }
+
+    #[test]
+    fn test_total_collected_balances_distributed_one_vs_one() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        let (winner, loser) = (Pubkey::new_unique(), Pubkey::new_unique());
+        session.seat_player(0, 0, winner, 1000).unwrap();
+        session.seat_player(1, 0, loser, 1000).unwrap();
+        assert_eq!(session.total_collected, 200);
+        assert_eq!(session.total_distributed, 0);
+
+        session.start(1000).unwrap();
+        session.mark_resolved(Some(0)).unwrap();
+        for (player, amount) in session.winner_shares(0).unwrap() {
+            session.record_payout(player, amount).unwrap();
+        }
+
+        // The program takes no fee, so everything collected is paid back out
+        assert_eq!(session.total_distributed, session.total_collected);
+        assert_eq!(session.required_vault_balance().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_total_collected_drops_leaver_stake() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.record_contribution(0, 0, 30).unwrap();
+        session.team_a.total_bet += 30;
+        assert_eq!(session.total_collected, 130);
+
+        session.unseat_player(0, 0).unwrap();
+        assert_eq!(session.total_collected, 0);
+    }
+
+    #[test]
+    fn test_concurrent_spawn_cap_under_lifetime_cap() {
+        let mut session = filled_pay_to_spawn_session(0, 0);
+        session.status = GameStatus::WaitingForPlayers;
+        session.update_spawn_caps(15, 40).unwrap();
+        session.status = GameStatus::InProgress;
+        session.team_a.player_spawns[0] = 5;
+
+        // One bundle fits, a second would hold more lives than allowed at once
+        session.check_spawn_caps(0, 0, 10).unwrap();
+        session.add_spawns(0, 0).unwrap();
+        assert_eq!(session.team_a.player_spawns_bought[0], 10);
+        assert_eq!(
+            session.check_spawn_caps(0, 0, 10).unwrap_err(),
+            error!(WagerError::MaxSpawnsExceeded)
+        );
+
+        // Dying frees concurrent room until the lifetime cap is reached
+        for _ in 0..3 {
+            session.team_a.player_spawns[0] = 0;
+            session.check_spawn_caps(0, 0, 10).unwrap();
+            session.add_spawns(0, 0).unwrap();
+        }
+        assert_eq!(session.team_a.player_spawns_bought[0], 40);
+        session.team_a.player_spawns[0] = 0;
+        assert_eq!(
+            session.check_spawn_caps(0, 0, 10).unwrap_err(),
+            error!(WagerError::LifetimeSpawnsExceeded)
+        );
+        assert!(session.update_spawn_caps(20, 80).is_err());
+    }
+
+    #[test]
+    fn test_spawn_config_locked_after_start() {
+        let mut session = filled_pay_to_spawn_session(0, 0);
+        session.update_spawns_per_purchase(5).unwrap();
+        session.update_spawn_cost_scale(1_000).unwrap();
+
+        session.status = GameStatus::InProgress;
+        assert!(session.update_spawns_per_purchase(8).is_err());
+        assert!(session.update_spawn_cost_scale(2_000).is_err());
+        assert_eq!(session.spawns_per_purchase, 5);
+        assert_eq!(session.spawn_cost_scale_bps, 1_000);
+
+        session.status = GameStatus::Completed;
+        assert!(session.update_spawns_per_purchase(8).is_err());
+    }
+
+    #[test]
+    fn test_force_settle_refund_all_in_progress_one_vs_one() {
+        let mut session = GameSession::new(
+            "test".to_string(),
+            Pubkey::new_unique(),
+            1000,
+            GameMode::WinnerTakesAllOneVsOne,
+            1000,
+            1,
+            2,
+        ).unwrap();
+        let player_a = Pubkey::new_unique();
+        let player_b = Pubkey::new_unique();
+        session.seat_player(0, 0, player_a, 1000).unwrap();
+        session.seat_player(1, 0, player_b, 1000).unwrap();
+        session.status = GameStatus::InProgress;
+
+        let refunds = session.force_settle_payouts(ForceSettleResolution::RefundAll).unwrap();
+        assert_eq!(refunds, vec![(player_a, 1000), (player_b, 1000)]);
+
+        let award = session.force_settle_payouts(ForceSettleResolution::AwardTeamB).unwrap();
+        assert_eq!(award, vec![(player_b, 2000)]);
+
+        session.mark_force_settled(ForceSettleResolution::RefundAll).unwrap();
+        assert!(session.status == GameStatus::Distributed);
+        assert!(session.is_draw);
+        assert!(session.force_settle_payouts(ForceSettleResolution::RefundAll).is_err());
+        assert!(session.mark_force_settled(ForceSettleResolution::AwardTeamA).is_err());
+    }
+
+    #[test]
+    fn test_force_settle_award_pays_remainder() {
+        let mut session = empty_session(GameMode::WinnerTakesAllThreeVsThree);
+        let team_a: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        for (slot, player) in team_a.iter().enumerate() {
+            session.seat_player(0, slot, *player, 1000).unwrap();
+            session.seat_player(1, slot, Pubkey::new_unique(), 1000).unwrap();
+        }
+        session.bonus_pool = 2;
+        session.status = GameStatus::InProgress;
+
+        let award = session.force_settle_payouts(ForceSettleResolution::AwardTeamA).unwrap();
+        assert_eq!(
+            award,
+            vec![(team_a[0], 201), (team_a[1], 201), (team_a[2], 200)]
+        );
+        assert_eq!(
+            award.iter().map(|(_, amount)| amount).sum::<u64>(),
+            session.required_vault_balance().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_find_player_in_team_a() {
+        let mut session = empty_session(GameMode::WinnerTakesAllThreeVsThree);
+        let player = Pubkey::new_unique();
+        session.seat_player(0, 1, player, 1000).unwrap();
+
+        assert_eq!(session.find_player(&player), Some((0, 1)));
+        assert_eq!(session.get_player_team_and_index(player).unwrap(), (0, 1));
+        assert!(session.validate_player_not_joined(&player).is_err());
+    }
+
+    #[test]
+    fn test_find_player_in_team_b() {
+        let mut session = empty_session(GameMode::WinnerTakesAllThreeVsThree);
+        let player = Pubkey::new_unique();
+        session.seat_player(1, 2, player, 1000).unwrap();
+
+        assert_eq!(session.find_player(&player), Some((1, 2)));
+        assert_eq!(session.get_player_team_and_index(player).unwrap(), (1, 2));
+        assert!(session.validate_player_not_joined(&player).is_err());
+    }
+
+    #[test]
+    fn test_find_player_absent() {
+        let session = empty_session(GameMode::WinnerTakesAllThreeVsThree);
+        let player = Pubkey::new_unique();
+
+        assert_eq!(session.find_player(&player), None);
+        assert!(session.get_player_team_and_index(player).is_err());
+        assert!(session.validate_player_not_joined(&player).is_ok());
+
+        // Empty slots never match the default key
+        assert_eq!(session.find_player(&Pubkey::default()), None);
+    }
+
+    #[test]
+    fn test_health_flags_drained_vault() {
+        let mut session = filled_pay_to_spawn_session(0, 0);
+        session.team_a.total_bet = 100;
+        session.team_b.total_bet = 100;
+
+        let healthy = session.health(200, 1001).unwrap();
+        assert_eq!(
+            healthy,
+            SessionHealth {
+                solvent: true,
+                vault_drift: 0,
+                is_expired: false,
+                status: GameStatus::WaitingForPlayers as u8,
+                fully_settled: false,
+                headroom: 0,
+            }
+        );
+
+        // 30 tokens drained outside the program
+        let unhealthy = session.health(170, 1001).unwrap();
+        assert!(!unhealthy.solvent);
+        assert_eq!(unhealthy.vault_drift, -30);
+        assert_eq!(unhealthy.headroom, -30);
+        assert!(!unhealthy.is_expired);
+
+        // Paying everything out settles the session
+        session.add_total_distributed(200).unwrap();
+        session.status = GameStatus::Distributed;
+        let settled = session.health(0, session.expires_at).unwrap();
+        assert!(settled.solvent && settled.fully_settled && settled.is_expired);
+        assert_eq!(settled.vault_drift, 0);
+    }
+
+    #[test]
+    fn test_under_funded_vault_detected() {
+        let mut session = filled_pay_to_spawn_session(0, 0);
+        session.team_a.total_bet = 100;
+        session.team_b.total_bet = 100;
+        assert_eq!(session.required_vault_balance().unwrap(), 200);
+        assert!(session.assert_vault_covers_contributions(200).is_ok());
+        assert!(session.assert_vault_covers_contributions(250).is_ok());
+
+        // 10 tokens drained from the vault outside the program
+        assert!(session.assert_vault_covers_contributions(190).is_err());
+
+        // Recorded payouts lower what the vault must hold
+        session.add_total_distributed(60).unwrap();
+        assert!(session.assert_vault_covers_contributions(140).is_ok());
+        assert!(session.assert_vault_covers_contributions(139).is_err());
+    }
+
+    #[test]
+    fn test_draw_refund_completes_over_two_batches() {
+        let mut session = GameSession::new(
+            "test".to_string(),
+            Pubkey::new_unique(),
+            100,
+            GameMode::WinnerTakesAllFiveVsFive,
+            1000,
+            1,
+            2,
+        ).unwrap();
+        for team in 0..2u8 {
+            for slot in 0..MAX_PLAYERS_PER_TEAM {
+                session.seat_player(team, slot, Pubkey::new_unique(), 1000).unwrap();
+            }
+        }
+        session.status = GameStatus::InProgress;
+        session.mark_resolved(None).unwrap();
+        let players = session.get_all_players();
+
+        let first = session.get_pending_draw_refunds(MAX_REFUNDS_PER_BATCH).unwrap();
+        assert_eq!(first.len(), MAX_REFUNDS_PER_BATCH);
+        for (player, amount) in &first {
+            session.record_payout(*player, *amount).unwrap();
+            session.mark_refunded(*player).unwrap();
+        }
+
+        let second = session.get_pending_draw_refunds(MAX_REFUNDS_PER_BATCH).unwrap();
+        assert_eq!(second.len(), MAX_REFUNDS_PER_BATCH);
+        assert_eq!(second[0].0, players[MAX_REFUNDS_PER_BATCH]);
+        assert!(second.iter().all(|(_, amount)| *amount == 100));
+        for (player, amount) in &second {
+            session.record_payout(*player, *amount).unwrap();
+            session.mark_refunded(*player).unwrap();
+        }
+
+        assert!(session.get_pending_draw_refunds(MAX_REFUNDS_PER_BATCH).unwrap().is_empty());
+        assert!(session.mark_refunded(players[0]).is_err());
+    }
+
+    #[test]
+    fn test_three_vs_three_draw_refunds_all_six_players() {
+        let mut session = empty_session(GameMode::WinnerTakesAllThreeVsThree);
+        for team in 0..2u8 {
+            for slot in 0..3 {
+                session.seat_player(team, slot, Pubkey::new_unique(), 1000).unwrap();
+            }
+        }
+        assert!(session.settle_as_draw().is_err());
+
+        session.status = GameStatus::InProgress;
+        session.mark_resolved(None).unwrap();
+        let refunds = session.settle_as_draw().unwrap();
+
+        assert!(session.is_draw);
+        assert_eq!(session.winning_team, None);
+        assert_eq!(
+            refunds.iter().map(|(player, _)| *player).collect::<Vec<_>>(),
+            session.get_all_players()
+        );
+        assert!(refunds.iter().all(|(_, amount)| *amount == 100));
+        assert_eq!(refunds.iter().map(|(_, amount)| amount).sum::<u64>(), 600);
+    }
+
+    #[test]
+    fn test_total_pot_winner_takes_all() {
+        let mut session = GameSession::new(
+            "test".to_string(),
+            Pubkey::new_unique(),
+            1000,
+            GameMode::WinnerTakesAllThreeVsThree,
+            1000,
+            1,
+            2,
+        ).unwrap();
+        assert_eq!(session.total_pot().unwrap(), 6000);
+
+        session.team_a_bet = u64::MAX / 2;
+        assert!(session.total_pot().is_err());
+    }
+
+    #[test]
+    fn test_total_pot_pay_to_spawn_sums_team_bets() {
+        let mut session = filled_pay_to_spawn_session(0, 0);
+        session.team_a.total_bet = 300;
+        session.team_b.total_bet = 500;
+        assert_eq!(session.total_pot().unwrap(), 800);
+
+        session.team_a.total_bet = u64::MAX;
+        assert!(session.total_pot().is_err());
+    }
+
+    fn best_of_three() -> GameSeries {
+        GameSeries {
+            series_id: "series".to_string(),
+            authority: Pubkey::new_unique(),
+            rounds_needed: 2,
+            team_a_wins: 0,
+            team_b_wins: 0,
+            rounds_played: 0,
+            current_session: Pubkey::new_unique(),
+            winner: None,
+            finalized: false,
+            bump: 1,
+            vault_bump: 2,
+            team_a_players: [Pubkey::default(); MAX_PLAYERS_PER_TEAM],
+            team_b_players: [Pubkey::default(); MAX_PLAYERS_PER_TEAM],
+        }
+    }
+
+    #[test]
+    fn test_best_of_three_team_a_wins_rounds_one_and_three() {
+        let mut series = best_of_three();
+
+        series.record_round(Some(0)).unwrap();
+        assert_eq!(series.winner, None);
+        series.record_round(Some(1)).unwrap();
+        assert_eq!(series.winner, None);
+        series.record_round(Some(0)).unwrap();
+
+        assert_eq!(series.winner, Some(0));
+        assert_eq!((series.team_a_wins, series.team_b_wins, series.rounds_played), (2, 1, 3));
+        assert!(series.record_round(Some(1)).is_err());
+    }
+
+    #[test]
+    fn test_series_draw_round_counts_as_played_only() {
+        let mut series = best_of_three();
+
+        series.record_round(None).unwrap();
+        assert_eq!((series.team_a_wins, series.team_b_wins, series.rounds_played), (0, 0, 1));
+        assert!(series.record_round(Some(2)).is_err());
+    }
+
+    #[test]
+    fn test_series_pot_split_keeps_no_dust() {
+        assert_eq!(GameSeries::split_pot(600, 3).unwrap(), vec![200, 200, 200]);
+        assert_eq!(GameSeries::split_pot(601, 3).unwrap(), vec![201, 200, 200]);
+        assert!(GameSeries::split_pot(600, 0).is_err());
+    }
+
+    #[test]
+    fn test_next_series_round_must_match() {
+        let first = empty_session(GameMode::WinnerTakesAllThreeVsThree);
+        let mut next = empty_session(GameMode::WinnerTakesAllThreeVsThree);
+        next.authority = first.authority;
+        next.token_mint = first.token_mint;
+        assert!(first.validate_next_series_round(&next).is_ok());
+
+        next.session_bet = first.session_bet + 1;
+        assert!(first.validate_next_series_round(&next).is_err());
+        next.session_bet = first.session_bet;
+
+        next.series = Pubkey::new_unique();
+        assert!(first.validate_next_series_round(&next).is_err());
+    }
+
+    #[test]
+    fn test_series_rounds_keep_round_one_rosters() {
+        let mut series = best_of_three();
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut first = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        first.seat_player(0, 0, a, 1000).unwrap();
+        first.seat_player(1, 0, b, 1000).unwrap();
+        series.check_round_roster(&first).unwrap();
+        series.record_round(Some(0)).unwrap();
+
+        let mut same = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        same.seat_player(0, 0, a, 1000).unwrap();
+        same.seat_player(1, 0, b, 1000).unwrap();
+        series.check_round_roster(&same).unwrap();
+
+        // Swapped sides, or a substitute, is a different series
+        let mut swapped = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        swapped.seat_player(0, 0, b, 1000).unwrap();
+        swapped.seat_player(1, 0, a, 1000).unwrap();
+        assert_eq!(
+            series.check_round_roster(&swapped).unwrap_err(),
+            error!(WagerError::SeriesRoundMismatch)
+        );
+        let mut substitute = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        substitute.seat_player(0, 0, a, 1000).unwrap();
+        substitute.seat_player(1, 0, Pubkey::new_unique(), 1000).unwrap();
+        assert!(series.check_round_roster(&substitute).is_err());
+    }
+
+    #[test]
+    fn test_undecided_series_refunds_by_team_bet() {
+        let mut series = best_of_three();
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        series.team_a_players[MAX_PLAYERS_PER_TEAM - 1] = a;
+        series.team_b_players[MAX_PLAYERS_PER_TEAM - 1] = b;
+
+        assert_eq!(series.refund_shares(100, 100, 400).unwrap(), vec![(a, 200), (b, 200)]);
+        assert_eq!(series.refund_shares(100, 200, 601).unwrap(), vec![(a, 201), (b, 400)]);
+        assert!(series.refund_shares(100, 100, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_expected_vault_balance_matches_deposits() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.seat_player(1, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.add_bonus(50).unwrap();
+
+        assert_eq!(session.expected_vault_balance().unwrap(), 250);
+
+        // Part way through a resumed payout the ledger still balances
+        session.total_distributed = 200;
+        assert_eq!(session.expected_vault_balance().unwrap(), 50);
+    }
+
+    #[test]
+    fn test_vault_shortfall_blocks_distribution() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.seat_player(1, 0, Pubkey::new_unique(), 1000).unwrap();
+
+        assert_eq!(
+            session.assert_vault_covers_contributions(199).unwrap_err(),
+            error!(WagerError::InsufficientVaultBalance)
+        );
+    }
+
+    #[test]
+    fn test_vault_donation_does_not_block_distribution() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        let winner = Pubkey::new_unique();
+        session.seat_player(0, 0, winner, 1000).unwrap();
+        session.seat_player(1, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.start(1000).unwrap();
+        session.mark_resolved(Some(0)).unwrap();
+
+        // Someone sent one extra token straight to the vault
+        let mut vault_balance = 201;
+        session.assert_vault_covers_contributions(vault_balance).unwrap();
+
+        let shares = session.winner_shares(0).unwrap();
+        assert_eq!(shares, vec![(winner, 200)]);
+        for (player, amount) in shares {
+            vault_balance -= amount;
+            session.record_payout(player, amount).unwrap();
+        }
+        session.status = GameStatus::Distributed;
+
+        // The donation is left for sweep_dust
+        assert_eq!(session.sweepable_dust(vault_balance).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_bonus_pool_increases_winner_payout() {
+        let mut session = empty_session(GameMode::WinnerTakesAllThreeVsThree);
+        for slot in 0..3 {
+            session.seat_player(0, slot, Pubkey::new_unique(), 1000).unwrap();
+            session.seat_player(1, slot, Pubkey::new_unique(), 1000).unwrap();
+        }
+        assert_eq!(session.winner_payout(3).unwrap(), 200);
+
+        session.add_bonus(300).unwrap();
+        assert_eq!(session.bonus_pool, 300);
+        assert_eq!(session.winner_payout(3).unwrap(), 300);
+        assert_eq!(session.winner_payout(2).unwrap(), 450);
+        assert_eq!(session.total_pot().unwrap(), 900);
+        assert!(session.winner_payout(0).is_err());
+    }
+
+    #[test]
+    fn test_winner_shares_hand_out_the_remainder_by_slot() {
+        let mut session = empty_session(GameMode::WinnerTakesAllThreeVsThree);
+        let winners = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        for (slot, winner) in winners.iter().enumerate() {
+            session.seat_player(0, slot, *winner, 1000).unwrap();
+        }
+        // 300 in bets plus a 700 bonus leaves a 1000 token pot for three winners
+        session.add_bonus(700).unwrap();
+        assert_eq!(session.winner_pot().unwrap(), 1000);
+
+        let shares = session.winner_shares(0).unwrap();
+        assert_eq!(
+            shares,
+            vec![(winners[0], 334), (winners[1], 333), (winners[2], 333)]
+        );
+        assert_eq!(shares.iter().map(|(_, share)| share).sum::<u64>(), 1000);
+
+        // A remainder of two goes to the first two slots
+        session.add_bonus(1).unwrap();
+        let shares: Vec<u64> = session.winner_shares(0).unwrap().into_iter().map(|(_, s)| s).collect();
+        assert_eq!(shares, vec![334, 334, 333]);
+
+        assert!(session.winner_shares(1).is_err());
+    }
+
+    #[test]
+    fn test_handicap_winner_split_matches_collected_total() {
+        let mut session = empty_session(GameMode::WinnerTakesAllThreeVsThree);
+        session.set_team_bets(2000, 1000).unwrap();
+        for slot in 0..3 {
+            session.seat_player(0, slot, Pubkey::new_unique(), 1000).unwrap();
+            session.seat_player(1, slot, Pubkey::new_unique(), 1000).unwrap();
+        }
+
+        assert_eq!(session.team_a.total_bet, 6000);
+        assert_eq!(session.team_b.total_bet, 3000);
+        assert_eq!(session.team_a.player_contributions[0], 2000);
+        assert_eq!(session.team_b.player_contributions[0], 1000);
+        assert_eq!(session.total_pot().unwrap(), 9000);
+
+        // Whichever side wins, its three players split all 9000 collected
+        let payout = session.winner_payout(3).unwrap();
+        assert_eq!(payout, 3000);
+        assert_eq!(payout * 3, session.team_a.total_bet + session.team_b.total_bet);
+        assert_eq!(session.leave_refund_split(0, 0).unwrap(), (2000, 0));
+        assert_eq!(session.leave_refund_split(1, 0).unwrap(), (1000, 0));
+    }
+
+    #[test]
+    fn test_team_bets_locked_once_joined() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        assert!(session.set_team_bets(2000, 1000).is_err());
+        assert!(empty_session(GameMode::PayToSpawnOneVsOne).set_team_bets(2000, 1000).is_err());
+    }
+
+    #[test]
+    fn test_bonus_only_while_open_or_in_progress() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        session.status = GameStatus::InProgress;
+        session.add_bonus(50).unwrap();
+
+        session.status = GameStatus::Completed;
+        assert!(session.add_bonus(50).is_err());
+        assert!(empty_session(GameMode::PayToSpawnOneVsOne).add_bonus(50).is_err());
+        assert_eq!(session.required_vault_balance().unwrap(), 50);
+    }
+
+    #[test]
+    fn test_match_seed_derived_when_not_provided() {
+        let authority = Pubkey::new_unique();
+        let session = GameSession::new(
+            "test".to_string(),
+            authority,
+            100,
+            GameMode::WinnerTakesAllOneVsOne,
+            1000,
+            1,
+            2,
+        ).unwrap();
+
+        assert_eq!(session.match_seed, GameSession::derive_match_seed(&authority, 1000));
+        assert_ne!(session.match_seed, GameSession::derive_match_seed(&authority, 1001));
+        assert_ne!(
+            session.match_seed,
+            GameSession::derive_match_seed(&Pubkey::new_unique(), 1000)
+        );
+
+        // The seed survives a serialization round trip
+        let bytes = session.try_to_vec().unwrap();
+        let restored = GameSession::try_from_slice(&bytes).unwrap();
+        assert_eq!(restored.match_seed, session.match_seed);
+    }
+
+    #[test]
+    fn test_session_snapshot_round_trip() {
+        let mut session = filled_pay_to_spawn_session(3, 1);
+        session.session_id = "a".repeat(MAX_SESSION_ID_LENGTH);
+        session.status = GameStatus::InProgress;
+        session.team_a.total_bet = 100;
+        session.winning_team = Some(0);
+
+        let snapshot = session.snapshot();
+        let bytes = snapshot.try_to_vec().unwrap();
+        assert!(bytes.len() <= 1024);
+
+        let restored = SessionSnapshot::try_from_slice(&bytes).unwrap();
+        assert!(restored == snapshot);
+        assert!(restored.status == GameStatus::InProgress);
+        assert_eq!(restored.team_a.players[0], session.team_a.players[0]);
+        assert_eq!(restored.team_a.player_kills[0], 3);
+        assert_eq!(restored.team_b.player_kills[0], 1);
+        assert_eq!(restored.team_a.total_bet, 100);
+        assert_eq!(restored.expires_at, session.expires_at);
+        assert_eq!(restored.winning_team, Some(0));
+    }
+
+    #[test]
+    fn test_metadata_round_trip() {
+        let mut session = filled_pay_to_spawn_session(0, 0);
+        assert_eq!(session.metadata, [0; 32]);
+
+        let mut metadata = [0u8; 32];
+        metadata[..14].copy_from_slice(b"bracket-7/semi");
+        metadata[31] = 0xff;
+        session.metadata = metadata;
+
+        let bytes = session.try_to_vec().unwrap();
+        let restored = GameSession::try_from_slice(&bytes).unwrap();
+        assert_eq!(restored.metadata, metadata);
+    }
+
+    #[test]
+    fn test_team_roster_lists_only_seated_players() {
+        let mut session = GameSession::new(
+            "test".to_string(),
+            Pubkey::new_unique(),
+            100,
+            GameMode::WinnerTakesAllThreeVsThree,
+            1000,
+            1,
+            2,
+        ).unwrap();
+        let first = Pubkey::new_unique();
+        let second = Pubkey::new_unique();
+        session.seat_player(0, 0, first, 1000).unwrap();
+        session.seat_player(0, 2, second, 1000).unwrap();
+        session.team_a.player_kills[2] = 4;
+
+        let roster = session.get_team_roster(0).unwrap();
+        assert_eq!(roster.len(), 2);
+        assert_eq!(roster[0], (first, session.team_a.player_spawns[0], 0));
+        assert_eq!(roster[1], (second, session.team_a.player_spawns[2], 4));
+
+        assert!(session.get_team_roster(1).unwrap().is_empty());
+        assert!(session.get_team_roster(2).is_err());
+    }
+
+    #[test]
+    fn test_allowed_mints_whitelist() {
+        let allowed = Pubkey::new_unique();
+        let disallowed = Pubkey::new_unique();
+        let mut config = GlobalConfig {
+            admin: Pubkey::new_unique(),
+            allowed_mints: Vec::new(),
+            creation_paused: false,
+            bump: 255,
+        };
+
+        // An empty list allows every mint
+        assert!(config.is_mint_allowed(&disallowed));
+
+        config.add_allowed_mint(allowed).unwrap();
+        assert!(config.is_mint_allowed(&allowed));
+        assert!(!config.is_mint_allowed(&disallowed));
+        assert!(config.add_allowed_mint(allowed).is_err());
+
+        config.remove_allowed_mint(allowed).unwrap();
+        assert!(config.is_mint_allowed(&disallowed));
+        assert!(config.remove_allowed_mint(allowed).is_err());
+    }
+
+    #[test]
+    fn test_leave_penalty_refunds_ninety_percent() {
+        let mut session = empty_session(GameMode::PayToSpawnOneVsOne);
+        assert!(session.update_leave_penalty(MAX_LEAVE_PENALTY_BPS).is_ok());
+        assert!(session.update_leave_penalty(MAX_LEAVE_PENALTY_BPS + 1).is_err());
+
+        session.update_leave_penalty(1_000).unwrap();
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        assert_eq!(session.leave_refund_split(0, 0).unwrap(), (90, 10));
+    }
+
+    #[test]
+    fn test_leave_penalty_locked_after_join() {
+        let mut session = empty_session(GameMode::PayToSpawnOneVsOne);
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+
+        // The player staked under no penalty, which cannot be raised on them
+        assert_eq!(
+            session.update_leave_penalty(5_000).unwrap_err(),
+            error!(WagerError::ConfigLockedError)
+        );
+        assert_eq!(session.leave_refund_split(0, 0).unwrap(), (100, 0));
+    }
+
+    #[test]
+    fn test_authority_stats_average_bet_over_three_sessions() {
+        let mut stats = AuthorityStats {
+            authority: Pubkey::new_unique(),
+            total_bet_volume: 0,
+            sessions_created: 0,
+            bump: 255,
+        };
+        assert_eq!(stats.average_bet(), 0);
+
+        for bet in [10_000u64, 20_000, 60_000] {
+            stats.record_session(bet).unwrap();
+        }
+
+        assert_eq!(stats.sessions_created, 3);
+        assert_eq!(stats.total_bet_volume, 90_000);
+        assert_eq!(stats.average_bet(), 30_000);
+    }
+
+    #[test]
+    fn test_session_index_tracks_active_sessions() {
+        let mut index = AuthoritySessionIndex {
+            authority: Pubkey::new_unique(),
+            sessions: Vec::new(),
+            bump: 255,
+        };
+        let first = Pubkey::new_unique();
+        let second = Pubkey::new_unique();
+
+        index.add_session(first).unwrap();
+        index.add_session(second).unwrap();
+        assert!(index.add_session(first).is_err());
+
+        index.remove_session(first);
+        assert_eq!(index.sessions, vec![second]);
+
+        // Removing an unlisted session is a no-op
+        index.remove_session(first);
+        assert_eq!(index.sessions.len(), 1);
+    }
+
+    #[test]
+    fn test_session_quota_follows_creations_and_closures() {
+        let mut index = AuthoritySessionIndex {
+            authority: Pubkey::new_unique(),
+            sessions: Vec::new(),
+            bump: 255,
+        };
+        assert_eq!(index.quota().active_sessions, 0);
+        assert_eq!(index.quota().max_sessions, MAX_SESSIONS_PER_AUTHORITY as u32);
+
+        let sessions: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        for session in &sessions {
+            index.add_session(*session).unwrap();
+        }
+        assert_eq!(index.quota().active_sessions, 3);
+
+        index.remove_session(sessions[1]);
+        assert_eq!(index.quota().active_sessions, 2);
+        index.remove_session(sessions[1]);
+        assert_eq!(index.quota().active_sessions, 2);
+    }
+
+    #[test]
+    fn test_session_index_capacity() {
+        let mut index = AuthoritySessionIndex {
+            authority: Pubkey::new_unique(),
+            sessions: Vec::new(),
+            bump: 255,
+        };
+        for _ in 0..MAX_SESSIONS_PER_AUTHORITY {
+            index.add_session(Pubkey::new_unique()).unwrap();
+        }
+        assert!(index.add_session(Pubkey::new_unique()).is_err());
+    }
+
+    #[test]
+    fn test_authority_liveness_deadline_opens_emergency_refunds() {
+        let mut session = filled_pay_to_spawn_session(0, 0);
+        session.team_a.player_contributions[0] = 100;
+        session.team_b.player_contributions[0] = 300;
+        session.team_b.player_payouts[0] = 50;
+
+        session.update_liveness_window(600).unwrap();
+        assert_eq!(session.authority_liveness_deadline(), 1600);
+        assert!(!session.is_authority_silent(1600));
+        assert!(session.is_authority_silent(1601));
+
+        // A heartbeat pushes the deadline out again
+        session.record_heartbeat(1500).unwrap();
+        assert!(!session.is_authority_silent(1601));
+        assert!(session.is_authority_silent(2101));
+
+        let outstanding = session.get_outstanding_contributions().unwrap();
+        assert_eq!(
+            outstanding,
+            vec![(session.team_a.players[0], 100), (session.team_b.players[0], 250)]
+        );
+
+        assert!(session.update_liveness_window(0).is_err());
+        assert!(session.update_liveness_window(MAX_LIVENESS_WINDOW_SECONDS + 1).is_err());
+    }
+
+    #[test]
+    fn test_is_joinable_open_session() {
         let session = GameSession::new(
             "test".to_string(),
             Pubkey::new_unique(),
             100,
             GameMode::WinnerTakesAllOneVsOne,
-            current_time,
+            1000,
             1,
             2,
-            3,
         ).unwrap();
 
-        assert!(!session.is_expired(current_time));
-        assert!(session.is_expired(current_time + SESSION_TIMEOUT_SECONDS + 1));
+        assert!(session.is_joinable(1000));
+        assert!(session.get_player_empty_slot(0, 1000).is_ok());
+    }
+
+    #[test]
+    fn test_is_joinable_rejects_expired_session() {
+        let session = GameSession::new(
+            "test".to_string(),
+            Pubkey::new_unique(),
+            100,
+            GameMode::WinnerTakesAllOneVsOne,
+            1000,
+            1,
+            2,
+        ).unwrap();
+        let expired_at = 1000 + SESSION_TIMEOUT_SECONDS;
+
+        assert!(!session.is_joinable(expired_at));
+        assert!(session.get_player_empty_slot(0, expired_at).is_err());
+    }
+
+    #[test]
+    fn test_is_joinable_rejects_full_session() {
+        let session = filled_pay_to_spawn_session(0, 0);
+
+        // Still waiting for players but both teams are full
+        assert!(session.status == GameStatus::WaitingForPlayers);
+        assert!(!session.is_joinable(1000));
+    }
+
+    #[test]
+    fn test_is_joinable_rejects_in_progress_session() {
+        let mut session = filled_pay_to_spawn_session(0, 0);
+        session.team_b.players[0] = Pubkey::default();
+        assert!(session.is_joinable(1000));
+
+        session.status = GameStatus::InProgress;
+        assert!(!session.is_joinable(1000));
+        assert!(session.get_player_empty_slot(1, 1000).is_err());
+    }
+
+    #[test]
+    fn test_kill_tie_resolves_as_draw() {
+        let mut session = expired_kill_tie_session(KillTieBreaker::Draw);
+        let winning_team = session.resolve_expired_by_kills(1000 + SESSION_TIMEOUT_SECONDS);
+        assert_eq!(winning_team, None);
+
+        session.mark_resolved(winning_team).unwrap();
+        assert!(session.is_draw);
+        assert!(session.status == GameStatus::AwaitingDistribution);
+
+        let mut decided = filled_pay_to_spawn_session(3, 5);
+        decided.status = GameStatus::InProgress;
+        decided.mark_resolved(decided.resolve_by_kills()).unwrap();
+        assert!(!decided.is_draw);
+    }
+
+    #[test]
+    fn test_round_payout_to_whole_tokens() {
+        let mut session = filled_pay_to_spawn_session(0, 0);
+        session.token_decimals = 6;
+
+        // Rounding disabled pays the exact amount
+        assert_eq!(session.round_payout(1_500_123).unwrap(), (1_500_123, 0));
+
+        // One whole token of a 6-decimal mint is 1_000_000 base units
+        session.round_to_whole_tokens = true;
+        assert_eq!(session.round_payout(1_500_123).unwrap(), (1_000_000, 500_123));
+
+        let (payout, remainder) = session.round_payout(3_000_123).unwrap();
+        assert_eq!(payout, 3_000_000);
+        assert_eq!(remainder, 123);
+
+        session.add_rounding_remainder(remainder).unwrap();
+        assert_eq!(session.rounding_remainder, 123);
+    }
+
+    #[test]
+    fn test_round_to_whole_tokens_locked_at_start() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        session.set_round_to_whole_tokens(true).unwrap();
+
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.seat_player(1, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.start(1000).unwrap();
+        assert_eq!(
+            session.set_round_to_whole_tokens(false).unwrap_err(),
+            error!(WagerError::ConfigLockedError)
+        );
+        assert!(session.round_to_whole_tokens);
+    }
+
+    fn free_for_all_session(kills: &[u16]) -> GameSession {
+        let mut session = GameSession::new(
+            "test".to_string(),
+            Pubkey::new_unique(),
+            100,
+            GameMode::FreeForAll,
+            1000,
+            1,
+            2,
+        ).unwrap();
+
+        // Seat players across both slot banks
+        for (i, &player_kills) in kills.iter().enumerate() {
+            let team = (i % 2) as u8;
+            session
+                .seat_player(team, i / 2, Pubkey::new_unique(), 1000)
+                .unwrap();
+            let bank = if team == 0 { &mut session.team_a } else { &mut session.team_b };
+            bank.player_kills[i / 2] = player_kills;
+        }
+        session
+    }
+
+    #[test]
+    fn test_free_for_all_most_kills_wins() {
+        let mut session = free_for_all_session(&[1, 4, 2, 0]);
+        assert_eq!(session.game_mode.players_per_team(), MAX_PLAYERS_PER_TEAM);
+        assert_eq!(session.get_all_players().len(), 4);
+
+        // Four of ten seats is enough to start a free-for-all
+        assert!(!session.check_all_filled().unwrap());
+        session.start(1000).unwrap();
+
+        // The player seated second (team B, slot 0) has the most kills
+        let top_killer = session.team_b.players[0];
+        assert_eq!(session.free_for_all_winner(), Some(top_killer));
+
+        session.mark_free_for_all_resolved().unwrap();
+        assert!(!session.is_draw);
+        assert!(session.status == GameStatus::AwaitingDistribution);
+    }
+
+    #[test]
+    fn test_free_for_all_tied_top_score_is_a_draw() {
+        let mut session = free_for_all_session(&[3, 1, 3, 0]);
+        session.start(1000).unwrap();
+
+        assert_eq!(session.free_for_all_winner(), None);
+        session.mark_free_for_all_resolved().unwrap();
+        assert!(session.is_draw);
+    }
+
+    #[test]
+    fn test_second_distribution_rejected_while_flagged() {
+        let mut session = filled_pay_to_spawn_session(0, 0);
+
+        session.begin_distribution().unwrap();
+        assert_eq!(
+            session.begin_distribution().unwrap_err(),
+            error!(WagerError::ConcurrentModificationDetected)
+        );
+
+        session.end_distribution();
+        assert!(session.begin_distribution().is_ok());
+    }
+
+    #[test]
+    fn test_resumed_distribution_pays_only_unclaimed_winners() {
+        let mut session = GameSession::new(
+            "test".to_string(),
+            Pubkey::new_unique(),
+            100,
+            GameMode::WinnerTakesAllThreeVsThree,
+            1000,
+            1,
+            2,
+        ).unwrap();
+        let winners: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        for (slot, winner) in winners.iter().enumerate() {
+            session.seat_player(0, slot, *winner, 1000).unwrap();
+        }
+        assert_eq!(session.get_unclaimed_winners(0).unwrap(), winners);
+
+        // First run paid the first winner before failing on the rest
+        session.mark_claimed(winners[0]).unwrap();
+        assert_eq!(session.get_unclaimed_winners(0).unwrap(), winners[1..].to_vec());
+        assert!(session.mark_claimed(winners[0]).is_err());
+
+        // The resumed run pays the remaining two
+        session.mark_claimed(winners[1]).unwrap();
+        session.mark_claimed(winners[2]).unwrap();
+        assert!(session.get_unclaimed_winners(0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_empty_winning_team_refunds_contributors() {
+        let mut session = GameSession::new(
+            "test".to_string(),
+            Pubkey::new_unique(),
+            100,
+            GameMode::WinnerTakesAllOneVsOne,
+            1000,
+            1,
+            2,
+        ).unwrap();
+        assert!(session.empty_winner_policy == EmptyWinnerPolicy::RefundContributors);
+
+        // Team A left, only team B's stake and later purchase remain in the vault
+        let remaining_player = Pubkey::new_unique();
+        session.seat_player(1, 0, remaining_player, 1000).unwrap();
+        session.record_contribution(1, 0, 30).unwrap();
+
+        assert!(session.get_active_team_players(0).unwrap().is_empty());
+        assert_eq!(
+            session.get_outstanding_contributions().unwrap(),
+            vec![(remaining_player, 130)]
+        );
+    }
+
+    #[test]
+    fn test_empty_winner_policy_locked_once_anyone_joins() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        session
+            .update_empty_winner_policy(EmptyWinnerPolicy::SendToAuthority)
+            .unwrap();
+        assert!(session.empty_winner_policy == EmptyWinnerPolicy::SendToAuthority);
+
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        assert_eq!(
+            session
+                .update_empty_winner_policy(EmptyWinnerPolicy::RefundContributors)
+                .unwrap_err(),
+            error!(WagerError::ConfigLockedError)
+        );
+
+        // Nor mid-game, once a team could have emptied
+        session.seat_player(1, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.start(1000).unwrap();
+        assert!(session
+            .update_empty_winner_policy(EmptyWinnerPolicy::RefundContributors)
+            .is_err());
+        assert!(session.empty_winner_policy == EmptyWinnerPolicy::SendToAuthority);
+    }
+
+    #[test]
+    fn test_payout_must_match_recorded_winner() {
+        let mut session = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        session.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.seat_player(1, 0, Pubkey::new_unique(), 1000).unwrap();
+        session.start(1000).unwrap();
+        session.mark_resolved(Some(1)).unwrap();
+
+        session.check_recorded_winner(1).unwrap();
+        for wrong in [0, DRAW_TEAM] {
+            assert_eq!(
+                session.check_recorded_winner(wrong).unwrap_err(),
+                error!(WagerError::InvalidWinningTeam)
+            );
+        }
+        // Nor can a decided game be re-settled as a draw
+        assert_eq!(
+            session.settle_as_draw().unwrap_err(),
+            error!(WagerError::InvalidWinningTeam)
+        );
+        assert_eq!(session.winning_team, Some(1));
+
+        let mut drawn = empty_session(GameMode::WinnerTakesAllOneVsOne);
+        drawn.seat_player(0, 0, Pubkey::new_unique(), 1000).unwrap();
+        drawn.seat_player(1, 0, Pubkey::new_unique(), 1000).unwrap();
+        drawn.start(1000).unwrap();
+        drawn.mark_resolved(None).unwrap();
+        drawn.check_recorded_winner(DRAW_TEAM).unwrap();
+        assert!(drawn.check_recorded_winner(0).is_err());
     }
 }
\ No newline at end of file
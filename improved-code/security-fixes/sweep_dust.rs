@@ -0,0 +1,85 @@
+use crate::{
+    errors::WagerError,
+    events::*,
+    instructions::{vault_transfer, verify_vault_drain},
+    state::*,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Token, TokenAccount};
+
+/// Moves rounding dust left in a settled session's vault to the authority's
+/// treasury token account so the vault can be closed (authority only)
+pub fn sweep_dust_handler(ctx: Context<SweepDust>, session_id: String) -> Result<()> {
+    let game_session = &ctx.accounts.game_session;
+    let vault_balance_before = ctx.accounts.vault_token_account.amount;
+    let dust = game_session.sweepable_dust(vault_balance_before)?;
+
+    vault_transfer(
+        &ctx.accounts.token_program,
+        &ctx.accounts.vault_token_account,
+        &ctx.accounts.vault,
+        &session_id,
+        game_session.vault_bump,
+        &ctx.accounts.treasury_token_account.to_account_info(),
+        dust,
+    )?;
+
+    ctx.accounts.vault_token_account.reload()?;
+    verify_vault_drain(vault_balance_before, ctx.accounts.vault_token_account.amount, dust)?;
+
+    emit!(TokenMoved {
+        session_id: session_id.clone(),
+        from: ctx.accounts.vault_token_account.key(),
+        to: ctx.accounts.treasury_token_account.key(),
+        amount: dust,
+        reason: TokenMoveReason::Sweep as u8,
+    });
+
+    let game_session = &mut ctx.accounts.game_session;
+    game_session.add_total_distributed(dust)?;
+    game_session.rounding_remainder = 0;
+
+    msg!("Swept {} dust tokens from session {} to treasury", dust, session_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct SweepDust<'info> {
+    #[account(
+        constraint = authority.key() == game_session.authority @ WagerError::UnauthorizedAction,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump = game_session.bump,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: Vault PDA that holds the funds
+    #[account(
+        seeds = [b"vault", session_id.as_bytes()],
+        bump = game_session.vault_bump,
+    )]
+    pub vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = game_session.token_mint,
+        associated_token::authority = vault,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = treasury_token_account.owner == authority.key() @ WagerError::InvalidTokenAccountOwner,
+        constraint = treasury_token_account.mint == game_session.token_mint @ WagerError::InvalidTokenMint,
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+/// Signer seeds of a session's vault PDA: ["vault", session_id, bump]
+pub fn vault_seeds<'a>(session_id: &'a str, vault_bump: &'a [u8; 1]) -> [&'a [u8]; 3] {
+    [b"vault", session_id.as_bytes(), vault_bump]
+}
+
+/// Transfers `amount` out of a session's vault token account to `to`, signing
+/// as the vault PDA
+pub fn vault_transfer<'info>(
+    token_program: &Program<'info, Token>,
+    vault_token_account: &Account<'info, TokenAccount>,
+    vault: &AccountInfo<'info>,
+    session_id: &str,
+    vault_bump: u8,
+    to: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let bump = [vault_bump];
+    anchor_spl::token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: vault_token_account.to_account_info(),
+                to: to.clone(),
+                authority: vault.clone(),
+            },
+            &[&vault_seeds(session_id, &bump)],
+        ),
+        amount,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vault_seeds_derive_vault_pda() {
+        let program_id = Pubkey::new_unique();
+        let session_id = "session_42";
+        let (vault, bump) =
+            Pubkey::find_program_address(&[b"vault", session_id.as_bytes()], &program_id);
+
+        let bump_seed = [bump];
+        let seeds = vault_seeds(session_id, &bump_seed);
+        assert_eq!(seeds[0], b"vault");
+        assert_eq!(seeds[1], session_id.as_bytes());
+        assert_eq!(Pubkey::create_program_address(&seeds, &program_id).unwrap(), vault);
+
+        // Another session's id signs for a different vault
+        let other = vault_seeds("session_43", &bump_seed);
+        assert_ne!(Pubkey::create_program_address(&other, &program_id).ok(), Some(vault));
+    }
+}
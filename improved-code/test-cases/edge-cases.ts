@@ -79,7 +79,7 @@ describe("Edge Cases Security Tests", () => {
 
         // Create game session
         await program.methods
-          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { payToSpawnOneVsOne: {} })
+          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { payToSpawnOneVsOne: {} }, null, null, null)
           .accounts({
             gameServer: gameServer.publicKey,
           })
@@ -102,7 +102,7 @@ describe("Edge Cases Security Tests", () => {
         // Attempt to record another kill (should fail)
         try {
           await program.methods
-            .addKill(sessionId, 0, player2.publicKey, 0, player1.publicKey)
+            .addKill(sessionId, 0, player2.publicKey, 0, player1.publicKey, null)
             .accounts({
               gameServer: gameServer.publicKey,
             })
@@ -120,7 +120,7 @@ describe("Edge Cases Security Tests", () => {
         
         // Create game and join players
         await program.methods
-          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { payToSpawnOneVsOne: {} })
+          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { payToSpawnOneVsOne: {} }, null, null, null)
           .accounts({
             gameServer: gameServer.publicKey,
           })
@@ -165,7 +165,7 @@ describe("Edge Cases Security Tests", () => {
         
         // Create game session with high bet amount
         await program.methods
-          .createGameSession(sessionId, new anchor.BN(1000000), { payToSpawnOneVsOne: {} })
+          .createGameSession(sessionId, new anchor.BN(1000000), { payToSpawnOneVsOne: {} }, null, null, null)
           .accounts({
             gameServer: gameServer.publicKey,
           })
@@ -221,7 +221,7 @@ describe("Edge Cases Security Tests", () => {
         const sessionId = "calc_test";
         
         await program.methods
-          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { payToSpawnOneVsOne: {} })
+          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { payToSpawnOneVsOne: {} }, null, null, null)
           .accounts({
             gameServer: gameServer.publicKey,
           })
@@ -247,7 +247,7 @@ describe("Edge Cases Security Tests", () => {
         const sessionId = "race_condition_test";
         
         await program.methods
-          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} })
+          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} }, null, null, null)
           .accounts({
             gameServer: gameServer.publicKey,
           })
@@ -289,7 +289,7 @@ describe("Edge Cases Security Tests", () => {
         const sessionId = "atomic_test";
         
         await program.methods
-          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} })
+          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} }, null, null, null)
           .accounts({
             gameServer: gameServer.publicKey,
           })
@@ -346,7 +346,7 @@ describe("Edge Cases Security Tests", () => {
         const sessionId = "auth_test";
         
         await program.methods
-          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { payToSpawnOneVsOne: {} })
+          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { payToSpawnOneVsOne: {} }, null, null, null)
           .accounts({
             gameServer: gameServer.publicKey,
           })
@@ -394,7 +394,7 @@ describe("Edge Cases Security Tests", () => {
         
         // Create session with gameServer authority
         await program.methods
-          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { payToSpawnOneVsOne: {} })
+          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { payToSpawnOneVsOne: {} }, null, null, null)
           .accounts({
             gameServer: gameServer.publicKey,
           })
@@ -418,7 +418,7 @@ describe("Edge Cases Security Tests", () => {
         const sessionId = "duplicate_test";
         
         await program.methods
-          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllThreeVsThree: {} })
+          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllThreeVsThree: {} }, null, null, null)
           .accounts({
             gameServer: gameServer.publicKey,
           })
@@ -456,7 +456,7 @@ describe("Edge Cases Security Tests", () => {
         const sessionId = "same_team_test";
         
         await program.methods
-          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllThreeVsThree: {} })
+          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllThreeVsThree: {} }, null, null, null)
           .accounts({
             gameServer: gameServer.publicKey,
           })
@@ -497,7 +497,7 @@ describe("Edge Cases Security Tests", () => {
         
         try {
           await program.methods
-            .createGameSession(sessionId, new anchor.BN(0), { winnerTakesAllOneVsOne: {} })
+            .createGameSession(sessionId, new anchor.BN(0), { winnerTakesAllOneVsOne: {} }, null, null, null)
             .accounts({
               gameServer: gameServer.publicKey,
             })
@@ -515,7 +515,7 @@ describe("Edge Cases Security Tests", () => {
         
         try {
           await program.methods
-            .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT - 1), { winnerTakesAllOneVsOne: {} })
+            .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT - 1), { winnerTakesAllOneVsOne: {} }, null, null, null)
             .accounts({
               gameServer: gameServer.publicKey,
             })
@@ -533,7 +533,7 @@ describe("Edge Cases Security Tests", () => {
         
         try {
           await program.methods
-            .createGameSession(sessionId, new anchor.BN(MAX_BET_AMOUNT + 1), { winnerTakesAllOneVsOne: {} })
+            .createGameSession(sessionId, new anchor.BN(MAX_BET_AMOUNT + 1), { winnerTakesAllOneVsOne: {} }, null, null, null)
             .accounts({
               gameServer: gameServer.publicKey,
             })
@@ -552,7 +552,7 @@ describe("Edge Cases Security Tests", () => {
         const sessionId = "large_kills_test";
         
         await program.methods
-          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { payToSpawnOneVsOne: {} })
+          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { payToSpawnOneVsOne: {} }, null, null, null)
           .accounts({
             gameServer: gameServer.publicKey,
           })
@@ -569,7 +569,7 @@ describe("Edge Cases Security Tests", () => {
         const sessionId = "spawn_overflow_test";
         
         await program.methods
-          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { payToSpawnOneVsOne: {} })
+          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { payToSpawnOneVsOne: {} }, null, null, null)
           .accounts({
             gameServer: gameServer.publicKey,
           })
@@ -626,7 +626,7 @@ describe("Edge Cases Security Tests", () => {
         
         // Create session (this would normally set expiration time)
         await program.methods
-          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} })
+          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} }, null, null, null)
           .accounts({
             gameServer: gameServer.publicKey,
           })
@@ -649,7 +649,7 @@ describe("Edge Cases Security Tests", () => {
         const sessionId = "extend_test";
         
         await program.methods
-          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} })
+          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} }, null, null, null)
           .accounts({
             gameServer: gameServer.publicKey,
           })
@@ -670,6 +670,50 @@ describe("Edge Cases Security Tests", () => {
           console.log("Extension not implemented:", error.message);
         }
       });
+
+      it("Should reject extensions past the hard deadline", async () => {
+        const sessionId = "extend_deadline_test";
+
+        await program.methods
+          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} }, null, null, null)
+          .accounts({
+            gameServer: gameServer.publicKey,
+          })
+          .signers([gameServer])
+          .rpc();
+
+        // Six full days fit inside the seven-day lifetime
+        for (let day = 0; day < 6; day++) {
+          await program.methods
+            .extendSession(sessionId, new anchor.BN(86400))
+            .accounts({
+              authority: gameServer.publicKey,
+            })
+            .signers([gameServer])
+            .rpc();
+        }
+
+        try {
+          await program.methods
+            .extendSession(sessionId, new anchor.BN(86400))
+            .accounts({
+              authority: gameServer.publicKey,
+            })
+            .signers([gameServer])
+            .rpc();
+          assert.fail("Extension past the hard deadline should be rejected");
+        } catch (error) {
+          expect(error.toString()).to.include("InvalidExtensionTime");
+        }
+
+        const gameSession = await program.account.gameSession.fetch(
+          PublicKey.findProgramAddressSync(
+            [Buffer.from("game_session"), Buffer.from(sessionId)],
+            program.programId
+          )[0]
+        );
+        expect(gameSession.expiresAt.toNumber()).to.be.at.most(gameSession.hardDeadline.toNumber());
+      });
     });
 
     describe("Input Validation Edge Cases", () => {
@@ -677,7 +721,7 @@ describe("Edge Cases Security Tests", () => {
         // Too short
         try {
           await program.methods
-            .createGameSession("x", new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} })
+            .createGameSession("x", new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} }, null, null, null)
             .accounts({
               gameServer: gameServer.publicKey,
             })
@@ -693,7 +737,7 @@ describe("Edge Cases Security Tests", () => {
         const longId = "a".repeat(MAX_SESSION_ID_LENGTH + 1);
         try {
           await program.methods
-            .createGameSession(longId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} })
+            .createGameSession(longId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} }, null, null, null)
             .accounts({
               gameServer: gameServer.publicKey,
             })
@@ -710,7 +754,7 @@ describe("Edge Cases Security Tests", () => {
         const sessionId = "team_validation_test";
         
         await program.methods
-          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} })
+          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} }, null, null, null)
           .accounts({
             gameServer: gameServer.publicKey,
           })
@@ -740,7 +784,7 @@ describe("Edge Cases Security Tests", () => {
         const sessionId = "state_test";
         
         await program.methods
-          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { payToSpawnOneVsOne: {} })
+          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { payToSpawnOneVsOne: {} }, null, null, null)
           .accounts({
             gameServer: gameServer.publicKey,
           })
@@ -769,7 +813,7 @@ describe("Edge Cases Security Tests", () => {
         
         // Create winner-takes-all game
         await program.methods
-          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} })
+          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} }, null, null, null)
           .accounts({
             gameServer: gameServer.publicKey,
           })
@@ -819,7 +863,7 @@ describe("Edge Cases Security Tests", () => {
       const sessionId = "max_team_test";
       
       await program.methods
-        .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllFiveVsFive: {} })
+        .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllFiveVsFive: {} }, null, null, null)
         .accounts({
           gameServer: gameServer.publicKey,
         })
@@ -873,7 +917,7 @@ describe("Edge Cases Security Tests", () => {
       const sessionId = "rapid_test";
       
       await program.methods
-        .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { payToSpawnThreeVsThree: {} })
+        .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { payToSpawnThreeVsThree: {} }, null, null, null)
         .accounts({
           gameServer: gameServer.publicKey,
         })
@@ -933,7 +977,7 @@ describe("Configuration and Boundary Tests", () => {
       // This should fail at account validation level
       try {
         await program.methods
-          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} })
+          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} }, null, null, null)
           .accounts({
             gameServer: gameServer.publicKey,
             mint: wrongMint, // Wrong mint
@@ -951,7 +995,7 @@ describe("Configuration and Boundary Tests", () => {
       const sessionId = "ownership_test";
       
       await program.methods
-        .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} })
+        .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} }, null, null, null)
         .accounts({
           gameServer: gameServer.publicKey,
         })
@@ -990,7 +1034,7 @@ describe("Configuration and Boundary Tests", () => {
       const wrongSessionId = "wrong_session";
       
       await program.methods
-        .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} })
+        .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} }, null, null, null)
         .accounts({
           gameServer: gameServer.publicKey,
         })
@@ -1025,7 +1069,7 @@ describe("Configuration and Boundary Tests", () => {
       const sessionId = "vault_pda_test";
       
       await program.methods
-        .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} })
+        .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} }, null, null, null)
         .accounts({
           gameServer: gameServer.publicKey,
         })
@@ -1068,7 +1112,7 @@ describe("Configuration and Boundary Tests", () => {
       
       const promises = sessionIds.map(sessionId =>
         program.methods
-          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} })
+          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} }, null, null, null)
           .accounts({
             gameServer: gameServer.publicKey,
           })
@@ -1101,7 +1145,7 @@ describe("Configuration and Boundary Tests", () => {
       
       // Create first session
       await program.methods
-        .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} })
+        .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} }, null, null, null)
         .accounts({
           gameServer: gameServer.publicKey,
         })
@@ -1111,7 +1155,7 @@ describe("Configuration and Boundary Tests", () => {
       // Try to create duplicate session
       try {
         await program.methods
-          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} })
+          .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} }, null, null, null)
           .accounts({
             gameServer: gameServer.publicKey,
           })
@@ -1131,7 +1175,7 @@ describe("Configuration and Boundary Tests", () => {
       const maxLengthSessionId = "a".repeat(MAX_SESSION_ID_LENGTH);
       
       await program.methods
-        .createGameSession(maxLengthSessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} })
+        .createGameSession(maxLengthSessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} }, null, null, null)
         .accounts({
           gameServer: gameServer.publicKey,
         })
@@ -1152,7 +1196,7 @@ describe("Configuration and Boundary Tests", () => {
       const minSessionId = "abc"; // MIN_SESSION_ID_LENGTH = 3
       
       await program.methods
-        .createGameSession(minSessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} })
+        .createGameSession(minSessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} }, null, null, null)
         .accounts({
           gameServer: gameServer.publicKey,
         })
@@ -1176,7 +1220,7 @@ describe("Configuration and Boundary Tests", () => {
       const sessionId = "recovery_test";
       
       await program.methods
-        .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} })
+        .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} }, null, null, null)
         .accounts({
           gameServer: gameServer.publicKey,
         })
@@ -1237,7 +1281,7 @@ describe("Configuration and Boundary Tests", () => {
       const sessionId = "consistency_test";
       
       await program.methods
-        .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllThreeVsThree: {} })
+        .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllThreeVsThree: {} }, null, null, null)
         .accounts({
           gameServer: gameServer.publicKey,
         })
@@ -1284,7 +1328,7 @@ describe("Configuration and Boundary Tests", () => {
       
       // Legitimate server creates session
       await program.methods
-        .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} })
+        .createGameSession(sessionId, new anchor.BN(MIN_BET_AMOUNT), { winnerTakesAllOneVsOne: {} }, null, null, null)
         .accounts({
           gameServer: gameServer.publicKey,
         })
@@ -1322,7 +1366,7 @@ describe("Configuration and Boundary Tests", () => {
         
         try {
           await program.methods
-            .createGameSession(sessionId, new anchor.BN(testCase.bet), { winnerTakesAllOneVsOne: {} })
+            .createGameSession(sessionId, new anchor.BN(testCase.bet), { winnerTakesAllOneVsOne: {} }, null, null, null)
             .accounts({
               gameServer: gameServer.publicKey,
             })
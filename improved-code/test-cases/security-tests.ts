@@ -52,7 +52,7 @@ describe("Security Vulnerability Tests", () => {
 
       // Create game session
       await program.methods
-        .createGameSession(sessionId, betAmount, { winnerTakesAllOneVsOne: {} })
+        .createGameSession(sessionId, betAmount, { winnerTakesAllOneVsOne: {} }, null, null, null)
         .accounts({
           gameServer: gameServer.publicKey,
         })
@@ -83,7 +83,7 @@ describe("Security Vulnerability Tests", () => {
       // Kill user2 10 times to exhaust spawns
       for (let i = 0; i < 10; i++) {
         await program.methods
-          .recordKill(sessionId, 0, user1.publicKey, 1, user2.publicKey)
+          .recordKill(sessionId, 0, user1.publicKey, 1, user2.publicKey, null)
           .accounts({
             gameServer: gameServer.publicKey,
           })
@@ -94,7 +94,7 @@ describe("Security Vulnerability Tests", () => {
       // Try to kill user2 when they have 0 spawns - should fail
       try {
         await program.methods
-          .recordKill(sessionId, 0, user1.publicKey, 1, user2.publicKey)
+          .recordKill(sessionId, 0, user1.publicKey, 1, user2.publicKey, null)
           .accounts({
             gameServer: gameServer.publicKey,
           })
@@ -117,7 +117,7 @@ describe("Security Vulnerability Tests", () => {
 
       // Create game session
       await program.methods
-        .createGameSession(sessionId, betAmount, { winnerTakesAllOneVsOne: {} })
+        .createGameSession(sessionId, betAmount, { winnerTakesAllOneVsOne: {} }, null, null, null)
         .accounts({
           gameServer: gameServer.publicKey,
         })
@@ -163,7 +163,7 @@ describe("Security Vulnerability Tests", () => {
 
       // Create pay-to-spawn game
       await program.methods
-        .createGameSession(sessionId, betAmount, { payToSpawnOneVsOne: {} })
+        .createGameSession(sessionId, betAmount, { payToSpawnOneVsOne: {} }, null, null, null)
         .accounts({
           gameServer: gameServer.publicKey,
         })
@@ -209,7 +209,7 @@ describe("Security Vulnerability Tests", () => {
 
       try {
         await program.methods
-          .createGameSession(sessionId, betAmount, { winnerTakesAllOneVsOne: {} })
+          .createGameSession(sessionId, betAmount, { winnerTakesAllOneVsOne: {} }, null, null, null)
           .accounts({
             gameServer: gameServer.publicKey,
           })
@@ -242,7 +242,7 @@ describe("Security Vulnerability Tests", () => {
 
       // Create 1v1 game
       await program.methods
-        .createGameSession(sessionId, betAmount, { winnerTakesAllOneVsOne: {} })
+        .createGameSession(sessionId, betAmount, { winnerTakesAllOneVsOne: {} }, null, null, null)
         .accounts({
           gameServer: gameServer.publicKey,
         })
@@ -288,7 +288,7 @@ describe("Security Vulnerability Tests", () => {
 
       try {
         await program.methods
-          .createGameSession(sessionId, maxBet, { winnerTakesAllOneVsOne: {} })
+          .createGameSession(sessionId, maxBet, { winnerTakesAllOneVsOne: {} }, null, null, null)
           .accounts({
             gameServer: gameServer.publicKey,
           })